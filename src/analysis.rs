@@ -0,0 +1,405 @@
+use rustfft::num_complex::Complex;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::poly;
+use crate::taps;
+
+// Ниже этого модуля знаменатель считается нулём (ноль на единичной окружности)
+const SINGULAR: f64 = 1e-12;
+
+/// Transfer function b(z)/a(z) with coefficients of z^0, z^-1, ...
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferFunction {
+    pub b: Vec<f64>,
+    pub a: Vec<f64>,
+}
+
+impl TransferFunction {
+    pub fn new(b: Vec<f64>, a: Vec<f64>) -> Result<TransferFunction, String> {
+        if b.is_empty() || a.is_empty() || a[0] == 0.0 {
+            return Err("Coefficients must be non-empty and a[0] must be nonzero".to_string());
+        }
+        Ok(TransferFunction { b, a })
+    }
+
+    pub fn fir(taps: Vec<f64>) -> Result<TransferFunction, String> {
+        TransferFunction::new(taps, vec![1.0])
+    }
+
+    /// Kernel of `dsp::move_average` with the given length
+    pub fn move_average(kernel_length: usize) -> Result<TransferFunction, String> {
+        if kernel_length == 0 {
+            return Err("Length of window must be positive".to_string());
+        }
+        TransferFunction::fir(vec![1.0 / kernel_length as f64; kernel_length])
+    }
+}
+
+/// Loads the filter from one of the sources: FIR taps, b/a files,
+/// sections saved by `filter iir` or length of moving average
+pub fn load_filter(
+    taps_file: Option<&str>,
+    numerator: Option<&str>,
+    denominator: Option<&str>,
+    sos: Option<&str>,
+    mov_average: Option<usize>,
+) -> Result<Vec<TransferFunction>, String> {
+    match (taps_file, numerator, sos, mov_average) {
+        (Some(taps_file), _, _, _) => Ok(vec![TransferFunction::fir(taps::read_taps(taps_file)?)?]),
+        (_, Some(numerator), _, _) => {
+            let a = match denominator {
+                Some(denominator) => taps::read_taps(denominator)?,
+                None => vec![1.0],
+            };
+            Ok(vec![TransferFunction::new(taps::read_taps(numerator)?, a)?])
+        }
+        (_, _, Some(sos), _) => Ok(taps::read_sos(sos)?
+            .iter()
+            .map(|(b, a)| TransferFunction::new(b.to_vec(), a.to_vec()))
+            .collect::<Result<Vec<_>, String>>()?),
+        (_, _, _, Some(kernel_length)) => Ok(vec![TransferFunction::move_average(kernel_length)?]),
+        _ => Err("No filter is given".to_string()),
+    }
+}
+
+// Значения sum c_k z^-k и sum k c_k z^-k для z = exp(jw)
+fn eval(coeffs: &[f64], w: f64) -> (Complex<f64>, Complex<f64>) {
+    let mut value = Complex::new(0.0, 0.0);
+    let mut weighted = Complex::new(0.0, 0.0);
+    for (k, c) in coeffs.iter().enumerate() {
+        let term = Complex::from_polar(*c, -w * k as f64);
+        value += term;
+        weighted += term * k as f64;
+    }
+    (value, weighted)
+}
+
+fn to_omega(freq: f64, fs: f64) -> f64 {
+    2.0 * PI * freq / fs
+}
+
+/// Complex frequency response of the cascade at frequencies in Hz
+pub fn freqz(filter: &[TransferFunction], freqs: &[f64], fs: f64) -> Vec<Complex<f64>> {
+    freqs
+        .iter()
+        .map(|&f| {
+            let w = to_omega(f, fs);
+            filter.iter().fold(Complex::new(1.0, 0.0), |acc, tf| {
+                acc * eval(&tf.b, w).0 / eval(&tf.a, w).0
+            })
+        })
+        .collect()
+}
+
+/// Group delay in samples, zero where the response has a zero on the unit circle
+pub fn group_delay(filter: &[TransferFunction], freqs: &[f64], fs: f64) -> Vec<f64> {
+    // Для полинома от z^-1 задержка равна Re(sum k c_k z^-k / sum c_k z^-k)
+    let delay = |coeffs: &[f64], w: f64| {
+        let (value, weighted) = eval(coeffs, w);
+        if value.norm() < SINGULAR {
+            0.0
+        } else {
+            (weighted / value).re
+        }
+    };
+    freqs
+        .iter()
+        .map(|&f| {
+            let w = to_omega(f, fs);
+            filter
+                .iter()
+                .map(|tf| delay(&tf.b, w) - delay(&tf.a, w))
+                .sum()
+        })
+        .collect()
+}
+
+/// Phase of the response unwrapped along the frequency grid
+pub fn unwrapped_phase(response: &[Complex<f64>]) -> Vec<f64> {
+    let mut phase: Vec<f64> = Vec::with_capacity(response.len());
+    let mut offset = 0.0;
+    let mut prev: Option<f64> = None;
+    for h in response {
+        let arg = h.arg();
+        if let Some(p) = prev {
+            let jump = arg + offset - p;
+            offset -= 2.0 * PI * (jump / (2.0 * PI)).round();
+        }
+        let value = arg + offset;
+        phase.push(value);
+        prev = Some(value);
+    }
+    phase
+}
+
+/// Phase delay in samples, at zero frequency the group delay is used
+pub fn phase_delay(filter: &[TransferFunction], freqs: &[f64], fs: f64) -> Vec<f64> {
+    let phase = unwrapped_phase(&freqz(filter, freqs, fs));
+    let delay = group_delay(filter, freqs, fs);
+    freqs
+        .iter()
+        .enumerate()
+        .map(|(i, &f)| {
+            let w = to_omega(f, fs);
+            if w.abs() < SINGULAR {
+                delay[i]
+            } else {
+                -phase[i] / w
+            }
+        })
+        .collect()
+}
+
+/// Zeros and poles of the cascade in the z-plane
+pub fn zeros_poles(filter: &[TransferFunction]) -> (Vec<Complex<f64>>, Vec<Complex<f64>>) {
+    let mut zeros = Vec::new();
+    let mut poles = Vec::new();
+    for tf in filter {
+        // Дополняем до одной длины, чтобы учесть нули и полюса в начале координат
+        let n = tf.b.len().max(tf.a.len());
+        let mut b = tf.b.clone();
+        let mut a = tf.a.clone();
+        b.resize(n, 0.0);
+        a.resize(n, 0.0);
+
+        // Нулевые первые коэффициенты числителя означают нули в бесконечности
+        zeros.extend(poly::roots(&b));
+        poles.extend(poly::roots(&a));
+    }
+    (zeros, poles)
+}
+
+/// All poles strictly inside the unit circle
+pub fn is_stable(poles: &[Complex<f64>]) -> bool {
+    poles.iter().all(|p| p.norm() < 1.0 - 1e-12)
+}
+
+/// Frequencies from 0 to fs/2 inclusive
+pub fn frequency_grid(points: usize, fs: f64) -> Vec<f64> {
+    if points < 2 {
+        return vec![0.0; points];
+    }
+    (0..points)
+        .map(|i| i as f64 * fs / 2.0 / (points - 1) as f64)
+        .collect()
+}
+
+/// Magnitude in dB, zeros of response are limited to -300 dB
+pub fn magnitude_db(response: &[Complex<f64>]) -> Vec<f64> {
+    response
+        .iter()
+        .map(|h| 20.0 * h.norm().max(1e-15).log10())
+        .collect()
+}
+
+/// Saves response as CSV with header
+/// `freq_hz,magnitude_db,phase_rad,group_delay,phase_delay`, delays in samples
+pub fn save_response(
+    filter: &[TransferFunction],
+    freqs: &[f64],
+    fs: f64,
+    filename: &str,
+) -> std::io::Result<()> {
+    let response = freqz(filter, freqs, fs);
+    let magnitude = magnitude_db(&response);
+    let phase = unwrapped_phase(&response);
+    let gd = group_delay(filter, freqs, fs);
+    let pd = phase_delay(filter, freqs, fs);
+
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(
+        writer,
+        "freq_hz,magnitude_db,phase_rad,group_delay,phase_delay"
+    )?;
+    for i in 0..freqs.len() {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            freqs[i], magnitude[i], phase[i], gd[i], pd[i]
+        )?;
+    }
+    writer.flush()
+}
+
+/// Saves zeros and poles as CSV with header `kind,re,im`
+pub fn save_zeros_poles(
+    zeros: &[Complex<f64>],
+    poles: &[Complex<f64>],
+    filename: &str,
+) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(writer, "kind,re,im")?;
+    for z in zeros {
+        writeln!(writer, "zero,{},{}", z.re, z.im)?;
+    }
+    for p in poles {
+        writeln!(writer, "pole,{},{}", p.re, p.im)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::{Biquad, BiquadType};
+    use crate::fir::{self, BandType, Window};
+    use crate::iir::{self, IirFamily};
+
+    const FS: f64 = 10000.0;
+
+    fn from_biquad(section: &Biquad) -> TransferFunction {
+        let (b, a) = section.coefficients();
+        TransferFunction::new(b.to_vec(), a.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_freqz_move_average() {
+        let filter = [TransferFunction::move_average(4).unwrap()];
+        let response = freqz(&filter, &[0.0, 2500.0, 1250.0], FS);
+        assert!((response[0] - Complex::new(1.0, 0.0)).norm() < 1e-12);
+        // Окно длины 4 обнуляет fs/4
+        assert!(response[1].norm() < 1e-12);
+        // |H| = |sin(2w)| / (4 |sin(w/2)|) при w = pi/4
+        let expected = 1.0 / (4.0 * (PI / 8.0).sin());
+        assert!((response[2].norm() - expected).abs() < 1e-12);
+
+        assert!(TransferFunction::move_average(0).is_err());
+    }
+
+    #[test]
+    fn test_linear_phase_fir_delays() {
+        let taps = fir::firwin(31, &[1000.0], BandType::Lowpass, Window::Hamming, FS).unwrap();
+        let filter = [TransferFunction::fir(taps).unwrap()];
+        let freqs = frequency_grid(50, FS);
+        let passband: Vec<f64> = freqs.iter().cloned().filter(|&f| f < 800.0).collect();
+
+        for d in group_delay(&filter, &passband, FS) {
+            assert!((d - 15.0).abs() < 1e-9);
+        }
+        for d in phase_delay(&filter, &passband, FS) {
+            assert!((d - 15.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_group_delay_of_pure_delay_and_biquad() {
+        let delay = [TransferFunction::fir(vec![0.0, 0.0, 0.0, 1.0]).unwrap()];
+        for d in group_delay(&delay, &[0.0, 1000.0, 4000.0], FS) {
+            assert!((d - 3.0).abs() < 1e-12);
+        }
+
+        // Численная производная фазы совпадает с аналитической задержкой
+        let section = Biquad::design(BiquadType::Peaking, 1000.0, 2.0, 6.0, FS).unwrap();
+        let filter = [from_biquad(&section)];
+        let step = 0.01;
+        let phase = unwrapped_phase(&freqz(&filter, &[900.0 - step, 900.0 + step], FS));
+        let numeric = -(phase[1] - phase[0]) / (to_omega(2.0 * step, FS));
+        let analytic = group_delay(&filter, &[900.0], FS)[0];
+        assert!((numeric - analytic).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unwrapped_phase() {
+        let response: Vec<Complex<f64>> = (0..20)
+            .map(|i| Complex::from_polar(1.0, -0.5 * i as f64))
+            .collect();
+        let phase = unwrapped_phase(&response);
+        for (i, p) in phase.iter().enumerate() {
+            assert!((p + 0.5 * i as f64).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_zeros_poles_and_stability() {
+        // y[n] = x[n] - x[n-1] + 0.5 y[n-1]: ноль в 1, полюс в 0.5
+        let filter = [TransferFunction::new(vec![1.0, -1.0], vec![1.0, -0.5]).unwrap()];
+        let (zeros, poles) = zeros_poles(&filter);
+        assert_eq!(zeros, vec![Complex::new(1.0, 0.0)]);
+        assert_eq!(poles, vec![Complex::new(0.5, 0.0)]);
+        assert!(is_stable(&poles));
+
+        let unstable = [TransferFunction::new(vec![1.0], vec![1.0, -1.5]).unwrap()];
+        assert!(!is_stable(&zeros_poles(&unstable).1));
+
+        // Задержка: все полюса в нуле
+        let delay = [TransferFunction::fir(vec![0.0, 0.0, 1.0]).unwrap()];
+        let (zeros, poles) = zeros_poles(&delay);
+        assert!(zeros.is_empty());
+        assert_eq!(poles.len(), 2);
+        assert!(poles.iter().all(|p| p.norm() == 0.0));
+    }
+
+    #[test]
+    fn test_sos_analysis() {
+        let sections = iir::iirfilter(
+            6,
+            &[1000.0],
+            BandType::Lowpass,
+            IirFamily::Butterworth,
+            0.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        let filter: Vec<TransferFunction> = sections.iter().map(from_biquad).collect();
+
+        let response = freqz(&filter, &[0.0, 1000.0], FS);
+        assert!((response[0].norm() - 1.0).abs() < 1e-9);
+        assert!((response[1].norm() - 0.5_f64.sqrt()).abs() < 1e-9);
+
+        let (zeros, poles) = zeros_poles(&filter);
+        assert_eq!(zeros.len(), 6);
+        assert_eq!(poles.len(), 6);
+        assert!(
+            zeros
+                .iter()
+                .all(|z| (z - Complex::new(-1.0, 0.0)).norm() < 1e-3)
+        );
+        assert!(is_stable(&poles));
+    }
+
+    #[test]
+    fn test_frequency_grid() {
+        assert_eq!(
+            frequency_grid(5, FS),
+            vec![0.0, 1250.0, 2500.0, 3750.0, 5000.0]
+        );
+        assert_eq!(frequency_grid(1, FS), vec![0.0]);
+    }
+
+    #[test]
+    fn test_load_filter() {
+        let filter = load_filter(None, None, None, None, Some(3)).unwrap();
+        assert_eq!(filter[0].b.len(), 3);
+        assert_eq!(filter[0].a, vec![1.0]);
+
+        assert!(load_filter(None, None, None, None, None).is_err());
+        assert!(load_filter(Some("missing_taps.csv"), None, None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_save_response_and_zeros_poles() {
+        let filter = [TransferFunction::new(vec![1.0, -1.0], vec![1.0, -0.5]).unwrap()];
+
+        let fname = "test_response.csv";
+        save_response(&filter, &frequency_grid(3, FS), FS, fname).unwrap();
+        let text = std::fs::read_to_string(fname).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            "freq_hz,magnitude_db,phase_rad,group_delay,phase_delay"
+        );
+        assert!(lines[1].starts_with("0,-300,"));
+        std::fs::remove_file(fname).ok();
+
+        let fname = "test_zeros_poles.csv";
+        let (zeros, poles) = zeros_poles(&filter);
+        save_zeros_poles(&zeros, &poles, fname).unwrap();
+        let text = std::fs::read_to_string(fname).unwrap();
+        assert_eq!(text, "kind,re,im\nzero,1,0\npole,0.5,0\n");
+        std::fs::remove_file(fname).ok();
+    }
+}
@@ -1,4 +1,4 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{ArgGroup, Args, Parser, Subcommand};
 
 // Делаем парсер командной строки
 // структура такая: [operation] [operation args]
@@ -24,6 +24,20 @@ pub enum Commands {
         signal1: String,
         #[arg(short('2'), long, help = "second signal")]
         signal2: String,
+        #[arg(
+            short,
+            long,
+            default_value = "none",
+            help = "align second signal to first: none, integer, fractional"
+        )]
+        align: String,
+        #[arg(
+            short,
+            long,
+            default_value = "shortest",
+            help = "length of output: shortest, longest, first"
+        )]
+        length: String,
         #[arg(
             short,
             long,
@@ -38,6 +52,20 @@ pub enum Commands {
         signal1: String,
         #[arg(short('2'), long, help = "second signal")]
         signal2: String,
+        #[arg(
+            short,
+            long,
+            default_value = "none",
+            help = "align second signal to first: none, integer, fractional"
+        )]
+        align: String,
+        #[arg(
+            short,
+            long,
+            default_value = "shortest",
+            help = "length of output: shortest, longest, first"
+        )]
+        length: String,
         #[arg(
             short,
             long,
@@ -52,6 +80,20 @@ pub enum Commands {
         signal1: String,
         #[arg(short('2'), long, help = "second signal")]
         signal2: String,
+        #[arg(
+            short,
+            long,
+            default_value = "none",
+            help = "align second signal to first: none, integer, fractional"
+        )]
+        align: String,
+        #[arg(
+            short,
+            long,
+            default_value = "shortest",
+            help = "length of output: shortest, longest, first"
+        )]
+        length: String,
         #[arg(
             short,
             long,
@@ -74,6 +116,62 @@ pub enum Commands {
         )]
         out_signal: String,
     },
+    /// Dynamic range processing: compressor, limiter, expander, gate, AGC
+    Dyn(DynArgs),
+    /// Normalize signal to peak, true peak, RMS or integrated loudness
+    Normalize {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            default_value = "lufs",
+            help = "level: peak, true-peak, rms, lufs"
+        )]
+        mode: String,
+        #[arg(
+            short,
+            long,
+            default_value_t = -23.,
+            allow_negative_numbers = true,
+            help = "target level in dBFS, dBTP or LUFS"
+        )]
+        target: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "normalized_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Loudness meter of EBU R128 and ITU-R BS.1770
+    Loudness {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            help = "check loudness against target in LUFS, e.g. -23, exit 1 on failure"
+        )]
+        target: Option<f64>,
+        #[arg(
+            long,
+            default_value_t = 0.5,
+            help = "permitted deviation from target in LU"
+        )]
+        tolerance: f64,
+        #[arg(
+            long,
+            default_value_t = -1.,
+            allow_negative_numbers = true,
+            help = "maximum permitted true peak in dBTP"
+        )]
+        max_true_peak: f64,
+        #[arg(short, long, help = "save momentary and short-term loudness to csv")]
+        out_csv: Option<String>,
+    },
     /// Moving average
     MovAverage {
         #[arg(short, long, help = "signal")]
@@ -93,60 +191,1433 @@ pub enum Commands {
         )]
         out_signal: String,
     },
-}
-
-#[derive(Debug, Args)]
-#[command(args_conflicts_with_subcommands = true)]
-#[command(flatten_help = true)]
-pub struct GenArgs {
-    #[command(subcommand)]
-    pub command: Option<GenCommands>,
-}
-
-#[derive(Debug, Subcommand)]
-pub enum GenCommands {
-    Sine {
-        #[arg(short, long, default_value_t = 50., help = "frequency in Hz")]
-        freq: f64,
-        #[arg(short, long, default_value_t = 0., help = "phase in degrees")]
-        phase: f64,
-        #[arg(short, long, default_value_t = 1., help = "duration in seconds")]
-        duration: f64,
-        #[arg(short, long, default_value_t = 1., help = "amplitude of sinus")]
-        amplitude: f64,
-        #[arg(short, long, default_value = "", help = "filename")]
-        out_filename: String,
+    /// Smooth signal by running median, Savitzky-Golay, exponential or Gaussian filter
+    Smooth(SmoothArgs),
+    /// Filter signal
+    Filter(FilterArgs),
+    /// Parametric equalizer from TOML, JSON or REW/AutoEQ preset
+    Eq {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            help = "preset: .toml, .json or REW/AutoEQ text with \"Filter 1: ON PK Fc 100 Hz Gain -3 dB Q 1.41\" lines"
+        )]
+        preset: String,
+        #[arg(
+            short,
+            long,
+            default_value = "eq_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
     },
-
-    Noise {
-        #[arg(short, long, default_value_t = 1., help = "duration")]
-        duration: f64,
-        #[arg(short, long, default_value_t = 1., help = "standart deviation")]
-        std: f64,
-        #[arg(short, long, default_value_t = 1., help = "mean of noise")]
-        mu: f64,
-        #[arg(short, long, default_value = "", help = "filename")]
-        out_filename: String,
+    /// Split signal into bands by Linkwitz-Riley or linear-phase FIR crossover
+    Crossover {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            required = true,
+            value_delimiter = ',',
+            help = "increasing crossover frequencies in Hz"
+        )]
+        freqs: Vec<f64>,
+        #[arg(
+            short('t'),
+            long,
+            default_value = "lr",
+            help = "crossover type: lr, fir"
+        )]
+        crossover_type: String,
+        #[arg(
+            short('n'),
+            long,
+            default_value_t = 4,
+            help = "order of Linkwitz-Riley filters: 2, 4, 8"
+        )]
+        order: usize,
+        #[arg(short, long, default_value_t = 255, help = "odd length of FIR filters")]
+        length: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "band",
+            help = "prefix of output files <prefix>_N.wav"
+        )]
+        out_prefix: String,
     },
-
-    Sweep {
-        #[arg(long, default_value_t = 1., help = "start frequency")]
-        f0: f64,
-        #[arg(long, default_value_t = 1., help = "frequency at t1")]
-        f1: f64,
-        #[arg(long, default_value_t = 1., help = "time for stop sweep")]
-        t1: f64,
+    /// Design filter and export its taps
+    Design(DesignArgs),
+    /// Draw waveform or spectrum in terminal
+    View {
+        #[arg(short, long, help = "signal")]
+        signal: String,
         #[arg(
             short,
             long,
-            default_value = "linear",
-            help = "type of sweep: linear, quadratic, logarithmic, hyperbolic"
+            default_value = "wave",
+            help = "what to draw: wave, spectrum"
+        )]
+        mode: String,
+        #[arg(long, default_value_t = 0., help = "start of time range in seconds")]
+        start: f64,
+        #[arg(long, help = "end of time range in seconds [default: end of signal]")]
+        end: Option<f64>,
+        #[arg(short, long, default_value_t = 80, help = "width in characters")]
+        width: usize,
+        #[arg(long, default_value_t = 20, help = "height in characters")]
+        height: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "braille",
+            help = "characters for drawing: braille, block, ascii"
+        )]
+        charset: String,
+    },
+    /// Convolution of signal with kernel, e.g. room impulse response
+    Convolve {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, help = "kernel: .wav file or taps in .csv, .json")]
+        kernel: String,
+        #[arg(
+            short,
+            long,
+            default_value = "full",
+            help = "output mode: full, same, valid"
+        )]
+        mode: String,
+        #[arg(
+            long,
+            default_value = "auto",
+            help = "method: auto, direct, overlap-add, overlap-save"
         )]
         method: String,
-        #[arg(short, long, help = "only for quadratic, vertex of the parabola")]
-        vertex_zero: bool,
-        #[arg(short, long, default_value = "", help = "filename")]
-        out_filename: String,
+        #[arg(
+            short,
+            long,
+            default_value = "convolved_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Frequency response, delays, zeros and poles of filter
+    #[command(group(ArgGroup::new("coefficients").required(true).args(["taps", "numerator", "sos", "mov_average"])))]
+    AnalyzeFilter {
+        #[arg(long, help = "file with FIR taps, csv or json")]
+        taps: Option<String>,
+        #[arg(short('b'), long, help = "file with numerator b of transfer function")]
+        numerator: Option<String>,
+        #[arg(
+            short('a'),
+            long,
+            requires = "numerator",
+            help = "file with denominator a of transfer function [default: 1]"
+        )]
+        denominator: Option<String>,
+        #[arg(long, help = "file with second-order sections saved by filter iir")]
+        sos: Option<String>,
+        #[arg(short, long, help = "length of window of moving average")]
+        mov_average: Option<usize>,
+        #[arg(
+            short('n'),
+            long,
+            default_value_t = 512,
+            help = "number of frequencies from 0 to fs/2"
+        )]
+        points: usize,
+        #[arg(long, default_value_t = 10000., help = "sample rate in Hz")]
+        fs: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "filter_response.csv",
+            help = "fname of csv with frequency, magnitude, phase, group and phase delay"
+        )]
+        out_csv: String,
+        #[arg(long, help = "save zeros and poles to csv")]
+        pz_out: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = 80,
+            help = "width of plots in characters"
+        )]
+        width: usize,
+        #[arg(long, default_value_t = 12, help = "height of plots in characters")]
+        height: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "braille",
+            help = "characters for drawing: braille, block, ascii"
+        )]
+        charset: String,
+    },
+    /// Sample rate conversion
+    Resample {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, help = "new sample rate in Hz")]
+        to: u32,
+        #[arg(
+            short,
+            long,
+            default_value = "medium",
+            help = "quality of anti-aliasing filter: low, medium, high"
+        )]
+        quality: String,
+        #[arg(
+            short,
+            long,
+            default_value = "auto",
+            help = "method: auto, polyphase, arbitrary"
+        )]
+        method: String,
+        #[arg(
+            short,
+            long,
+            default_value = "resampled_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Change duration keeping pitch by phase vocoder or WSOLA
+    Stretch {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, help = "ratio of output duration to input duration")]
+        factor: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "vocoder",
+            help = "method: vocoder, wsola"
+        )]
+        method: String,
+        #[arg(
+            short('n'),
+            long,
+            default_value_t = 1024,
+            help = "frame length in samples"
+        )]
+        frame: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "stretched_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Shift pitch keeping duration by phase vocoder or WSOLA
+    Pitch {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('t'),
+            long,
+            allow_negative_numbers = true,
+            help = "pitch shift in semitones"
+        )]
+        semitones: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "vocoder",
+            help = "method: vocoder, wsola"
+        )]
+        method: String,
+        #[arg(
+            short('n'),
+            long,
+            default_value_t = 1024,
+            help = "frame length in samples"
+        )]
+        frame: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "pitched_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Lower sample rate by integer factor
+    Decimate {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, help = "decimation factor")]
+        factor: usize,
+        #[arg(long, default_value = "fir", help = "filter: fir, iir")]
+        filter: String,
+        #[arg(short, long, help = "compensate phase of filter")]
+        zero_phase: bool,
+        #[arg(
+            short,
+            long,
+            default_value = "medium",
+            help = "quality of fir filter: low, medium, high"
+        )]
+        quality: String,
+        #[arg(
+            short,
+            long,
+            default_value = "decimated_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Raise sample rate by integer factor
+    Interpolate {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, help = "interpolation factor")]
+        factor: usize,
+        #[arg(long, default_value = "fir", help = "filter: fir, iir")]
+        filter: String,
+        #[arg(short, long, help = "compensate phase of filter")]
+        zero_phase: bool,
+        #[arg(
+            short,
+            long,
+            default_value = "medium",
+            help = "quality of fir filter: low, medium, high"
+        )]
+        quality: String,
+        #[arg(
+            short,
+            long,
+            default_value = "interpolated_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Fixed-point CIC decimator or interpolator with droop compensation
+    Cic {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            default_value = "decimate",
+            help = "mode: decimate, interpolate"
+        )]
+        mode: String,
+        #[arg(short, long, help = "rate change factor R")]
+        rate: usize,
+        #[arg(short('n'), long, default_value_t = 4, help = "number of stages N")]
+        stages: usize,
+        #[arg(short, long, default_value_t = 1, help = "differential delay M")]
+        delay: usize,
+        #[arg(short, long, default_value_t = 16, help = "bits of input samples")]
+        bits: u32,
+        #[arg(short, long, help = "length of droop compensation FIR")]
+        compensation: Option<usize>,
+        #[arg(
+            long,
+            default_value_t = 0.5,
+            help = "compensated band as fraction of low-rate Nyquist"
+        )]
+        passband: f64,
+        #[arg(long, help = "save compensation taps to .csv, .json or .h")]
+        taps_out: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value = "cic_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Envelope of signal by Hilbert transform
+    Envelope {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            default_value = "fft",
+            help = "Hilbert transform: fft, fir"
+        )]
+        method: String,
+        #[arg(
+            short('l'),
+            long,
+            default_value_t = 101,
+            help = "odd length of FIR Hilbert transformer"
+        )]
+        fir_length: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "envelope.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Unwrapped instantaneous phase in radians
+    InstPhase {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            default_value = "fft",
+            help = "Hilbert transform: fft, fir"
+        )]
+        method: String,
+        #[arg(
+            short('l'),
+            long,
+            default_value_t = 101,
+            help = "odd length of FIR Hilbert transformer"
+        )]
+        fir_length: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "inst_phase.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Instantaneous frequency in Hz
+    InstFreq {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            default_value = "fft",
+            help = "Hilbert transform: fft, fir"
+        )]
+        method: String,
+        #[arg(
+            short('l'),
+            long,
+            default_value_t = 101,
+            help = "odd length of FIR Hilbert transformer"
+        )]
+        fir_length: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "inst_freq.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Cross-correlation of two signals or autocorrelation of one
+    Xcorr {
+        #[arg(short('1'), long, help = "first signal")]
+        signal1: String,
+        #[arg(
+            short('2'),
+            long,
+            help = "second signal [default: autocorrelation of first]"
+        )]
+        signal2: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value = "none",
+            help = "scale: none, biased, unbiased, normalized"
+        )]
+        scale: String,
+        #[arg(short('l'), long, help = "maximum lag in samples")]
+        max_lag: Option<usize>,
+        #[arg(
+            short,
+            long,
+            default_value = "xcorr.csv",
+            help = "fname of csv with lag and correlation"
+        )]
+        out_csv: String,
+    },
+    /// Delay of second signal relative to first
+    Delay {
+        #[arg(short('1'), long, help = "first signal")]
+        signal1: String,
+        #[arg(short('2'), long, help = "second signal")]
+        signal2: String,
+        #[arg(
+            short,
+            long,
+            default_value = "phat",
+            help = "weighting of cross spectrum: none, phat, scot"
+        )]
+        weighting: String,
+        #[arg(short('l'), long, help = "maximum lag in samples")]
+        max_lag: Option<usize>,
+    },
+    /// Shift signal by fractional delay with zero or circular fill
+    #[command(group(ArgGroup::new("amount").required(true).args(["delay", "time"])))]
+    Shift {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            help = "delay in samples, negative advances"
+        )]
+        delay: Option<f64>,
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            help = "delay in ms, negative advances"
+        )]
+        time: Option<f64>,
+        #[arg(
+            short,
+            long,
+            default_value = "sinc",
+            help = "interpolation: sinc, lagrange, farrow"
+        )]
+        method: String,
+        #[arg(short, long, default_value = "zero", help = "fill: zero, circular")]
+        fill: String,
+        #[arg(
+            short,
+            long,
+            default_value = "shifted_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Delay-line effects: comb, echo, chorus, flanger, phaser
+    Effect(EffectArgs),
+    /// Reverb: Freeverb, feedback delay network or measured impulse response
+    Reverb(ReverbArgs),
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+pub struct GenArgs {
+    #[command(subcommand)]
+    pub command: Option<GenCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GenCommands {
+    Sine {
+        #[arg(short, long, default_value_t = 50., help = "frequency in Hz")]
+        freq: f64,
+        #[arg(short, long, default_value_t = 0., help = "phase in degrees")]
+        phase: f64,
+        #[arg(short, long, default_value_t = 1., help = "duration in seconds")]
+        duration: f64,
+        #[arg(short, long, default_value_t = 1., help = "amplitude of sinus")]
+        amplitude: f64,
+        #[arg(short, long, default_value = "", help = "filename")]
+        out_filename: String,
+    },
+
+    Noise {
+        #[arg(short, long, default_value_t = 1., help = "duration")]
+        duration: f64,
+        #[arg(short, long, default_value_t = 1., help = "standart deviation")]
+        std: f64,
+        #[arg(short, long, default_value_t = 1., help = "mean of noise")]
+        mu: f64,
+        #[arg(short, long, default_value = "", help = "filename")]
+        out_filename: String,
+    },
+
+    Sweep {
+        #[arg(long, default_value_t = 1., help = "start frequency")]
+        f0: f64,
+        #[arg(long, default_value_t = 1., help = "frequency at t1")]
+        f1: f64,
+        #[arg(long, default_value_t = 1., help = "time for stop sweep")]
+        t1: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "linear",
+            help = "type of sweep: linear, quadratic, logarithmic, hyperbolic"
+        )]
+        method: String,
+        #[arg(short, long, help = "only for quadratic, vertex of the parabola")]
+        vertex_zero: bool,
+        #[arg(short, long, default_value = "", help = "filename")]
+        out_filename: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+pub struct FilterArgs {
+    #[command(subcommand)]
+    pub command: Option<FilterCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FilterCommands {
+    /// FIR filter designed by windowed-sinc method
+    Fir {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('t'),
+            long,
+            default_value = "lowpass",
+            help = "type of filter: lowpass, highpass, bandpass, bandstop"
+        )]
+        band_type: String,
+        #[arg(
+            short,
+            long,
+            required = true,
+            value_delimiter = ',',
+            help = "cutoff frequencies in Hz, two for bandpass and bandstop"
+        )]
+        cutoff: Vec<f64>,
+        #[arg(short, long, help = "length of filter")]
+        length: Option<usize>,
+        #[arg(
+            long,
+            conflicts_with = "length",
+            help = "transition width in Hz, length is selected for Kaiser window"
+        )]
+        transition: Option<f64>,
+        #[arg(
+            short,
+            long,
+            default_value_t = 60.,
+            help = "stopband attenuation in dB for Kaiser window"
+        )]
+        attenuation: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "hamming",
+            help = "window: rectangular, hann, hamming, blackman, kaiser"
+        )]
+        window: String,
+        #[arg(
+            long,
+            help = "save taps of filter to file, format by extension: .csv, .json, .h"
+        )]
+        taps_out: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value = "filtered_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Biquad filter from RBJ audio EQ cookbook
+    Biquad {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('t'),
+            long("type"),
+            default_value = "lowpass",
+            help = "type of filter: lowpass, highpass, bandpass, notch, allpass, peaking, lowshelf, highshelf"
+        )]
+        kind: String,
+        #[arg(short, long, default_value_t = 1000., help = "frequency in Hz")]
+        freq: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.707,
+            help = "quality factor (slope for shelves)"
+        )]
+        q: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.,
+            help = "gain in dB for peaking and shelves"
+        )]
+        gain: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "filtered_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// IIR filter as a cascade of second-order sections
+    Iir {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            default_value = "butter",
+            help = "family of filter: butter, cheby1, cheby2, ellip, bessel"
+        )]
+        family: String,
+        #[arg(
+            short('t'),
+            long,
+            default_value = "lowpass",
+            help = "type of filter: lowpass, highpass, bandpass, bandstop"
+        )]
+        band_type: String,
+        #[arg(
+            short,
+            long,
+            required = true,
+            value_delimiter = ',',
+            help = "cutoff frequencies in Hz, two for bandpass and bandstop"
+        )]
+        cutoff: Vec<f64>,
+        #[arg(
+            short('n'),
+            long,
+            default_value_t = 4,
+            help = "order of prototype, doubled for bandpass and bandstop"
+        )]
+        order: usize,
+        #[arg(
+            short,
+            long,
+            default_value_t = 1.,
+            help = "passband ripple in dB for cheby1 and ellip"
+        )]
+        ripple: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 60.,
+            help = "stopband attenuation in dB for cheby2 and ellip"
+        )]
+        attenuation: f64,
+        #[arg(
+            long,
+            help = "save sections to csv, row per section: b0,b1,b2,a0,a1,a2"
+        )]
+        sos_out: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value = "filtered_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Zero-phase forward-backward filtering by coefficients from files
+    #[command(group(ArgGroup::new("coefficients").required(true).args(["taps", "numerator", "sos"])))]
+    Filtfilt {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(long, help = "file with FIR taps, csv or json")]
+        taps: Option<String>,
+        #[arg(short('b'), long, help = "file with numerator b of transfer function")]
+        numerator: Option<String>,
+        #[arg(
+            short('a'),
+            long,
+            requires = "numerator",
+            help = "file with denominator a of transfer function [default: 1]"
+        )]
+        denominator: Option<String>,
+        #[arg(long, help = "file with second-order sections saved by filter iir")]
+        sos: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value = "filtered_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+pub struct DesignArgs {
+    #[command(subcommand)]
+    pub command: Option<DesignCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DesignCommands {
+    /// Equiripple FIR filter by Parks-McClellan (Remez) algorithm
+    Remez {
+        #[arg(short, long, help = "length of filter")]
+        length: usize,
+        #[arg(
+            short,
+            long,
+            required = true,
+            value_delimiter = ',',
+            help = "band edges in Hz, two for each band"
+        )]
+        bands: Vec<f64>,
+        #[arg(
+            short,
+            long,
+            required = true,
+            value_delimiter = ',',
+            help = "desired gain for each band or each band edge"
+        )]
+        desired: Vec<f64>,
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help = "weight for each band [default: 1 for each band]"
+        )]
+        weight: Vec<f64>,
+        #[arg(
+            short('t'),
+            long,
+            default_value = "bandpass",
+            help = "type of filter: bandpass, differentiator, hilbert"
+        )]
+        filter_type: String,
+        #[arg(long, default_value_t = 10000., help = "sample rate in Hz")]
+        fs: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "remez_taps.csv",
+            help = "fname of taps, format by extension: .csv, .json, .h"
+        )]
+        out_taps: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+pub struct DynArgs {
+    #[command(subcommand)]
+    pub command: Option<DynCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DynCommands {
+    /// Feed-forward compressor
+    Compress {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('c'),
+            long,
+            help = "sidechain signal for level detection [default: signal]"
+        )]
+        sidechain: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = -20.,
+            allow_negative_numbers = true,
+            help = "threshold in dBFS"
+        )]
+        threshold: f64,
+        #[arg(short, long, default_value_t = 4., help = "ratio")]
+        ratio: f64,
+        #[arg(short, long, default_value_t = 6., help = "width of soft knee in dB")]
+        knee: f64,
+        #[arg(short, long, default_value_t = 10., help = "attack time in ms")]
+        attack: f64,
+        #[arg(long, default_value_t = 100., help = "release time in ms")]
+        release: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.,
+            allow_negative_numbers = true,
+            help = "makeup gain in dB"
+        )]
+        makeup: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "peak",
+            help = "level detector: peak, rms"
+        )]
+        detector: String,
+        #[arg(
+            short,
+            long,
+            default_value = "dynamics_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Brickwall limiter with lookahead
+    Limit {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('c'),
+            long,
+            help = "sidechain signal for level detection [default: signal]"
+        )]
+        sidechain: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = -1.,
+            allow_negative_numbers = true,
+            help = "ceiling in dBFS"
+        )]
+        threshold: f64,
+        #[arg(short, long, default_value_t = 5., help = "lookahead in ms")]
+        lookahead: f64,
+        #[arg(long, default_value_t = 50., help = "release time in ms")]
+        release: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "dynamics_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Downward expander
+    Expand {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('c'),
+            long,
+            help = "sidechain signal for level detection [default: signal]"
+        )]
+        sidechain: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = -40.,
+            allow_negative_numbers = true,
+            help = "threshold in dBFS"
+        )]
+        threshold: f64,
+        #[arg(short, long, default_value_t = 2., help = "ratio")]
+        ratio: f64,
+        #[arg(short, long, default_value_t = 6., help = "width of soft knee in dB")]
+        knee: f64,
+        #[arg(short, long, default_value_t = 1., help = "attack time in ms")]
+        attack: f64,
+        #[arg(long, default_value_t = 100., help = "release time in ms")]
+        release: f64,
+        #[arg(
+            long,
+            default_value_t = -80.,
+            allow_negative_numbers = true,
+            help = "maximum attenuation in dB"
+        )]
+        range: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "peak",
+            help = "level detector: peak, rms"
+        )]
+        detector: String,
+        #[arg(
+            short,
+            long,
+            default_value = "dynamics_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Noise gate
+    Gate {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('c'),
+            long,
+            help = "sidechain signal for level detection [default: signal]"
+        )]
+        sidechain: Option<String>,
+        #[arg(
+            short,
+            long,
+            default_value_t = -50.,
+            allow_negative_numbers = true,
+            help = "threshold in dBFS"
+        )]
+        threshold: f64,
+        #[arg(short, long, default_value_t = 1., help = "attack time in ms")]
+        attack: f64,
+        #[arg(long, default_value_t = 50., help = "release time in ms")]
+        release: f64,
+        #[arg(
+            long,
+            default_value_t = -80.,
+            allow_negative_numbers = true,
+            help = "maximum attenuation in dB"
+        )]
+        range: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "peak",
+            help = "level detector: peak, rms"
+        )]
+        detector: String,
+        #[arg(
+            short,
+            long,
+            default_value = "dynamics_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Automatic gain control, the file is processed by blocks
+    Agc {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short,
+            long,
+            default_value_t = -20.,
+            allow_negative_numbers = true,
+            help = "target peak level in dBFS"
+        )]
+        target: f64,
+        #[arg(short('g'), long, default_value_t = 30., help = "maximum gain in dB")]
+        max_gain: f64,
+        #[arg(short, long, default_value_t = 10., help = "attack time in ms")]
+        attack: f64,
+        #[arg(long, default_value_t = 1000., help = "decay time in ms")]
+        decay: f64,
+        #[arg(long, default_value_t = 200., help = "hold time after peak in ms")]
+        hold: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 65536,
+            help = "number of samples read at once"
+        )]
+        block_size: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "dynamics_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+pub struct SmoothArgs {
+    #[command(subcommand)]
+    pub command: Option<SmoothCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SmoothCommands {
+    /// Running median, removes impulse noise
+    Median {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, default_value_t = 5, help = "odd length of window")]
+        window: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "reflect",
+            help = "edges: valid, reflect, constant, nearest"
+        )]
+        edge: String,
+        #[arg(
+            short,
+            long,
+            default_value = "smoothed_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Savitzky-Golay smoothing or derivative
+    Savgol {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, default_value_t = 11, help = "odd length of window")]
+        window: usize,
+        #[arg(short, long, default_value_t = 2, help = "order of polynomial")]
+        polyorder: usize,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0,
+            help = "order of derivative, per sample"
+        )]
+        deriv: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "reflect",
+            help = "edges: valid, reflect, constant, nearest"
+        )]
+        edge: String,
+        #[arg(
+            short,
+            long,
+            default_value = "smoothed_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Exponential moving average
+    Ema {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('w'),
+            long,
+            default_value_t = 10,
+            help = "span, alpha = 2 / (span + 1)"
+        )]
+        span: usize,
+        #[arg(
+            short,
+            long,
+            default_value = "reflect",
+            help = "edges: valid, reflect, constant, nearest"
+        )]
+        edge: String,
+        #[arg(
+            short,
+            long,
+            default_value = "smoothed_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Gaussian smoothing
+    Gaussian {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            long,
+            default_value_t = 2.,
+            help = "standard deviation in samples, kernel is truncated at 4 sigma"
+        )]
+        sigma: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "reflect",
+            help = "edges: valid, reflect, constant, nearest"
+        )]
+        edge: String,
+        #[arg(
+            short,
+            long,
+            default_value = "smoothed_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+pub struct EffectArgs {
+    #[command(subcommand)]
+    pub command: Option<EffectCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum EffectCommands {
+    /// Feedforward or feedback comb filter
+    Comb {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, default_value_t = 10., help = "delay in ms")]
+        delay: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.5,
+            allow_negative_numbers = true,
+            help = "gain of delayed signal"
+        )]
+        gain: f64,
+        #[arg(
+            short('t'),
+            long,
+            default_value = "feedback",
+            help = "comb type: feedforward, feedback"
+        )]
+        comb_type: String,
+        #[arg(
+            short,
+            long,
+            default_value = "effect_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Echo with decaying repeats
+    Echo {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, default_value_t = 300., help = "delay in ms")]
+        delay: f64,
+        #[arg(short, long, default_value_t = 0.4, help = "decay of each repeat")]
+        feedback: f64,
+        #[arg(short, long, default_value_t = 0.5, help = "level of repeats")]
+        mix: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "effect_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Chorus with several modulated voices
+    Chorus {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, default_value_t = 20., help = "base delay in ms")]
+        delay: f64,
+        #[arg(long, default_value_t = 5., help = "modulation depth in ms")]
+        depth: f64,
+        #[arg(short, long, default_value_t = 1.5, help = "LFO rate in Hz")]
+        rate: f64,
+        #[arg(short, long, default_value_t = 3, help = "number of voices")]
+        voices: usize,
+        #[arg(short, long, default_value_t = 0.5, help = "share of wet signal")]
+        mix: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "linear",
+            help = "fractional delay interpolation: linear, allpass, lagrange"
+        )]
+        interpolation: String,
+        #[arg(
+            short,
+            long,
+            default_value = "sine",
+            help = "LFO waveform: sine, triangle"
+        )]
+        waveform: String,
+        #[arg(
+            short,
+            long,
+            default_value = "effect_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Flanger with swept short delay and feedback
+    Flanger {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, default_value_t = 2., help = "minimum delay in ms")]
+        delay: f64,
+        #[arg(long, default_value_t = 2., help = "modulation depth in ms")]
+        depth: f64,
+        #[arg(short, long, default_value_t = 0.25, help = "LFO rate in Hz")]
+        rate: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.5,
+            allow_negative_numbers = true,
+            help = "feedback gain"
+        )]
+        feedback: f64,
+        #[arg(short, long, default_value_t = 0.5, help = "share of wet signal")]
+        mix: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "linear",
+            help = "fractional delay interpolation: linear, allpass, lagrange"
+        )]
+        interpolation: String,
+        #[arg(
+            short,
+            long,
+            default_value = "sine",
+            help = "LFO waveform: sine, triangle"
+        )]
+        waveform: String,
+        #[arg(
+            short,
+            long,
+            default_value = "effect_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Phaser with swept allpass stages
+    Phaser {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('n'),
+            long,
+            default_value_t = 4,
+            help = "number of allpass stages"
+        )]
+        stages: usize,
+        #[arg(long, default_value_t = 300., help = "minimum break frequency in Hz")]
+        min_freq: f64,
+        #[arg(long, default_value_t = 1600., help = "maximum break frequency in Hz")]
+        max_freq: f64,
+        #[arg(short, long, default_value_t = 0.5, help = "LFO rate in Hz")]
+        rate: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.5,
+            allow_negative_numbers = true,
+            help = "feedback gain"
+        )]
+        feedback: f64,
+        #[arg(short, long, default_value_t = 0.5, help = "share of wet signal")]
+        mix: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "sine",
+            help = "LFO waveform: sine, triangle"
+        )]
+        waveform: String,
+        #[arg(
+            short,
+            long,
+            default_value = "effect_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+pub struct ReverbArgs {
+    #[command(subcommand)]
+    pub command: Option<ReverbCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReverbCommands {
+    /// Freeverb: parallel lowpass-feedback combs and series allpasses
+    Freeverb {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('t'),
+            long,
+            default_value_t = 1.,
+            help = "reverberation time RT60 in s"
+        )]
+        rt60: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.2,
+            help = "damping of high frequencies, from 0 to 1"
+        )]
+        damping: f64,
+        #[arg(short, long, default_value_t = 20., help = "pre-delay in ms")]
+        pre_delay: f64,
+        #[arg(short, long, default_value_t = 0.3, help = "share of wet signal")]
+        mix: f64,
+        #[arg(
+            long,
+            default_value_t = 0.,
+            help = "length of tail appended to signal in s"
+        )]
+        tail: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "reverb_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Feedback delay network with Householder matrix
+    Fdn {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(
+            short('t'),
+            long,
+            default_value_t = 1.,
+            help = "reverberation time RT60 in s"
+        )]
+        rt60: f64,
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.2,
+            help = "damping of high frequencies, from 0 to 1"
+        )]
+        damping: f64,
+        #[arg(
+            short('n'),
+            long,
+            default_value_t = 8,
+            help = "number of delay lines, 1 to 8"
+        )]
+        lines: usize,
+        #[arg(short, long, default_value_t = 20., help = "pre-delay in ms")]
+        pre_delay: f64,
+        #[arg(short, long, default_value_t = 0.3, help = "share of wet signal")]
+        mix: f64,
+        #[arg(
+            long,
+            default_value_t = 0.,
+            help = "length of tail appended to signal in s"
+        )]
+        tail: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "reverb_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
+    },
+    /// Convolution with measured impulse response
+    Ir {
+        #[arg(short, long, help = "signal")]
+        signal: String,
+        #[arg(short, long, help = "impulse response .wav with the same sample rate")]
+        ir: String,
+        #[arg(short, long, default_value_t = 20., help = "pre-delay in ms")]
+        pre_delay: f64,
+        #[arg(short, long, default_value_t = 0.3, help = "share of wet signal")]
+        mix: f64,
+        #[arg(
+            long,
+            default_value_t = 0.,
+            help = "length of tail appended to signal in s"
+        )]
+        tail: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "reverb_signal.wav",
+            help = "fname of output signal"
+        )]
+        out_signal: String,
     },
 }
 
@@ -0,0 +1,272 @@
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BiquadType {
+    Lowpass,
+    Highpass,
+    Bandpass,
+    Notch,
+    Allpass,
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+impl BiquadType {
+    pub fn parse(name: &str) -> Result<BiquadType, String> {
+        match name {
+            "lowpass" => Ok(BiquadType::Lowpass),
+            "highpass" => Ok(BiquadType::Highpass),
+            "bandpass" => Ok(BiquadType::Bandpass),
+            "notch" => Ok(BiquadType::Notch),
+            "allpass" => Ok(BiquadType::Allpass),
+            "peaking" => Ok(BiquadType::Peaking),
+            "lowshelf" => Ok(BiquadType::LowShelf),
+            "highshelf" => Ok(BiquadType::HighShelf),
+            _ => Err(format!(
+                "Unknown biquad type {name}, expected lowpass, highpass, bandpass, notch, \
+                 allpass, peaking, lowshelf or highshelf"
+            )),
+        }
+    }
+}
+
+/// Second-order IIR section in transposed direct form II.
+/// Coefficients are normalized so that a0 = 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    pub fn new(b: [f64; 3], a: [f64; 3]) -> Biquad {
+        Biquad {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// Design by the RBJ audio EQ cookbook. `gain` in dB is used only by
+    /// peaking and shelving filters, for shelves `q` sets the slope.
+    pub fn design(
+        kind: BiquadType,
+        freq: f64,
+        q: f64,
+        gain: f64,
+        fs: f64,
+    ) -> Result<Biquad, String> {
+        if freq <= 0.0 || freq >= fs / 2.0 {
+            return Err(format!("Frequency must be in (0, {}) Hz", fs / 2.0));
+        }
+        if q <= 0.0 {
+            return Err("Q must be positive".to_string());
+        }
+
+        let w0 = 2.0 * PI * freq / fs;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a = 10.0_f64.powf(gain / 40.0);
+
+        let (b, a) = match kind {
+            BiquadType::Lowpass => (
+                [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+                [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            ),
+            BiquadType::Highpass => (
+                [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+                [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            ),
+            // Полосовой с единичным усилением на центральной частоте
+            BiquadType::Bandpass => ([alpha, 0.0, -alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha]),
+            BiquadType::Notch => (
+                [1.0, -2.0 * cos, 1.0],
+                [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            ),
+            BiquadType::Allpass => (
+                [1.0 - alpha, -2.0 * cos, 1.0 + alpha],
+                [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            ),
+            BiquadType::Peaking => (
+                [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+                [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+            ),
+            BiquadType::LowShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    [
+                        a * ((a + 1.0) - (a - 1.0) * cos + k),
+                        2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                        a * ((a + 1.0) - (a - 1.0) * cos - k),
+                    ],
+                    [
+                        (a + 1.0) + (a - 1.0) * cos + k,
+                        -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                        (a + 1.0) + (a - 1.0) * cos - k,
+                    ],
+                )
+            }
+            BiquadType::HighShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    [
+                        a * ((a + 1.0) + (a - 1.0) * cos + k),
+                        -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                        a * ((a + 1.0) + (a - 1.0) * cos - k),
+                    ],
+                    [
+                        (a + 1.0) - (a - 1.0) * cos + k,
+                        2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                        (a + 1.0) - (a - 1.0) * cos - k,
+                    ],
+                )
+            }
+        };
+
+        Ok(Biquad::new(b, a))
+    }
+
+    /// Numerator and denominator with a0 = 1
+    pub fn coefficients(&self) -> ([f64; 3], [f64; 3]) {
+        ([self.b0, self.b1, self.b2], [1.0, self.a1, self.a2])
+    }
+
+    pub fn process_sample(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    /// Filters a block, the state is kept between calls
+    pub fn process(&mut self, signal: &[f64]) -> Vec<f64> {
+        signal.iter().map(|&x| self.process_sample(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+    use crate::signal;
+
+    const FS: f64 = signal::SAMPLE_RATE as f64;
+
+    fn gain_db(filter: &Biquad, freq: f64) -> f64 {
+        let w = 2.0 * PI * freq / FS;
+        let (b, a) = filter.coefficients();
+        let eval = |c: [f64; 3]| {
+            let re = c[0] + c[1] * w.cos() + c[2] * (2.0 * w).cos();
+            let im = -c[1] * w.sin() - c[2] * (2.0 * w).sin();
+            (re * re + im * im).sqrt()
+        };
+        20.0 * (eval(b) / eval(a)).log10()
+    }
+
+    #[test]
+    fn test_biquad_type_parse() {
+        assert_eq!(BiquadType::parse("peaking"), Ok(BiquadType::Peaking));
+        assert_eq!(BiquadType::parse("lowshelf"), Ok(BiquadType::LowShelf));
+        assert!(BiquadType::parse("shelf").is_err());
+    }
+
+    #[test]
+    fn test_biquad_lowpass_highpass() {
+        let q = std::f64::consts::FRAC_1_SQRT_2;
+        let lp = Biquad::design(BiquadType::Lowpass, 1000.0, q, 0.0, FS).unwrap();
+        assert!(gain_db(&lp, 0.0).abs() < 1e-9);
+        assert!((gain_db(&lp, 1000.0) + 3.0103).abs() < 1e-3);
+        assert!(gain_db(&lp, 4000.0) < -20.0);
+
+        let hp = Biquad::design(BiquadType::Highpass, 1000.0, q, 0.0, FS).unwrap();
+        assert!(gain_db(&hp, FS / 2.0 - 1e-9).abs() < 1e-6);
+        assert!((gain_db(&hp, 1000.0) + 3.0103).abs() < 1e-3);
+        assert!(gain_db(&hp, 100.0) < -30.0);
+    }
+
+    #[test]
+    fn test_biquad_bandpass_notch_allpass() {
+        let bp = Biquad::design(BiquadType::Bandpass, 1000.0, 2.0, 0.0, FS).unwrap();
+        assert!(gain_db(&bp, 1000.0).abs() < 1e-9);
+        assert!(gain_db(&bp, 100.0) < -20.0);
+
+        let notch = Biquad::design(BiquadType::Notch, 1000.0, 2.0, 0.0, FS).unwrap();
+        assert!(gain_db(&notch, 1000.0) < -100.0);
+        assert!(gain_db(&notch, 4000.0).abs() < 0.5);
+
+        let ap = Biquad::design(BiquadType::Allpass, 1000.0, 2.0, 0.0, FS).unwrap();
+        for freq in [10.0, 500.0, 1000.0, 3000.0] {
+            assert!(gain_db(&ap, freq).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_biquad_peaking_and_shelves() {
+        let peak = Biquad::design(BiquadType::Peaking, 1000.0, 2.0, 6.0, FS).unwrap();
+        assert!((gain_db(&peak, 1000.0) - 6.0).abs() < 1e-9);
+        assert!(gain_db(&peak, 50.0).abs() < 0.1);
+
+        let low = Biquad::design(BiquadType::LowShelf, 300.0, 0.707, -6.0, FS).unwrap();
+        assert!((gain_db(&low, 0.0) + 6.0).abs() < 1e-9);
+        assert!((gain_db(&low, 300.0) + 3.0).abs() < 1e-6);
+        assert!(gain_db(&low, 4000.0).abs() < 0.1);
+
+        let high = Biquad::design(BiquadType::HighShelf, 2000.0, 0.707, 4.0, FS).unwrap();
+        assert!((gain_db(&high, FS / 2.0 - 1e-9) - 4.0).abs() < 1e-6);
+        assert!(gain_db(&high, 20.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_biquad_invalid_parameters() {
+        assert!(Biquad::design(BiquadType::Lowpass, 0.0, 1.0, 0.0, FS).is_err());
+        assert!(Biquad::design(BiquadType::Lowpass, 6000.0, 1.0, 0.0, FS).is_err());
+        assert!(Biquad::design(BiquadType::Lowpass, 1000.0, 0.0, 0.0, FS).is_err());
+    }
+
+    #[test]
+    fn test_biquad_new_normalizes() {
+        let filter = Biquad::new([2.0, 4.0, 6.0], [2.0, 1.0, 0.5]);
+        assert_eq!(filter.coefficients(), ([1.0, 2.0, 3.0], [1.0, 0.5, 0.25]));
+    }
+
+    #[test]
+    fn test_biquad_impulse_response() {
+        // y[n] = x[n] + 0.5 y[n-1]
+        let mut filter = Biquad::new([1.0, 0.0, 0.0], [1.0, -0.5, 0.0]);
+        let result = filter.process(&[1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(result, vec![1.0, 0.5, 0.25, 0.125]);
+    }
+
+    #[test]
+    fn test_biquad_stream_equals_block() {
+        let sig = generate::create_sine(440.0, 0.0, 0.1, 1.0);
+        let mut whole = Biquad::design(BiquadType::Peaking, 1000.0, 2.0, 6.0, FS).unwrap();
+        let mut parts = whole;
+
+        let expected = whole.process(&sig);
+        let mut result = parts.process(&sig[..333]);
+        result.extend(parts.process(&sig[333..]));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_biquad_notch_removes_tone() {
+        let sig = generate::create_sine(1000.0, 0.0, 1.0, 1.0);
+        let mut notch = Biquad::design(BiquadType::Notch, 1000.0, 5.0, 0.0, FS).unwrap();
+        let result = notch.process(&sig);
+
+        let tail = &result[result.len() / 2..];
+        assert!(tail.iter().all(|x| x.abs() < 1e-3));
+    }
+}
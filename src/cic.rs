@@ -0,0 +1,302 @@
+use std::f64::consts::PI;
+
+use crate::fir::Window;
+
+// Плотность сетки частот при расчёте корректора
+const GRID_POINTS: usize = 4096;
+// Окно Кайзера для корректора, боковые лепестки около -50 дБ
+const COMPENSATION_BETA: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CicMode {
+    Decimator,
+    Interpolator,
+}
+
+impl CicMode {
+    pub fn parse(name: &str) -> Result<CicMode, String> {
+        match name {
+            "decimate" => Ok(CicMode::Decimator),
+            "interpolate" => Ok(CicMode::Interpolator),
+            _ => Err(format!(
+                "Unknown CIC mode {name}, expected decimate or interpolate"
+            )),
+        }
+    }
+}
+
+/// Cascaded integrator-comb filter (Hogenauer) with `stages` integrators
+/// and combs, differential delay `delay` and rate change `rate`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cic {
+    pub stages: usize,
+    pub delay: usize,
+    pub rate: usize,
+    pub mode: CicMode,
+}
+
+// Перенос в дополнительном коде с разрядностью width
+fn wrap(value: i64, width: u32) -> i64 {
+    if width >= 64 {
+        value
+    } else {
+        let shift = 64 - width;
+        (value << shift) >> shift
+    }
+}
+
+fn integrate(signal: &mut [i64], width: u32) {
+    let mut acc = 0i64;
+    for x in signal.iter_mut() {
+        acc = wrap(acc.wrapping_add(*x), width);
+        *x = acc;
+    }
+}
+
+fn comb(signal: &mut [i64], delay: usize, width: u32) {
+    let mut history = vec![0i64; delay];
+    for (i, x) in signal.iter_mut().enumerate() {
+        let previous = std::mem::replace(&mut history[i % delay], *x);
+        *x = wrap(x.wrapping_sub(previous), width);
+    }
+}
+
+impl Cic {
+    pub fn new(stages: usize, delay: usize, rate: usize, mode: CicMode) -> Result<Cic, String> {
+        if stages == 0 || delay == 0 || rate == 0 {
+            return Err("Stages, differential delay and rate must be positive".to_string());
+        }
+        Ok(Cic {
+            stages,
+            delay,
+            rate,
+            mode,
+        })
+    }
+
+    /// DC gain (RM)^N, for the interpolator divided by R because of zero stuffing
+    pub fn gain(&self) -> f64 {
+        let gain = ((self.rate * self.delay) as f64).powi(self.stages as i32);
+        match self.mode {
+            CicMode::Decimator => gain,
+            CicMode::Interpolator => gain / self.rate as f64,
+        }
+    }
+
+    /// Bits added to the input by the filter gain
+    pub fn bit_growth(&self) -> u32 {
+        self.gain().log2().ceil() as u32
+    }
+
+    /// Register width that keeps the output exact for `input_bits` input,
+    /// intermediate overflows wrap around harmlessly
+    pub fn register_width(&self, input_bits: u32) -> u32 {
+        input_bits + self.bit_growth()
+    }
+
+    /// Magnitude response normalized to unit DC gain,
+    /// `freq` is in cycles per sample of the low rate
+    pub fn magnitude(&self, freq: f64) -> f64 {
+        let x = PI * freq / self.rate as f64;
+        if x.sin().abs() < 1e-12 {
+            return 1.0;
+        }
+        let rm = (self.rate * self.delay) as f64;
+        ((PI * self.delay as f64 * freq).sin() / (rm * x.sin()))
+            .abs()
+            .powi(self.stages as i32)
+    }
+
+    /// Fixed-point filtering with registers of `width` bits
+    pub fn process(&self, input: &[i64], width: u32) -> Result<Vec<i64>, String> {
+        if !(2..=64).contains(&width) {
+            return Err(format!(
+                "Register width must be in 2..=64 bits, got {width}"
+            ));
+        }
+        match self.mode {
+            CicMode::Decimator => {
+                let mut high = input.to_vec();
+                for _ in 0..self.stages {
+                    integrate(&mut high, width);
+                }
+                let mut low: Vec<i64> = high.into_iter().step_by(self.rate).collect();
+                for _ in 0..self.stages {
+                    comb(&mut low, self.delay, width);
+                }
+                Ok(low)
+            }
+            CicMode::Interpolator => {
+                let mut low = input.to_vec();
+                for _ in 0..self.stages {
+                    comb(&mut low, self.delay, width);
+                }
+                let mut high = vec![0i64; low.len() * self.rate];
+                for (i, x) in low.iter().enumerate() {
+                    high[i * self.rate] = *x;
+                }
+                for _ in 0..self.stages {
+                    integrate(&mut high, width);
+                }
+                Ok(high)
+            }
+        }
+    }
+
+    /// Linear-phase FIR at the low rate flattening the passband droop,
+    /// `passband` is a fraction of the low-rate Nyquist frequency.
+    /// Above the passband the response falls linearly to zero at Nyquist.
+    pub fn compensation_filter(&self, length: usize, passband: f64) -> Result<Vec<f64>, String> {
+        if length < 3 || length.is_multiple_of(2) {
+            return Err(format!(
+                "Compensation filter must have odd length of at least 3, got {length}"
+            ));
+        }
+        if passband <= 0.0 || passband >= 1.0 {
+            return Err(format!("Passband must be in (0, 1), got {passband}"));
+        }
+        let edge = 0.5 * passband;
+        let peak = 1.0 / self.magnitude(edge);
+        let desired = |f: f64| {
+            if f <= edge {
+                1.0 / self.magnitude(f)
+            } else {
+                peak * (0.5 - f) / (0.5 - edge)
+            }
+        };
+
+        // Обратное преобразование Фурье чётной АЧХ методом трапеций
+        let center = (length - 1) as f64 / 2.0;
+        let step = 0.5 / GRID_POINTS as f64;
+        let window = Window::Kaiser(COMPENSATION_BETA).coefficients(length);
+        let mut taps: Vec<f64> = (0..length)
+            .map(|n| {
+                let m = n as f64 - center;
+                let sum: f64 = (0..=GRID_POINTS)
+                    .map(|k| {
+                        let f = k as f64 * step;
+                        let weight = if k == 0 || k == GRID_POINTS { 0.5 } else { 1.0 };
+                        weight * desired(f) * (2.0 * PI * f * m).cos()
+                    })
+                    .sum();
+                2.0 * step * sum * window[n]
+            })
+            .collect();
+
+        let gain: f64 = taps.iter().sum();
+        taps.iter_mut().for_each(|h| *h /= gain);
+        Ok(taps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsp::{self, ConvolveMethod, ConvolveMode};
+
+    // Эталон: каскад N скользящих сумм длины RM
+    fn boxcar_reference(signal: &[f64], cic: &Cic) -> Vec<f64> {
+        let boxcar = vec![1.0; cic.rate * cic.delay];
+        let mut result = signal.to_vec();
+        for _ in 0..cic.stages {
+            result = dsp::convolve(&result, &boxcar, ConvolveMode::Full, ConvolveMethod::Direct);
+        }
+        result
+    }
+
+    fn test_input() -> Vec<i64> {
+        (0..300).map(|i| (i * 37 % 255) - 127).collect()
+    }
+
+    #[test]
+    fn test_cic_decimator_matches_boxcar() {
+        let cic = Cic::new(3, 2, 5, CicMode::Decimator).unwrap();
+        let input = test_input();
+        let result = cic.process(&input, 64).unwrap();
+        assert_eq!(result.len(), 60);
+
+        let signal: Vec<f64> = input.iter().map(|&x| x as f64).collect();
+        let expected = boxcar_reference(&signal, &cic);
+        for (k, y) in result.iter().enumerate() {
+            assert_eq!(*y as f64, expected[k * cic.rate]);
+        }
+    }
+
+    #[test]
+    fn test_cic_interpolator_matches_boxcar() {
+        let cic = Cic::new(4, 1, 4, CicMode::Interpolator).unwrap();
+        let input = test_input();
+        let result = cic.process(&input, 64).unwrap();
+        assert_eq!(result.len(), 1200);
+
+        let mut stuffed = vec![0.0; input.len() * cic.rate];
+        for (i, x) in input.iter().enumerate() {
+            stuffed[i * cic.rate] = *x as f64;
+        }
+        let expected = boxcar_reference(&stuffed, &cic);
+        for (y, e) in result.iter().zip(expected.iter()) {
+            assert_eq!(*y as f64, *e);
+        }
+    }
+
+    #[test]
+    fn test_cic_wraparound_is_harmless() {
+        // Интеграторы многократно переполняются, но выход точен при ширине Хогенауэра
+        let cic = Cic::new(4, 1, 8, CicMode::Decimator).unwrap();
+        let width = cic.register_width(8);
+        assert_eq!(width, 20);
+
+        let input = test_input();
+        let wrapped = cic.process(&input, width).unwrap();
+        let exact = cic.process(&input, 64).unwrap();
+        assert_eq!(wrapped, exact);
+
+        let constant = vec![127i64; 1000];
+        let result = cic.process(&constant, width).unwrap();
+        assert_eq!(*result.last().unwrap(), 127 * 4096);
+        assert!(cic.process(&constant, 65).is_err());
+    }
+
+    #[test]
+    fn test_cic_gain_and_bit_growth() {
+        let cic = Cic::new(4, 1, 8, CicMode::Decimator).unwrap();
+        assert_eq!(cic.gain(), 4096.0);
+        assert_eq!(cic.bit_growth(), 12);
+
+        let cic = Cic::new(4, 1, 8, CicMode::Interpolator).unwrap();
+        assert_eq!(cic.gain(), 512.0);
+        assert_eq!(cic.bit_growth(), 9);
+
+        let cic = Cic::new(3, 2, 10, CicMode::Decimator).unwrap();
+        assert_eq!(cic.bit_growth(), 13);
+
+        assert!(Cic::new(0, 1, 8, CicMode::Decimator).is_err());
+        assert_eq!(CicMode::parse("interpolate"), Ok(CicMode::Interpolator));
+        assert!(CicMode::parse("comb").is_err());
+    }
+
+    #[test]
+    fn test_compensation_flattens_droop() {
+        let cic = Cic::new(4, 1, 8, CicMode::Decimator).unwrap();
+        let passband = 0.5;
+        // Без коррекции спад на краю полосы около 3.6 дБ
+        assert!(20.0 * cic.magnitude(0.25).log10() < -3.0);
+
+        let taps = cic.compensation_filter(31, passband).unwrap();
+        let center = 15.0;
+        // Излом АЧХ на краю полосы сглаживается окном, проверяем до 80% полосы
+        for i in 0..=40 {
+            let f = 0.2 * i as f64 / 40.0;
+            let response: f64 = taps
+                .iter()
+                .enumerate()
+                .map(|(n, h)| h * (2.0 * PI * f * (n as f64 - center)).cos())
+                .sum();
+            let total = 20.0 * (response.abs() * cic.magnitude(f)).log10();
+            assert!(total.abs() < 0.1, "{f} {total}");
+        }
+
+        assert!(cic.compensation_filter(30, passband).is_err());
+        assert!(cic.compensation_filter(31, 1.0).is_err());
+    }
+}
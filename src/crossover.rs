@@ -0,0 +1,223 @@
+use crate::biquad::Biquad;
+use crate::dsp;
+use crate::fir::{self, BandType, Window};
+use crate::iir::{self, IirFamily};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrossoverType {
+    LinkwitzRiley,
+    Fir,
+}
+
+impl CrossoverType {
+    pub fn parse(name: &str) -> Result<CrossoverType, String> {
+        match name {
+            "lr" => Ok(CrossoverType::LinkwitzRiley),
+            "fir" => Ok(CrossoverType::Fir),
+            _ => Err(format!("Unknown crossover type {name}, expected lr or fir")),
+        }
+    }
+}
+
+fn check_freqs(freqs: &[f64], fs: f64) -> Result<(), String> {
+    if freqs.is_empty() {
+        return Err("At least one crossover frequency is needed".to_string());
+    }
+    let nyquist = fs / 2.0;
+    if freqs.iter().any(|&f| f <= 0.0 || f >= nyquist) {
+        return Err(format!(
+            "Crossover frequencies must be in (0, {nyquist}) Hz"
+        ));
+    }
+    if freqs.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Crossover frequencies must be increasing".to_string());
+    }
+    Ok(())
+}
+
+/// Linkwitz-Riley lowpass and highpass of `order` 2, 4 or 8: squared
+/// Butterworth filters of half order, the highpass of order 2 is inverted
+/// so that the sum of both is allpass
+pub fn linkwitz_riley_pair(
+    order: usize,
+    freq: f64,
+    fs: f64,
+) -> Result<(Vec<Biquad>, Vec<Biquad>), String> {
+    if ![2, 4, 8].contains(&order) {
+        return Err(format!(
+            "Order of Linkwitz-Riley filter must be 2, 4 or 8, got {order}"
+        ));
+    }
+    let squared = |band: BandType| -> Result<Vec<Biquad>, String> {
+        let sections = iir::iirfilter(
+            order / 2,
+            &[freq],
+            band,
+            IirFamily::Butterworth,
+            0.0,
+            0.0,
+            fs,
+        )?;
+        Ok(sections.iter().chain(sections.iter()).cloned().collect())
+    };
+    let lowpass = squared(BandType::Lowpass)?;
+    let mut highpass = squared(BandType::Highpass)?;
+    if order == 2 {
+        let first = &mut highpass[0];
+        first.b0 = -first.b0;
+        first.b1 = -first.b1;
+        first.b2 = -first.b2;
+    }
+    Ok((lowpass, highpass))
+}
+
+fn filtered(sections: &[Biquad], signal: &[f64]) -> Vec<f64> {
+    iir::sosfilt(&mut sections.to_vec(), signal)
+}
+
+/// Splits signal into `freqs.len() + 1` bands by a tree of Linkwitz-Riley
+/// filters. Lower bands pass through allpasses of the higher crossovers, so
+/// the sum of the bands has flat magnitude response.
+pub fn linkwitz_riley(
+    signal: &[f64],
+    freqs: &[f64],
+    order: usize,
+    fs: f64,
+) -> Result<Vec<Vec<f64>>, String> {
+    check_freqs(freqs, fs)?;
+    let mut bands: Vec<Vec<f64>> = Vec::with_capacity(freqs.len() + 1);
+    let mut rest = signal.to_vec();
+    for &freq in freqs {
+        let (lowpass, highpass) = linkwitz_riley_pair(order, freq, fs)?;
+        // Фазовая компенсация уже выделенных полос: LP + HP этой частоты
+        for band in bands.iter_mut() {
+            let low = filtered(&lowpass, band);
+            let high = filtered(&highpass, band);
+            *band = low.iter().zip(high.iter()).map(|(l, h)| l + h).collect();
+        }
+        bands.push(filtered(&lowpass, &rest));
+        rest = filtered(&highpass, &rest);
+    }
+    bands.push(rest);
+    Ok(bands)
+}
+
+/// Splits signal by complementary linear-phase FIR filters of odd `length`.
+/// Kernels are differences of windowed-sinc lowpasses and sum up to a unit
+/// impulse, the output is aligned with the input.
+pub fn linear_phase(
+    signal: &[f64],
+    freqs: &[f64],
+    length: usize,
+    fs: f64,
+) -> Result<Vec<Vec<f64>>, String> {
+    check_freqs(freqs, fs)?;
+    if length.is_multiple_of(2) {
+        return Err(format!("Length of FIR filters must be odd, got {length}"));
+    }
+    let mut lowpasses: Vec<Vec<f64>> = freqs
+        .iter()
+        .map(|&freq| fir::firwin(length, &[freq], BandType::Lowpass, Window::Blackman, fs))
+        .collect::<Result<_, _>>()?;
+    let mut impulse = vec![0.0; length];
+    impulse[length / 2] = 1.0;
+    lowpasses.push(impulse);
+
+    let mut previous = vec![0.0; length];
+    Ok(lowpasses
+        .iter()
+        .map(|lowpass| {
+            let kernel: Vec<f64> = lowpass
+                .iter()
+                .zip(previous.iter())
+                .map(|(l, p)| l - p)
+                .collect();
+            previous = lowpass.clone();
+            dsp::convolve(
+                signal,
+                &kernel,
+                dsp::ConvolveMode::Same,
+                dsp::ConvolveMethod::Auto,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{impulse, magnitude};
+
+    const FS: f64 = 10000.0;
+
+    fn sum(bands: &[Vec<f64>]) -> Vec<f64> {
+        (0..bands[0].len())
+            .map(|n| bands.iter().map(|band| band[n]).sum())
+            .collect()
+    }
+
+    #[test]
+    fn test_linkwitz_riley_pair() {
+        for order in [2, 4, 8] {
+            let bands = linkwitz_riley(&impulse(4000), &[1000.0], order, FS).unwrap();
+            // На частоте раздела обе полосы ослаблены на 6 дБ
+            for band in bands.iter() {
+                assert!((magnitude(band, 1000.0, FS) - 0.5).abs() < 1e-6, "{order}");
+            }
+            let total = sum(&bands);
+            for freq in [50.0, 700.0, 1000.0, 1500.0, 4000.0] {
+                assert!(
+                    (magnitude(&total, freq, FS) - 1.0).abs() < 1e-6,
+                    "{order} {freq}"
+                );
+            }
+            // Крутизна спада 6 дБ на октаву на каждый порядок
+            let slope = 20.0
+                * (magnitude(&bands[0], 4000.0, FS) / magnitude(&bands[0], 2000.0, FS)).log10();
+            assert!(slope < -5.0 * order as f64, "{order} {slope}");
+        }
+        assert!(linkwitz_riley_pair(3, 1000.0, FS).is_err());
+    }
+
+    #[test]
+    fn test_linkwitz_riley_bands() {
+        let freqs = [200.0, 1000.0, 3000.0];
+        let bands = linkwitz_riley(&impulse(8000), &freqs, 4, FS).unwrap();
+        assert_eq!(bands.len(), 4);
+        let total = sum(&bands);
+        for freq in [20.0, 200.0, 500.0, 1000.0, 2000.0, 3000.0, 4500.0] {
+            assert!((magnitude(&total, freq, FS) - 1.0).abs() < 1e-6, "{freq}");
+        }
+        // Каждая полоса пропускает свою середину
+        for (band, freq) in bands.iter().zip([50.0, 450.0, 1700.0, 4500.0]) {
+            assert!(magnitude(band, freq, FS) > 0.8, "{freq}");
+        }
+
+        assert!(linkwitz_riley(&[1.0], &[1000.0, 200.0], 4, FS).is_err());
+        assert!(linkwitz_riley(&[1.0], &[], 4, FS).is_err());
+        assert!(linkwitz_riley(&[1.0], &[5000.0], 4, FS).is_err());
+    }
+
+    #[test]
+    fn test_linear_phase() {
+        let signal: Vec<f64> = (0..2000)
+            .map(|n| (0.3 * n as f64).sin() + 0.5 * (0.01 * n as f64).cos())
+            .collect();
+        let bands = linear_phase(&signal, &[300.0, 2000.0], 201, FS).unwrap();
+        assert_eq!(bands.len(), 3);
+        for (y, x) in sum(&bands).iter().zip(signal.iter()) {
+            assert!((y - x).abs() < 1e-9);
+        }
+
+        // Импульс в середине, чтобы режим same не обрезал характеристики
+        let mut centered = vec![0.0; 401];
+        centered[200] = 1.0;
+        let response = linear_phase(&centered, &[300.0, 2000.0], 201, FS).unwrap();
+        assert!(magnitude(&response[0], 1000.0, FS) < 1e-3);
+        assert!((magnitude(&response[1], 1000.0, FS) - 1.0).abs() < 1e-3);
+        assert!(magnitude(&response[2], 1000.0, FS) < 1e-3);
+        assert!(linear_phase(&signal, &[300.0], 200, FS).is_err());
+        assert_eq!(CrossoverType::parse("fir"), Ok(CrossoverType::Fir));
+        assert!(CrossoverType::parse("butter").is_err());
+    }
+}
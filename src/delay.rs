@@ -0,0 +1,186 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    Linear,
+    Allpass,
+    Lagrange,
+}
+
+impl Interpolation {
+    pub fn parse(name: &str) -> Result<Interpolation, String> {
+        match name {
+            "linear" => Ok(Interpolation::Linear),
+            "allpass" => Ok(Interpolation::Allpass),
+            "lagrange" => Ok(Interpolation::Lagrange),
+            _ => Err(format!(
+                "Unknown interpolation {name}, expected linear, allpass or lagrange"
+            )),
+        }
+    }
+}
+
+/// Weights of cubic Lagrange interpolation at fraction `d` between the
+/// second and third of four equally spaced points
+pub fn lagrange_weights(d: f64) -> [f64; 4] {
+    [
+        -d * (d - 1.0) * (d - 2.0) / 6.0,
+        (d + 1.0) * (d - 1.0) * (d - 2.0) / 2.0,
+        -(d + 1.0) * d * (d - 2.0) / 2.0,
+        (d + 1.0) * d * (d - 1.0) / 6.0,
+    ]
+}
+
+/// Circular delay line with fractional read. The allpass interpolator
+/// keeps its own state, so it has to be read once per written sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelayLine {
+    buffer: Vec<f64>,
+    position: usize,
+    interpolation: Interpolation,
+    allpass_state: f64,
+}
+
+impl DelayLine {
+    /// Delay line for delays up to `max_delay` samples
+    pub fn new(max_delay: usize, interpolation: Interpolation) -> DelayLine {
+        DelayLine {
+            // Запас на соседние точки интерполятора Лагранжа
+            buffer: vec![0.0; max_delay + 3],
+            position: 0,
+            interpolation,
+            allpass_state: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.position = (self.position + 1) % self.buffer.len();
+        self.buffer[self.position] = x;
+    }
+
+    /// Sample written `delay` pushes ago, `tap(0)` is the last one
+    pub fn tap(&self, delay: usize) -> f64 {
+        let length = self.buffer.len();
+        self.buffer[(self.position + length - delay % length) % length]
+    }
+
+    /// Sample `delay` samples ago, clamped to the length of the line.
+    /// Lagrange interpolation falls back to linear for delays under one sample
+    pub fn read(&mut self, delay: f64) -> f64 {
+        let delay = delay.clamp(0.0, (self.buffer.len() - 3) as f64);
+        let whole = delay.floor() as usize;
+        let frac = delay - whole as f64;
+        match self.interpolation {
+            Interpolation::Linear => (1.0 - frac) * self.tap(whole) + frac * self.tap(whole + 1),
+            Interpolation::Allpass => {
+                // Всепропускающий фильтр первого порядка, задержка frac на низких частотах
+                let eta = (1.0 - frac) / (1.0 + frac);
+                let y = eta * self.tap(whole) + self.tap(whole + 1) - eta * self.allpass_state;
+                self.allpass_state = y;
+                y
+            }
+            // Для задержки меньше отсчёта более нового отсчёта ещё нет
+            Interpolation::Lagrange if whole == 0 => {
+                (1.0 - frac) * self.tap(0) + frac * self.tap(1)
+            }
+            Interpolation::Lagrange => {
+                // Кубический Лагранж по точкам -1, 0, 1, 2 вокруг отсчёта whole
+                let points = [
+                    self.tap(whole - 1),
+                    self.tap(whole),
+                    self.tap(whole + 1),
+                    self.tap(whole + 2),
+                ];
+                points
+                    .iter()
+                    .zip(lagrange_weights(frac).iter())
+                    .map(|(x, w)| x * w)
+                    .sum()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    // Задержка сигнала переменной delay(n) через линию
+    fn delayed(signal: &[f64], interpolation: Interpolation, delay: f64) -> Vec<f64> {
+        let mut line = DelayLine::new(64, interpolation);
+        signal
+            .iter()
+            .map(|&x| {
+                line.push(x);
+                line.read(delay)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_integer_delay() {
+        let signal: Vec<f64> = (1..=20).map(|n| n as f64).collect();
+        for interpolation in [
+            Interpolation::Linear,
+            Interpolation::Allpass,
+            Interpolation::Lagrange,
+        ] {
+            let result = delayed(&signal, interpolation, 3.0);
+            assert_eq!(&result[..3], &[0.0; 3]);
+            assert_eq!(&result[3..], &signal[..17]);
+        }
+        let mut line = DelayLine::new(4, Interpolation::Linear);
+        line.push(1.0);
+        line.push(2.0);
+        assert_eq!(line.tap(0), 2.0);
+        assert_eq!(line.tap(1), 1.0);
+        // Чтение за пределами линии ограничивается её длиной
+        assert_eq!(line.read(100.0), line.read(4.0));
+    }
+
+    #[test]
+    fn test_fractional_delay_of_polynomials() {
+        // Линейная интерполяция точна для прямой, Лагранж для кубической параболы
+        let ramp: Vec<f64> = (0..40).map(|n| 0.5 * n as f64).collect();
+        let result = delayed(&ramp, Interpolation::Linear, 2.25);
+        for (n, y) in result.iter().enumerate().skip(3) {
+            assert!((y - 0.5 * (n as f64 - 2.25)).abs() < 1e-12);
+        }
+
+        let cubic = |t: f64| 0.01 * t.powi(3) - 0.2 * t * t + t;
+        let signal: Vec<f64> = (0..40).map(|n| cubic(n as f64)).collect();
+        let result = delayed(&signal, Interpolation::Lagrange, 5.6);
+        for (n, y) in result.iter().enumerate().skip(8) {
+            assert!((y - cubic(n as f64 - 5.6)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_lagrange_below_one_sample() {
+        // Без будущего отсчёта Лагранж переходит на линейную интерполяцию
+        let ramp: Vec<f64> = (0..20).map(|n| n as f64).collect();
+        let result = delayed(&ramp, Interpolation::Lagrange, 0.5);
+        for (n, y) in result.iter().enumerate().skip(1) {
+            assert!((y - (n as f64 - 0.5)).abs() < 1e-12, "{n} {y}");
+        }
+        let result = delayed(&ramp, Interpolation::Lagrange, 1.5);
+        for (n, y) in result.iter().enumerate().skip(3) {
+            assert!((y - (n as f64 - 1.5)).abs() < 1e-12, "{n} {y}");
+        }
+    }
+
+    #[test]
+    fn test_allpass_fractional_delay() {
+        // На низкой частоте всепропускающий интерполятор даёт нужную задержку без потерь
+        let freq = 0.01;
+        let signal: Vec<f64> = (0..2000)
+            .map(|n| (2.0 * PI * freq * n as f64).sin())
+            .collect();
+        let result = delayed(&signal, Interpolation::Allpass, 10.5);
+        for (n, y) in result.iter().enumerate().skip(200) {
+            let expected = (2.0 * PI * freq * (n as f64 - 10.5)).sin();
+            assert!((y - expected).abs() < 1e-3, "{y} {expected}");
+        }
+        assert_eq!(Interpolation::parse("allpass"), Ok(Interpolation::Allpass));
+        assert!(Interpolation::parse("cubic").is_err());
+    }
+}
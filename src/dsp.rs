@@ -1,3 +1,7 @@
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
 pub fn add_signal(sig1: &[f64], sig2: &[f64]) -> Vec<f64> {
     let mut sig_new: Vec<f64> = Vec::new();
     let length = if sig1.len() < sig2.len() {
@@ -68,10 +72,482 @@ pub fn move_average(sig1: &[f64], kernel_length: i32) -> Vec<f64> {
     sig_new
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LengthPolicy {
+    Shortest,
+    Longest,
+    First,
+}
+
+impl LengthPolicy {
+    pub fn parse(name: &str) -> Result<LengthPolicy, String> {
+        match name {
+            "shortest" => Ok(LengthPolicy::Shortest),
+            "longest" => Ok(LengthPolicy::Longest),
+            "first" => Ok(LengthPolicy::First),
+            _ => Err(format!(
+                "Unknown length policy {name}, expected shortest, longest or first"
+            )),
+        }
+    }
+}
+
+/// Truncates or pads with zeros both signals to a common length
+pub fn match_length(sig1: &[f64], sig2: &[f64], policy: LengthPolicy) -> (Vec<f64>, Vec<f64>) {
+    let length = match policy {
+        LengthPolicy::Shortest => sig1.len().min(sig2.len()),
+        LengthPolicy::Longest => sig1.len().max(sig2.len()),
+        LengthPolicy::First => sig1.len(),
+    };
+    let fit = |sig: &[f64]| {
+        let mut sig_new = sig[..sig.len().min(length)].to_vec();
+        sig_new.resize(length, 0.0);
+        sig_new
+    };
+    (fit(sig1), fit(sig2))
+}
+
+// Полуширина интерполирующего sinc для дробного сдвига
+const SHIFT_HALF_WIDTH: isize = 16;
+
+// Узлы интерполятора Лагранжа пятого порядка в структуре Фарроу
+const FARROW_NODES: std::ops::RangeInclusive<isize> = -2..=3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShiftMethod {
+    Sinc,
+    Lagrange,
+    Farrow,
+}
+
+impl ShiftMethod {
+    pub fn parse(name: &str) -> Result<ShiftMethod, String> {
+        match name {
+            "sinc" => Ok(ShiftMethod::Sinc),
+            "lagrange" => Ok(ShiftMethod::Lagrange),
+            "farrow" => Ok(ShiftMethod::Farrow),
+            _ => Err(format!(
+                "Unknown shift method {name}, expected sinc, lagrange or farrow"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fill {
+    Zero,
+    Circular,
+}
+
+impl Fill {
+    pub fn parse(name: &str) -> Result<Fill, String> {
+        match name {
+            "zero" => Ok(Fill::Zero),
+            "circular" => Ok(Fill::Circular),
+            _ => Err(format!("Unknown fill {name}, expected zero or circular")),
+        }
+    }
+}
+
+// Blackman-windowed sinc, taps for offsets -SHIFT_HALF_WIDTH..=SHIFT_HALF_WIDTH
+fn sinc_kernel(fraction: f64) -> Vec<f64> {
+    let span = (SHIFT_HALF_WIDTH + 1) as f64;
+    let mut kernel: Vec<f64> = (-SHIFT_HALF_WIDTH..=SHIFT_HALF_WIDTH)
+        .map(|k| {
+            let t = k as f64 - fraction;
+            let sinc = (std::f64::consts::PI * t).sin() / (std::f64::consts::PI * t);
+            let phase = std::f64::consts::PI * t / span;
+            sinc * (0.42 + 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos())
+        })
+        .collect();
+    let gain: f64 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|h| *h /= gain);
+    kernel
+}
+
+/// Farrow coefficients: row k holds the polynomial in the fraction, from the
+/// lowest power, of the Lagrange basis function for the node k of FARROW_NODES
+fn farrow_coefficients() -> Vec<Vec<f64>> {
+    FARROW_NODES
+        .map(|k| {
+            FARROW_NODES.filter(|&i| i != k).fold(vec![1.0], |poly, i| {
+                // Умножение на (d - i) / (k - i)
+                let scale = 1.0 / (k - i) as f64;
+                let mut next = vec![0.0; poly.len() + 1];
+                for (m, c) in poly.iter().enumerate() {
+                    next[m + 1] += c * scale;
+                    next[m] -= c * i as f64 * scale;
+                }
+                next
+            })
+        })
+        .collect()
+}
+
+/// Delays signal by `delay` samples (negative advances it) keeping its length.
+/// The fractional part is interpolated by Blackman-windowed sinc, cubic
+/// Lagrange or Farrow structure of fifth-order Lagrange; samples outside the
+/// signal are zeros or wrap around
+pub fn shift(signal: &[f64], delay: f64, method: ShiftMethod, fill: Fill) -> Vec<f64> {
+    let length = signal.len() as isize;
+    let sample = |i: isize| match fill {
+        _ if (0..length).contains(&i) => signal[i as usize],
+        Fill::Zero => 0.0,
+        Fill::Circular => signal[i.rem_euclid(length) as usize],
+    };
+
+    // y[n] = sum_j taps[j] * x[n - first - j]
+    let (first, taps) = match method {
+        ShiftMethod::Sinc => {
+            let whole = delay.round();
+            let fraction = delay - whole;
+            if fraction.abs() < 1e-9 {
+                (whole as isize, vec![1.0])
+            } else {
+                (whole as isize - SHIFT_HALF_WIDTH, sinc_kernel(fraction))
+            }
+        }
+        ShiftMethod::Lagrange => {
+            let whole = delay.floor();
+            let weights = crate::delay::lagrange_weights(delay - whole);
+            (whole as isize - 1, weights.to_vec())
+        }
+        ShiftMethod::Farrow => {
+            let whole = delay.floor();
+            let fraction = delay - whole;
+            // Полиномы по дробной части считаем схемой Горнера
+            let taps = farrow_coefficients()
+                .iter()
+                .map(|poly| poly.iter().rev().fold(0.0, |acc, c| acc * fraction + c))
+                .collect();
+            (whole as isize + FARROW_NODES.start(), taps)
+        }
+    };
+
+    (0..length)
+        .map(|n| {
+            taps.iter()
+                .enumerate()
+                .map(|(j, h)| h * sample(n - first - j as isize))
+                .sum()
+        })
+        .collect()
+}
+
+/// Shift with windowed sinc and zero fill
+pub fn shift_signal(signal: &[f64], delay: f64) -> Vec<f64> {
+    shift(signal, delay, ShiftMethod::Sinc, Fill::Zero)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvolveMode {
+    Full,
+    Same,
+    Valid,
+}
+
+impl ConvolveMode {
+    pub fn parse(name: &str) -> Result<ConvolveMode, String> {
+        match name {
+            "full" => Ok(ConvolveMode::Full),
+            "same" => Ok(ConvolveMode::Same),
+            "valid" => Ok(ConvolveMode::Valid),
+            _ => Err(format!(
+                "Unknown convolution mode {name}, expected full, same or valid"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvolveMethod {
+    Auto,
+    Direct,
+    OverlapAdd,
+    OverlapSave,
+}
+
+impl ConvolveMethod {
+    pub fn parse(name: &str) -> Result<ConvolveMethod, String> {
+        match name {
+            "auto" => Ok(ConvolveMethod::Auto),
+            "direct" => Ok(ConvolveMethod::Direct),
+            "overlap-add" => Ok(ConvolveMethod::OverlapAdd),
+            "overlap-save" => Ok(ConvolveMethod::OverlapSave),
+            _ => Err(format!(
+                "Unknown convolution method {name}, expected auto, direct, overlap-add or overlap-save"
+            )),
+        }
+    }
+
+    /// Picks direct or overlap-add method by estimated number of operations
+    pub fn resolve(self, signal_length: usize, kernel_length: usize) -> ConvolveMethod {
+        if self != ConvolveMethod::Auto {
+            return self;
+        }
+        let (long, short) = (
+            signal_length.max(kernel_length),
+            signal_length.min(kernel_length),
+        );
+        if short == 0 {
+            return ConvolveMethod::Direct;
+        }
+        let fft_size = fft_block_size(short);
+        let blocks = long.div_ceil(fft_size - short + 1);
+        // Прямой и обратный БПФ на блок плюс умножение спектров
+        let fft_cost = blocks * fft_size * (2 * fft_size.ilog2() as usize + 1);
+        if long * short <= fft_cost {
+            ConvolveMethod::Direct
+        } else {
+            ConvolveMethod::OverlapAdd
+        }
+    }
+}
+
+// Размер БПФ для блочной свёртки: степень двойки, в несколько раз длиннее ядра
+fn fft_block_size(kernel_length: usize) -> usize {
+    (4 * kernel_length).next_power_of_two().max(64)
+}
+
+fn convolve_direct(signal: &[f64], kernel: &[f64]) -> Vec<f64> {
+    let mut sig_new = vec![0.0; signal.len() + kernel.len() - 1];
+    for (i, x) in signal.iter().enumerate() {
+        for (k, h) in kernel.iter().enumerate() {
+            sig_new[i + k] += x * h;
+        }
+    }
+    sig_new
+}
+
+struct BlockConvolver {
+    fft_size: usize,
+    kernel_spectrum: Vec<Complex<f64>>,
+    forward: Arc<dyn Fft<f64>>,
+    inverse: Arc<dyn Fft<f64>>,
+}
+
+impl BlockConvolver {
+    fn new(kernel: &[f64]) -> BlockConvolver {
+        let fft_size = fft_block_size(kernel.len());
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(fft_size);
+        let inverse = planner.plan_fft_inverse(fft_size);
+
+        let mut kernel_spectrum = vec![Complex::new(0.0, 0.0); fft_size];
+        for (c, h) in kernel_spectrum.iter_mut().zip(kernel.iter()) {
+            c.re = *h;
+        }
+        forward.process(&mut kernel_spectrum);
+
+        BlockConvolver {
+            fft_size,
+            kernel_spectrum,
+            forward,
+            inverse,
+        }
+    }
+
+    // Круговая свёртка блока (дополненного нулями до fft_size) с ядром
+    fn circular(&self, block: &[f64]) -> Vec<f64> {
+        let mut buffer = vec![Complex::new(0.0, 0.0); self.fft_size];
+        for (c, x) in buffer.iter_mut().zip(block.iter()) {
+            c.re = *x;
+        }
+        self.forward.process(&mut buffer);
+        for (c, h) in buffer.iter_mut().zip(self.kernel_spectrum.iter()) {
+            *c *= h;
+        }
+        self.inverse.process(&mut buffer);
+        let scale = 1.0 / self.fft_size as f64;
+        buffer.iter().map(|c| c.re * scale).collect()
+    }
+}
+
+fn convolve_overlap_add(signal: &[f64], kernel: &[f64]) -> Vec<f64> {
+    let convolver = BlockConvolver::new(kernel);
+    let step = convolver.fft_size - kernel.len() + 1;
+    let mut sig_new = vec![0.0; signal.len() + kernel.len() - 1];
+
+    for (n, block) in signal.chunks(step).enumerate() {
+        let start = n * step;
+        let part = convolver.circular(block);
+        let length = (block.len() + kernel.len() - 1).min(sig_new.len() - start);
+        for (y, v) in sig_new[start..start + length].iter_mut().zip(part.iter()) {
+            *y += v;
+        }
+    }
+    sig_new
+}
+
+fn convolve_overlap_save(signal: &[f64], kernel: &[f64]) -> Vec<f64> {
+    let convolver = BlockConvolver::new(kernel);
+    let overlap = kernel.len() - 1;
+    let step = convolver.fft_size - overlap;
+    let length = signal.len() + overlap;
+
+    // Спереди нули на длину перекрытия, сзади до целого числа блоков
+    let mut padded = vec![0.0; overlap];
+    padded.extend_from_slice(signal);
+    padded.resize(overlap + length.div_ceil(step) * step, 0.0);
+
+    let mut sig_new: Vec<f64> = Vec::with_capacity(length);
+    let mut start = 0;
+    while sig_new.len() < length {
+        let part = convolver
+            .circular(&padded[start..start + convolver.fft_size.min(padded.len() - start)]);
+        // Первые overlap отсчётов испорчены круговым заворотом
+        sig_new.extend_from_slice(&part[overlap..]);
+        start += step;
+    }
+    sig_new.truncate(length);
+    sig_new
+}
+
+/// Convolution with numpy-like output modes, the method is picked
+/// automatically for `ConvolveMethod::Auto`
+pub fn convolve(
+    signal: &[f64],
+    kernel: &[f64],
+    mode: ConvolveMode,
+    method: ConvolveMethod,
+) -> Vec<f64> {
+    if signal.is_empty() || kernel.is_empty() {
+        return Vec::new();
+    }
+    // Свёртка коммутативна, блоками режем более длинный сигнал
+    let (long, short) = if signal.len() >= kernel.len() {
+        (signal, kernel)
+    } else {
+        (kernel, signal)
+    };
+
+    let full = match method.resolve(signal.len(), kernel.len()) {
+        ConvolveMethod::OverlapAdd => convolve_overlap_add(long, short),
+        ConvolveMethod::OverlapSave => convolve_overlap_save(long, short),
+        _ => convolve_direct(long, short),
+    };
+
+    match mode {
+        ConvolveMode::Full => full,
+        ConvolveMode::Same => {
+            let start = (short.len() - 1) / 2;
+            full[start..start + long.len()].to_vec()
+        }
+        ConvolveMode::Valid => {
+            let start = short.len() - 1;
+            full[start..long.len()].to_vec()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_match_length() {
+        let sig1 = [1.0, 2.0, 3.0];
+        let sig2 = [4.0, 5.0];
+        assert_eq!(
+            match_length(&sig1, &sig2, LengthPolicy::Shortest),
+            (vec![1.0, 2.0], vec![4.0, 5.0])
+        );
+        assert_eq!(
+            match_length(&sig1, &sig2, LengthPolicy::Longest),
+            (vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 0.0])
+        );
+        assert_eq!(
+            match_length(&sig2, &sig1, LengthPolicy::First),
+            (vec![4.0, 5.0], vec![1.0, 2.0])
+        );
+        assert!(LengthPolicy::parse("second").is_err());
+    }
+
+    #[test]
+    fn test_shift_signal_integer() {
+        let sig = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(shift_signal(&sig, 1.0), vec![0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(shift_signal(&sig, -2.0), vec![3.0, 4.0, 0.0, 0.0]);
+        assert_eq!(shift_signal(&sig, 0.0), sig.to_vec());
+    }
+
+    #[test]
+    fn test_shift_signal_fractional() {
+        // Сдвиг синуса на 2.3 отсчёта равен сдвигу фазы, фаза в градусах
+        let freq = 200.0;
+        let sig = crate::generate::create_sine(freq, 0.0, 0.1, 1.0);
+        let delay = 2.3;
+        let phase = -360.0 * freq * delay / crate::signal::SAMPLE_RATE as f64;
+        let expected = crate::generate::create_sine(freq, phase, 0.1, 1.0);
+        let result = shift_signal(&sig, delay);
+        assert_eq!(result.len(), sig.len());
+        for (r, e) in result[50..950].iter().zip(expected[50..950].iter()) {
+            assert!((r - e).abs() < 1e-3, "{r} {e}");
+        }
+    }
+
+    #[test]
+    fn test_shift_methods() {
+        let freq = 200.0;
+        let sig = crate::generate::create_sine(freq, 0.0, 0.1, 1.0);
+        for delay in [2.3, -0.7, 1.5] {
+            let phase = -360.0 * freq * delay / crate::signal::SAMPLE_RATE as f64;
+            let expected = crate::generate::create_sine(freq, phase, 0.1, 1.0);
+            for (method, tolerance) in [
+                (ShiftMethod::Sinc, 1e-3),
+                (ShiftMethod::Lagrange, 1e-3),
+                (ShiftMethod::Farrow, 1e-5),
+            ] {
+                let result = shift(&sig, delay, method, Fill::Zero);
+                for (r, e) in result[50..950].iter().zip(expected[50..950].iter()) {
+                    assert!((r - e).abs() < tolerance, "{method:?} {delay} {r} {e}");
+                }
+            }
+        }
+        // Целый сдвиг любым методом точен
+        let sig = [1.0, 2.0, 3.0, 4.0, 5.0];
+        for method in [
+            ShiftMethod::Sinc,
+            ShiftMethod::Lagrange,
+            ShiftMethod::Farrow,
+        ] {
+            let result = shift(&sig, 2.0, method, Fill::Zero);
+            for (r, e) in result.iter().zip([0.0, 0.0, 1.0, 2.0, 3.0]) {
+                assert!((r - e).abs() < 1e-12, "{method:?}");
+            }
+        }
+        assert_eq!(ShiftMethod::parse("farrow"), Ok(ShiftMethod::Farrow));
+        assert!(ShiftMethod::parse("cubic").is_err());
+        assert_eq!(Fill::parse("circular"), Ok(Fill::Circular));
+        assert!(Fill::parse("mirror").is_err());
+    }
+
+    #[test]
+    fn test_shift_circular() {
+        let sig = [1.0, 2.0, 3.0, 4.0];
+        let rotated = shift(&sig, 1.0, ShiftMethod::Sinc, Fill::Circular);
+        assert_eq!(rotated, vec![4.0, 1.0, 2.0, 3.0]);
+        let rotated = shift(&sig, -6.0, ShiftMethod::Lagrange, Fill::Circular);
+        assert_eq!(rotated, vec![3.0, 4.0, 1.0, 2.0]);
+
+        // Целое число периодов: циклический сдвиг точен и у краёв
+        let freq = 100.0;
+        let sig = crate::generate::create_sine(freq, 0.0, 0.1, 1.0);
+        let delay = 3.25;
+        let phase = -360.0 * freq * delay / crate::signal::SAMPLE_RATE as f64;
+        let expected = crate::generate::create_sine(freq, phase, 0.1, 1.0);
+        for method in [
+            ShiftMethod::Sinc,
+            ShiftMethod::Lagrange,
+            ShiftMethod::Farrow,
+        ] {
+            let result = shift(&sig, delay, method, Fill::Circular);
+            for (r, e) in result.iter().zip(expected.iter()) {
+                assert!((r - e).abs() < 1e-3, "{method:?} {r} {e}");
+            }
+        }
+    }
+
     #[test]
     fn test_add_signals_basic() {
         let sig1 = vec![1.0, 2.0, 3.0];
@@ -313,4 +789,112 @@ mod tests {
         });
         // В production лучше валидировать входные данные
     }
+
+    #[test]
+    fn test_convolve_modes() {
+        let signal = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let kernel = [1.0, 0.0, -1.0];
+        let direct = ConvolveMethod::Direct;
+
+        // numpy.convolve([1, 2, 3, 4, 5], [1, 0, -1], mode)
+        assert_eq!(
+            convolve(&signal, &kernel, ConvolveMode::Full, direct),
+            vec![1.0, 2.0, 2.0, 2.0, 2.0, -4.0, -5.0]
+        );
+        assert_eq!(
+            convolve(&signal, &kernel, ConvolveMode::Same, direct),
+            vec![2.0, 2.0, 2.0, 2.0, -4.0]
+        );
+        assert_eq!(
+            convolve(&signal, &kernel, ConvolveMode::Valid, direct),
+            vec![2.0, 2.0, 2.0]
+        );
+        // Порядок аргументов не важен
+        assert_eq!(
+            convolve(&kernel, &signal, ConvolveMode::Valid, direct),
+            vec![2.0, 2.0, 2.0]
+        );
+        assert!(convolve(&[], &kernel, ConvolveMode::Full, direct).is_empty());
+    }
+
+    #[test]
+    fn test_convolve_even_kernel_same() {
+        // numpy.convolve([1, 2, 3], [1, 1], 'same') == [1, 3, 5]
+        let result = convolve(
+            &[1.0, 2.0, 3.0],
+            &[1.0, 1.0],
+            ConvolveMode::Same,
+            ConvolveMethod::Direct,
+        );
+        assert_eq!(result, vec![1.0, 3.0, 5.0]);
+    }
+
+    #[test]
+    fn test_convolve_fft_methods_match_direct() {
+        let signal: Vec<f64> = (0..5000)
+            .map(|i| ((i * 7919) % 101) as f64 / 50.0 - 1.0)
+            .collect();
+        for kernel_length in [1, 2, 63, 300, 1999, 2500] {
+            let kernel: Vec<f64> = (0..kernel_length)
+                .map(|i| (-(i as f64) / 300.0).exp() * if i % 2 == 0 { 1.0 } else { -0.5 })
+                .collect();
+            for mode in [ConvolveMode::Full, ConvolveMode::Same, ConvolveMode::Valid] {
+                let expected = convolve(&signal, &kernel, mode, ConvolveMethod::Direct);
+                for method in [ConvolveMethod::OverlapAdd, ConvolveMethod::OverlapSave] {
+                    let result = convolve(&signal, &kernel, mode, method);
+                    assert_eq!(result.len(), expected.len());
+                    for (r, e) in result.iter().zip(expected.iter()) {
+                        assert!((r - e).abs() < 1e-9, "{method:?} {mode:?} {kernel_length}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_convolve_method_resolve() {
+        assert_eq!(
+            ConvolveMethod::Auto.resolve(10000, 5),
+            ConvolveMethod::Direct
+        );
+        assert_eq!(
+            ConvolveMethod::Auto.resolve(100000, 20000),
+            ConvolveMethod::OverlapAdd
+        );
+        assert_eq!(
+            ConvolveMethod::Auto.resolve(20000, 100000),
+            ConvolveMethod::OverlapAdd
+        );
+        assert_eq!(
+            ConvolveMethod::OverlapSave.resolve(10, 5),
+            ConvolveMethod::OverlapSave
+        );
+    }
+
+    #[test]
+    fn test_convolve_parse() {
+        assert_eq!(ConvolveMode::parse("same"), Ok(ConvolveMode::Same));
+        assert!(ConvolveMode::parse("circular").is_err());
+        assert_eq!(
+            ConvolveMethod::parse("overlap-save"),
+            Ok(ConvolveMethod::OverlapSave)
+        );
+        assert!(ConvolveMethod::parse("fft").is_err());
+    }
+
+    #[test]
+    fn test_convolve_with_move_average_kernel() {
+        // Режим valid с ядром из единиц совпадает с move_average
+        let signal = [1.0, 4.0, 2.0, 8.0, 5.0, 7.0];
+        let kernel = [1.0 / 3.0; 3];
+        let result = convolve(
+            &signal,
+            &kernel,
+            ConvolveMode::Valid,
+            ConvolveMethod::OverlapAdd,
+        );
+        for (r, e) in result.iter().zip(move_average(&signal, 3).iter()) {
+            assert!((r - e).abs() < 1e-12);
+        }
+    }
 }
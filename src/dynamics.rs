@@ -0,0 +1,538 @@
+use std::collections::VecDeque;
+
+// Нижняя граница уровня, чтобы тишина не давала минус бесконечность
+const LEVEL_FLOOR: f64 = -200.0;
+// Постоянная времени усреднения квадрата сигнала в RMS-детекторе
+const RMS_TIME: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Detector {
+    Peak,
+    Rms,
+}
+
+impl Detector {
+    pub fn parse(name: &str) -> Result<Detector, String> {
+        match name {
+            "peak" => Ok(Detector::Peak),
+            "rms" => Ok(Detector::Rms),
+            _ => Err(format!("Unknown detector {name}, expected peak or rms")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DynamicsKind {
+    Compressor,
+    Expander,
+    Gate,
+}
+
+// Коэффициент однополюсного сглаживания с постоянной времени seconds
+fn coefficient(seconds: f64, fs: f64) -> f64 {
+    if seconds > 0.0 {
+        (-1.0 / (seconds * fs)).exp()
+    } else {
+        0.0
+    }
+}
+
+fn to_db(value: f64) -> f64 {
+    (20.0 * value.log10()).max(LEVEL_FLOOR)
+}
+
+fn from_db(value: f64) -> f64 {
+    10f64.powf(value / 20.0)
+}
+
+/// Detector level in dB: instantaneous magnitude for peak, mean square smoothed
+/// over 10 ms for RMS (a full-scale sine reads -3 dB)
+pub fn detect(sidechain: &[f64], fs: f64, detector: Detector) -> Vec<f64> {
+    match detector {
+        Detector::Peak => sidechain.iter().map(|x| to_db(x.abs())).collect(),
+        Detector::Rms => {
+            let c = coefficient(RMS_TIME, fs);
+            let mut power = 0.0;
+            sidechain
+                .iter()
+                .map(|x| {
+                    power = c * power + (1.0 - c) * x * x;
+                    (10.0 * power.log10()).max(LEVEL_FLOOR)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Feed-forward compressor, downward expander or noise gate.
+/// Levels are in dB, times in seconds; the gain is computed from the static
+/// curve with a soft knee of width `knee` and smoothed in dB, attack is used
+/// while the detector level rises. Expander and gate attenuate down to `range`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dynamics {
+    pub kind: DynamicsKind,
+    pub threshold: f64,
+    pub ratio: f64,
+    pub knee: f64,
+    pub attack: f64,
+    pub release: f64,
+    pub makeup: f64,
+    pub range: f64,
+    pub detector: Detector,
+}
+
+impl Dynamics {
+    fn check(&self) -> Result<(), String> {
+        if self.ratio < 1.0 {
+            return Err(format!("Ratio must be at least 1, got {}", self.ratio));
+        }
+        if self.knee < 0.0 || self.attack < 0.0 || self.release < 0.0 {
+            return Err("Knee, attack and release must not be negative".to_string());
+        }
+        if self.range > 0.0 {
+            return Err(format!("Range must not be positive, got {}", self.range));
+        }
+        Ok(())
+    }
+
+    /// Static gain in dB for the detector level in dB
+    pub fn static_gain(&self, level: f64) -> f64 {
+        let over = level - self.threshold;
+        let half = self.knee / 2.0;
+        match self.kind {
+            DynamicsKind::Compressor => {
+                let slope = 1.0 / self.ratio - 1.0;
+                if over <= -half {
+                    0.0
+                } else if over < half {
+                    slope * (over + half).powi(2) / (2.0 * self.knee)
+                } else {
+                    slope * over
+                }
+            }
+            DynamicsKind::Expander => {
+                let slope = self.ratio - 1.0;
+                let gain = if over >= half {
+                    0.0
+                } else if over > -half {
+                    -slope * (over - half).powi(2) / (2.0 * self.knee)
+                } else {
+                    slope * over
+                };
+                gain.max(self.range)
+            }
+            DynamicsKind::Gate => {
+                if over >= 0.0 {
+                    0.0
+                } else {
+                    self.range
+                }
+            }
+        }
+    }
+
+    /// Smoothed gain in dB without makeup
+    pub fn gain(&self, sidechain: &[f64], fs: f64) -> Vec<f64> {
+        let attack = coefficient(self.attack, fs);
+        let release = coefficient(self.release, fs);
+        let mut state = 0.0;
+        detect(sidechain, fs, self.detector)
+            .into_iter()
+            .map(|level| {
+                let target = self.static_gain(level);
+                // Компрессор на атаке уменьшает усиление, экспандер и гейт открываются
+                let rising = match self.kind {
+                    DynamicsKind::Compressor => target < state,
+                    _ => target > state,
+                };
+                let c = if rising { attack } else { release };
+                state = c * state + (1.0 - c) * target;
+                state
+            })
+            .collect()
+    }
+
+    /// Process the signal, the level is detected on `sidechain` if given.
+    /// Returns the output and the applied gain in dB including makeup.
+    pub fn process(
+        &self,
+        signal: &[f64],
+        sidechain: Option<&[f64]>,
+        fs: f64,
+    ) -> Result<(Vec<f64>, Vec<f64>), String> {
+        self.check()?;
+        let sidechain = sidechain_for(signal, sidechain);
+        let gain: Vec<f64> = self
+            .gain(&sidechain, fs)
+            .into_iter()
+            .map(|g| g + self.makeup)
+            .collect();
+        let output = signal
+            .iter()
+            .zip(gain.iter())
+            .map(|(x, g)| x * from_db(*g))
+            .collect();
+        Ok((output, gain))
+    }
+}
+
+// Управляющий сигнал той же длины, что и обрабатываемый
+fn sidechain_for(signal: &[f64], sidechain: Option<&[f64]>) -> Vec<f64> {
+    match sidechain {
+        Some(sidechain) => {
+            let mut sc = sidechain[..sidechain.len().min(signal.len())].to_vec();
+            sc.resize(signal.len(), 0.0);
+            sc
+        }
+        None => signal.to_vec(),
+    }
+}
+
+// Минимум в скользящем окне [n, n + window) за O(1) на отсчёт
+fn forward_min(values: &[f64], window: usize) -> Vec<f64> {
+    let mut deque: VecDeque<usize> = VecDeque::new();
+    let mut result = vec![0.0; values.len()];
+    for i in (0..values.len()).rev() {
+        while deque.back().is_some_and(|&j| values[j] >= values[i]) {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+        while deque.front().is_some_and(|&j| j >= i + window) {
+            deque.pop_front();
+        }
+        result[i] = values[deque[0]];
+    }
+    result
+}
+
+/// Brickwall limiter with `lookahead` seconds: the required gain is taken as
+/// the minimum over the lookahead window and averaged over the same window,
+/// so the gain reaches it before the peak and the output never exceeds
+/// `ceiling` dBFS. The lookahead delay is compensated.
+pub fn limit(
+    signal: &[f64],
+    sidechain: Option<&[f64]>,
+    fs: f64,
+    ceiling: f64,
+    lookahead: f64,
+    release: f64,
+) -> Result<(Vec<f64>, Vec<f64>), String> {
+    if lookahead < 0.0 || release < 0.0 {
+        return Err("Lookahead and release must not be negative".to_string());
+    }
+    let window = ((lookahead * fs).round() as usize).max(1);
+    let limit = from_db(ceiling);
+    let required: Vec<f64> = sidechain_for(signal, sidechain)
+        .iter()
+        .map(|x| {
+            if x.abs() > limit {
+                limit / x.abs()
+            } else {
+                1.0
+            }
+        })
+        .collect();
+    let minimum = forward_min(&required, window);
+
+    // Среднее минимумов за предыдущие window отсчётов не больше требуемого усиления
+    let c = coefficient(release, fs);
+    let mut sum = 0.0;
+    let mut state = 1.0;
+    let gain: Vec<f64> = (0..minimum.len())
+        .map(|n| {
+            sum += minimum[n];
+            // До начала сигнала минимумы считаются единичными
+            sum -= if n >= window {
+                minimum[n - window]
+            } else {
+                1.0
+            };
+            let smooth = (sum + window as f64) / window as f64;
+            state = if smooth < state {
+                smooth
+            } else {
+                c * state + (1.0 - c) * smooth
+            };
+            state
+        })
+        .collect();
+
+    let output = signal.iter().zip(gain.iter()).map(|(x, g)| x * g).collect();
+    Ok((output, gain.into_iter().map(to_db).collect()))
+}
+
+/// Streamable automatic gain control: a peak envelope with `attack` and
+/// `decay` time constants in seconds is held for `hold` seconds after each rise,
+/// the gain brings the envelope to `target` dBFS and never exceeds `max_gain` dB.
+/// The gain starts at 0 dB, state is kept between blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Agc {
+    target: f64,
+    max_gain: f64,
+    attack: f64,
+    decay: f64,
+    hold: usize,
+    envelope: f64,
+    hold_left: usize,
+}
+
+impl Agc {
+    pub fn new(
+        target: f64,
+        max_gain: f64,
+        attack: f64,
+        decay: f64,
+        hold: f64,
+        fs: f64,
+    ) -> Result<Agc, String> {
+        if attack < 0.0 || decay < 0.0 || hold < 0.0 {
+            return Err("Attack, decay and hold must not be negative".to_string());
+        }
+        if max_gain < 0.0 {
+            return Err(format!("Maximum gain must not be negative, got {max_gain}"));
+        }
+        Ok(Agc {
+            target: from_db(target),
+            max_gain: from_db(max_gain),
+            attack: coefficient(attack, fs),
+            decay: coefficient(decay, fs),
+            hold: (hold * fs).round() as usize,
+            envelope: from_db(target),
+            hold_left: 0,
+        })
+    }
+
+    /// Current gain in dB
+    pub fn gain(&self) -> f64 {
+        to_db((self.target / self.envelope).min(self.max_gain))
+    }
+
+    pub fn process_sample(&mut self, x: f64) -> f64 {
+        let level = x.abs();
+        if level > self.envelope {
+            self.envelope = self.attack * self.envelope + (1.0 - self.attack) * level;
+            self.hold_left = self.hold;
+        } else if self.hold_left > 0 {
+            // Удержание: после пика огибающая не спадает, усиление не растёт
+            self.hold_left -= 1;
+        } else {
+            self.envelope = self.decay * self.envelope + (1.0 - self.decay) * level;
+        }
+        x * (self.target / self.envelope).min(self.max_gain)
+    }
+
+    pub fn process(&mut self, block: &[f64]) -> Vec<f64> {
+        block.iter().map(|&x| self.process_sample(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FS: f64 = 10000.0;
+
+    fn compressor() -> Dynamics {
+        Dynamics {
+            kind: DynamicsKind::Compressor,
+            threshold: -20.0,
+            ratio: 4.0,
+            knee: 0.0,
+            attack: 0.01,
+            release: 0.1,
+            makeup: 0.0,
+            range: -80.0,
+            detector: Detector::Peak,
+        }
+    }
+
+    #[test]
+    fn test_static_curves() {
+        let mut comp = compressor();
+        assert_eq!(comp.static_gain(-30.0), 0.0);
+        assert_eq!(comp.static_gain(-8.0), -9.0);
+        // Мягкое колено непрерывно на обеих границах
+        comp.knee = 10.0;
+        assert_eq!(comp.static_gain(-25.0), 0.0);
+        assert!((comp.static_gain(-15.0) + 3.75).abs() < 1e-12);
+        assert!(comp.static_gain(-20.0) < 0.0 && comp.static_gain(-20.0) > -3.75);
+
+        let expander = Dynamics {
+            kind: DynamicsKind::Expander,
+            ratio: 2.0,
+            ..compressor()
+        };
+        assert_eq!(expander.static_gain(-10.0), 0.0);
+        assert_eq!(expander.static_gain(-30.0), -10.0);
+        assert_eq!(expander.static_gain(-150.0), -80.0);
+
+        let gate = Dynamics {
+            kind: DynamicsKind::Gate,
+            ..compressor()
+        };
+        assert_eq!(gate.static_gain(-19.0), 0.0);
+        assert_eq!(gate.static_gain(-21.0), -80.0);
+    }
+
+    #[test]
+    fn test_compressor_attack_and_steady_state() {
+        // Ступенька с -20 дБ до 0 дБ
+        let mut signal = vec![0.1; 5000];
+        signal.extend(vec![1.0; 5000]);
+        let (output, gain) = compressor().process(&signal, None, FS).unwrap();
+        assert!(gain[4999].abs() < 1e-9);
+        // За время атаки усиление проходит 63% пути к -15 дБ
+        let expected = -15.0 * (1.0 - (-1.0f64).exp());
+        assert!((gain[5099] - expected).abs() < 0.1, "{}", gain[5099]);
+        assert!((gain[9999] + 15.0).abs() < 1e-6);
+        assert!((output[9999] - from_db(-15.0)).abs() < 1e-6);
+
+        // RMS-детектор на синусе: уровень -3 дБ, усиление (1/4 - 1) * 17 дБ
+        let sine: Vec<f64> = (0..10000)
+            .map(|n| (2.0 * std::f64::consts::PI * 1000.0 * n as f64 / FS).sin())
+            .collect();
+        let comp = Dynamics {
+            detector: Detector::Rms,
+            makeup: 6.0,
+            ..compressor()
+        };
+        let (_, gain) = comp.process(&sine, None, FS).unwrap();
+        let expected = -0.75 * (20.0 - 3.0103) + 6.0;
+        assert!((gain[9999] - expected).abs() < 0.1, "{}", gain[9999]);
+
+        let bad = Dynamics {
+            ratio: 0.5,
+            ..compressor()
+        };
+        assert!(bad.process(&sine, None, FS).is_err());
+        assert_eq!(Detector::parse("rms"), Ok(Detector::Rms));
+        assert!(Detector::parse("lufs").is_err());
+    }
+
+    #[test]
+    fn test_gate_and_expander() {
+        // Тихий шум -40 дБ между громкими участками
+        let mut signal = vec![0.5; 2000];
+        signal.extend(vec![0.01; 3000]);
+        signal.extend(vec![0.5; 2000]);
+        let gate = Dynamics {
+            kind: DynamicsKind::Gate,
+            threshold: -30.0,
+            attack: 0.001,
+            release: 0.01,
+            ..compressor()
+        };
+        let (output, gain) = gate.process(&signal, None, FS).unwrap();
+        assert_eq!(output[1999], 0.5);
+        assert!((gain[4999] + 80.0).abs() < 0.1);
+        // Гейт открывается за время атаки
+        assert!((output[6999] - 0.5).abs() < 1e-6);
+
+        let expander = Dynamics {
+            kind: DynamicsKind::Expander,
+            threshold: -30.0,
+            ratio: 3.0,
+            release: 0.01,
+            ..compressor()
+        };
+        let (_, gain) = expander.process(&signal, None, FS).unwrap();
+        assert!((gain[4999] + 20.0).abs() < 0.1, "{}", gain[4999]);
+    }
+
+    #[test]
+    fn test_sidechain_ducking() {
+        let music = vec![0.1; 6000];
+        let mut voice = vec![0.0; 2000];
+        voice.extend(vec![1.0; 2000]);
+        voice.extend(vec![0.0; 2000]);
+        let (output, _) = compressor().process(&music, Some(&voice), FS).unwrap();
+        assert_eq!(output[1999], 0.1);
+        assert!((output[3999] - 0.1 * from_db(-15.0)).abs() < 1e-6);
+        assert!(output[5999] > 0.1 * from_db(-3.0));
+
+        // Короткий управляющий сигнал дополняется тишиной
+        let (output, _) = compressor()
+            .process(&music, Some(&voice[..3000]), FS)
+            .unwrap();
+        assert_eq!(output.len(), 6000);
+        assert!(output[5999] > 0.1 * from_db(-1.0));
+    }
+
+    #[test]
+    fn test_limiter_is_brickwall() {
+        let signal: Vec<f64> = (0..20000)
+            .map(|n| {
+                let t = n as f64 / FS;
+                let burst = if (n / 1000) % 4 == 1 { 3.0 } else { 0.3 };
+                burst * (2.0 * std::f64::consts::PI * 440.0 * t).sin()
+            })
+            .collect();
+        let ceiling = -1.0;
+        let (output, gain) = limit(&signal, None, FS, ceiling, 0.005, 0.05).unwrap();
+        let peak = output.iter().fold(0.0f64, |m, x| m.max(x.abs()));
+        assert!(peak <= from_db(ceiling) + 1e-12, "{peak}");
+        assert!(peak > from_db(ceiling) - 0.01);
+        // Далеко от всплесков сигнал не меняется
+        assert_eq!(gain[500], 0.0);
+        assert_eq!(output[900], signal[900]);
+        assert!(limit(&signal, None, FS, ceiling, -0.005, 0.05).is_err());
+    }
+
+    #[test]
+    fn test_forward_min() {
+        let values = [5.0, 3.0, 4.0, 1.0, 2.0, 6.0];
+        assert_eq!(forward_min(&values, 1), values.to_vec());
+        assert_eq!(forward_min(&values, 3), vec![3.0, 1.0, 1.0, 1.0, 2.0, 6.0]);
+    }
+
+    #[test]
+    fn test_agc_levels_sessions() {
+        // Два участка с разницей 40 дБ выравниваются к -20 dBFS
+        let sine = |amplitude: f64| -> Vec<f64> {
+            (0..30000)
+                .map(|n| amplitude * (2.0 * std::f64::consts::PI * 200.0 * n as f64 / FS).sin())
+                .collect()
+        };
+        let mut signal = sine(0.005);
+        signal.extend(sine(0.5));
+        let mut agc = Agc::new(-20.0, 40.0, 0.005, 0.5, 0.1, FS).unwrap();
+        assert_eq!(agc.gain(), 0.0);
+        let output = agc.process(&signal);
+
+        let peak = |range: std::ops::Range<usize>| {
+            output[range].iter().fold(0.0f64, |m, x| m.max(x.abs()))
+        };
+        assert!((to_db(peak(25000..30000)) + 20.0).abs() < 0.5);
+        assert!((to_db(peak(55000..60000)) + 20.0).abs() < 0.5);
+        assert!((agc.gain() + 14.0).abs() < 0.5, "{}", agc.gain());
+    }
+
+    #[test]
+    fn test_agc_max_gain_hold_and_streaming() {
+        // Тишина усиливается не больше чем на max_gain
+        let mut agc = Agc::new(-20.0, 12.0, 0.005, 0.05, 0.2, FS).unwrap();
+        agc.process(&vec![1e-4; 10000]);
+        assert!((agc.gain() - 12.0).abs() < 1e-9);
+
+        // После громкого всплеска усиление не растёт во время удержания
+        let mut agc = Agc::new(-20.0, 40.0, 0.001, 0.05, 0.2, FS).unwrap();
+        agc.process(&vec![1.0; 500]);
+        let after_burst = agc.gain();
+        agc.process(&vec![0.01; 1900]);
+        assert_eq!(agc.gain(), after_burst);
+        agc.process(&vec![0.01; 5000]);
+        assert!(agc.gain() > after_burst + 20.0);
+
+        // Обработка блоками совпадает с обработкой целиком
+        let signal: Vec<f64> = (0..5000)
+            .map(|n| ((n * 37 % 101) as f64 - 50.0) / 50.0)
+            .collect();
+        let mut whole = Agc::new(-10.0, 20.0, 0.01, 0.1, 0.05, FS).unwrap();
+        let expected = whole.process(&signal);
+        let mut blocks = Agc::new(-10.0, 20.0, 0.01, 0.1, 0.05, FS).unwrap();
+        let streamed: Vec<f64> = signal.chunks(333).flat_map(|b| blocks.process(b)).collect();
+        assert_eq!(streamed, expected);
+
+        assert!(Agc::new(-20.0, -1.0, 0.01, 0.1, 0.0, FS).is_err());
+    }
+}
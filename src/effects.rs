@@ -0,0 +1,449 @@
+use std::f64::consts::PI;
+
+use crate::delay::{DelayLine, Interpolation};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Sine,
+    Triangle,
+}
+
+impl Waveform {
+    pub fn parse(name: &str) -> Result<Waveform, String> {
+        match name {
+            "sine" => Ok(Waveform::Sine),
+            "triangle" => Ok(Waveform::Triangle),
+            _ => Err(format!(
+                "Unknown waveform {name}, expected sine or triangle"
+            )),
+        }
+    }
+}
+
+/// Low-frequency oscillator with values in [-1, 1], `phase` in cycles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lfo {
+    pub rate: f64,
+    pub waveform: Waveform,
+    pub phase: f64,
+}
+
+impl Lfo {
+    pub fn value(&self, t: f64) -> f64 {
+        let cycle = (self.rate * t + self.phase).rem_euclid(1.0);
+        match self.waveform {
+            Waveform::Sine => (2.0 * PI * cycle).sin(),
+            // Треугольник в фазе с синусом: 0 в начале периода, 1 на четверти
+            Waveform::Triangle => {
+                1.0 - 4.0
+                    * (cycle - 0.25)
+                        .rem_euclid(1.0)
+                        .min(1.0 - (cycle - 0.25).rem_euclid(1.0))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CombType {
+    Feedforward,
+    Feedback,
+}
+
+impl CombType {
+    pub fn parse(name: &str) -> Result<CombType, String> {
+        match name {
+            "feedforward" => Ok(CombType::Feedforward),
+            "feedback" => Ok(CombType::Feedback),
+            _ => Err(format!(
+                "Unknown comb type {name}, expected feedforward or feedback"
+            )),
+        }
+    }
+}
+
+fn check_feedback(feedback: f64) -> Result<(), String> {
+    if feedback.abs() >= 1.0 {
+        return Err(format!("Feedback must be in (-1, 1), got {feedback}"));
+    }
+    Ok(())
+}
+
+/// Comb filter with `delay` samples: y = x + g x[n - D] (feedforward)
+/// or y = x + g y[n - D] (feedback)
+pub fn comb(
+    signal: &[f64],
+    delay: usize,
+    gain: f64,
+    comb_type: CombType,
+) -> Result<Vec<f64>, String> {
+    if delay == 0 {
+        return Err("Delay must be at least one sample".to_string());
+    }
+    if comb_type == CombType::Feedback {
+        check_feedback(gain)?;
+    }
+    let mut line = DelayLine::new(delay, Interpolation::Linear);
+    Ok(signal
+        .iter()
+        .map(|&x| {
+            let y = x + gain * line.tap(delay - 1);
+            line.push(if comb_type == CombType::Feedback {
+                y
+            } else {
+                x
+            });
+            y
+        })
+        .collect())
+}
+
+/// Echo with repeats every `delay` samples decaying by `feedback`,
+/// `mix` is the level of the repeats
+pub fn echo(signal: &[f64], delay: usize, feedback: f64, mix: f64) -> Result<Vec<f64>, String> {
+    if delay == 0 {
+        return Err("Delay must be at least one sample".to_string());
+    }
+    check_feedback(feedback)?;
+    let mut line = DelayLine::new(delay, Interpolation::Linear);
+    Ok(signal
+        .iter()
+        .map(|&x| {
+            let wet = line.tap(delay - 1);
+            line.push(x + feedback * wet);
+            x + mix * wet
+        })
+        .collect())
+}
+
+/// Chorus: `voices` copies delayed by `delay` ± `depth` seconds, each modulated
+/// by the LFO with its own phase; `mix` is the share of the delayed copies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chorus {
+    pub delay: f64,
+    pub depth: f64,
+    pub lfo: Lfo,
+    pub voices: usize,
+    pub mix: f64,
+}
+
+impl Chorus {
+    pub fn process(
+        &self,
+        signal: &[f64],
+        fs: f64,
+        interpolation: Interpolation,
+    ) -> Result<Vec<f64>, String> {
+        if self.voices == 0 {
+            return Err("Chorus needs at least one voice".to_string());
+        }
+        if self.depth < 0.0 || self.depth > self.delay {
+            return Err(format!(
+                "Depth must be in [0, delay], got {} s for delay {} s",
+                self.depth, self.delay
+            ));
+        }
+        let max_delay = ((self.delay + self.depth) * fs).ceil() as usize + 1;
+        let mut lines = vec![DelayLine::new(max_delay, interpolation); self.voices];
+        Ok(signal
+            .iter()
+            .enumerate()
+            .map(|(n, &x)| {
+                let t = n as f64 / fs;
+                let wet: f64 = lines
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(voice, line)| {
+                        let lfo = Lfo {
+                            phase: self.lfo.phase + voice as f64 / self.voices as f64,
+                            ..self.lfo
+                        };
+                        line.push(x);
+                        line.read((self.delay + self.depth * lfo.value(t)) * fs)
+                    })
+                    .sum::<f64>()
+                    / self.voices as f64;
+                (1.0 - self.mix) * x + self.mix * wet
+            })
+            .collect())
+    }
+}
+
+/// Flanger: short delay swept between `delay` and `delay + 2 depth` seconds
+/// with feedback of the delayed signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flanger {
+    pub delay: f64,
+    pub depth: f64,
+    pub lfo: Lfo,
+    pub feedback: f64,
+    pub mix: f64,
+}
+
+impl Flanger {
+    pub fn process(
+        &self,
+        signal: &[f64],
+        fs: f64,
+        interpolation: Interpolation,
+    ) -> Result<Vec<f64>, String> {
+        check_feedback(self.feedback)?;
+        if self.delay < 0.0 || self.depth < 0.0 {
+            return Err("Delay and depth must not be negative".to_string());
+        }
+        let max_delay = ((self.delay + 2.0 * self.depth) * fs).ceil() as usize + 1;
+        let mut line = DelayLine::new(max_delay, interpolation);
+        let mut wet = 0.0;
+        Ok(signal
+            .iter()
+            .enumerate()
+            .map(|(n, &x)| {
+                line.push(x + self.feedback * wet);
+                let sweep = self.delay + self.depth * (1.0 + self.lfo.value(n as f64 / fs));
+                // Задержка не меньше отсчёта, иначе обратная связь без задержки
+                wet = line.read((sweep * fs).max(1.0));
+                (1.0 - self.mix) * x + self.mix * wet
+            })
+            .collect())
+    }
+}
+
+/// Phaser: chain of first-order allpass filters whose break frequency is swept
+/// between `min_freq` and `max_freq` Hz, mixed with the dry signal to get notches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Phaser {
+    pub stages: usize,
+    pub min_freq: f64,
+    pub max_freq: f64,
+    pub lfo: Lfo,
+    pub feedback: f64,
+    pub mix: f64,
+}
+
+impl Phaser {
+    pub fn process(&self, signal: &[f64], fs: f64) -> Result<Vec<f64>, String> {
+        check_feedback(self.feedback)?;
+        if self.stages == 0 {
+            return Err("Phaser needs at least one stage".to_string());
+        }
+        if self.min_freq <= 0.0 || self.max_freq < self.min_freq || self.max_freq >= fs / 2.0 {
+            return Err(format!(
+                "Frequencies must satisfy 0 < min <= max < {} Hz",
+                fs / 2.0
+            ));
+        }
+        // Состояния (x[n-1], y[n-1]) каждого звена
+        let mut states = vec![(0.0, 0.0); self.stages];
+        let mut wet = 0.0;
+        Ok(signal
+            .iter()
+            .enumerate()
+            .map(|(n, &x)| {
+                // Частота качается по логарифмической шкале
+                let sweep = 0.5 * (1.0 + self.lfo.value(n as f64 / fs));
+                let freq = self.min_freq * (self.max_freq / self.min_freq).powf(sweep);
+                let k = (PI * freq / fs).tan();
+                let a = (k - 1.0) / (k + 1.0);
+                wet = states
+                    .iter_mut()
+                    .fold(x + self.feedback * wet, |input, state| {
+                        let y = a * input + state.0 - a * state.1;
+                        *state = (input, y);
+                        y
+                    });
+                (1.0 - self.mix) * x + self.mix * wet
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{impulse, peak, tone};
+
+    const FS: f64 = 10000.0;
+
+    #[test]
+    fn test_lfo() {
+        let lfo = Lfo {
+            rate: 2.0,
+            waveform: Waveform::Triangle,
+            phase: 0.0,
+        };
+        let expected = [0.0, 1.0, 0.0, -1.0, 0.0, 0.5, -0.5];
+        let times = [0.0, 0.125, 0.25, 0.375, 0.5, 0.0625, 0.4375];
+        for (t, e) in times.iter().zip(expected.iter()) {
+            assert!((lfo.value(*t) - e).abs() < 1e-12, "{t}");
+        }
+        let lfo = Lfo {
+            waveform: Waveform::Sine,
+            phase: 0.25,
+            ..lfo
+        };
+        assert!((lfo.value(0.0) - 1.0).abs() < 1e-12);
+        assert!(Waveform::parse("square").is_err());
+    }
+
+    #[test]
+    fn test_comb_and_echo_impulse_responses() {
+        let result = comb(&impulse(10), 3, 0.5, CombType::Feedforward).unwrap();
+        assert_eq!(
+            result,
+            vec![1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+        );
+
+        let result = comb(&impulse(10), 3, 0.5, CombType::Feedback).unwrap();
+        assert_eq!(
+            result,
+            vec![1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.25, 0.0, 0.0, 0.125]
+        );
+        assert!(comb(&impulse(10), 3, 1.0, CombType::Feedback).is_err());
+        assert!(comb(&impulse(10), 0, 0.5, CombType::Feedforward).is_err());
+        assert_eq!(CombType::parse("feedback"), Ok(CombType::Feedback));
+
+        let result = echo(&impulse(10), 4, 0.5, 0.8).unwrap();
+        assert_eq!(
+            result,
+            vec![1.0, 0.0, 0.0, 0.0, 0.8, 0.0, 0.0, 0.0, 0.4, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_chorus() {
+        let lfo = Lfo {
+            rate: 1.5,
+            waveform: Waveform::Sine,
+            phase: 0.0,
+        };
+        // Без модуляции хорус это смесь с задержанной копией
+        let chorus = Chorus {
+            delay: 0.002,
+            depth: 0.0,
+            lfo,
+            voices: 3,
+            mix: 0.5,
+        };
+        let result = chorus
+            .process(&impulse(30), FS, Interpolation::Lagrange)
+            .unwrap();
+        let mut expected = vec![0.0; 30];
+        expected[0] = 0.5;
+        expected[20] = 0.5;
+        for (y, e) in result.iter().zip(expected.iter()) {
+            assert!((y - e).abs() < 1e-12);
+        }
+
+        // Результат детерминирован и не усиливает сигнал
+        let chorus = Chorus {
+            depth: 0.001,
+            ..chorus
+        };
+        let signal = tone(440.0, FS, 5000);
+        let first = chorus.process(&signal, FS, Interpolation::Linear).unwrap();
+        let second = chorus.process(&signal, FS, Interpolation::Linear).unwrap();
+        assert_eq!(first, second);
+        assert!(peak(&first) <= 1.0 + 1e-12);
+        assert!(
+            Chorus {
+                voices: 0,
+                ..chorus
+            }
+            .process(&signal, FS, Interpolation::Linear)
+            .is_err()
+        );
+        assert!(
+            Chorus {
+                depth: 0.01,
+                ..chorus
+            }
+            .process(&signal, FS, Interpolation::Linear)
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_flanger_notch() {
+        // Неподвижная задержка 1 мс: гребёнка с провалом на 500 Гц
+        let flanger = Flanger {
+            delay: 0.001,
+            depth: 0.0,
+            lfo: Lfo {
+                rate: 0.25,
+                waveform: Waveform::Triangle,
+                phase: 0.0,
+            },
+            feedback: 0.0,
+            mix: 0.5,
+        };
+        let result = flanger
+            .process(&tone(500.0, FS, 2000), FS, Interpolation::Linear)
+            .unwrap();
+        assert!(peak(&result[100..]) < 1e-9);
+        let result = flanger
+            .process(&tone(1000.0, FS, 2000), FS, Interpolation::Linear)
+            .unwrap();
+        // На 1 кГц задержка равна периоду, тон проходит без изменений
+        let signal = tone(1000.0, FS, 2000);
+        for (y, x) in result.iter().zip(signal.iter()).skip(100) {
+            assert!((y - x).abs() < 1e-9);
+        }
+
+        let swept = Flanger {
+            depth: 0.002,
+            feedback: 0.7,
+            ..flanger
+        };
+        let result = swept
+            .process(&tone(500.0, FS, 20000), FS, Interpolation::Allpass)
+            .unwrap();
+        assert!(result.iter().all(|x| x.is_finite()));
+        assert!(
+            Flanger {
+                feedback: -1.0,
+                ..flanger
+            }
+            .process(&result, FS, Interpolation::Linear)
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_phaser_notch() {
+        // Два звена дают сдвиг фазы -pi на частоте излома, смесь 50/50 гасит тон
+        let phaser = Phaser {
+            stages: 2,
+            min_freq: 800.0,
+            max_freq: 800.0,
+            lfo: Lfo {
+                rate: 0.5,
+                waveform: Waveform::Sine,
+                phase: 0.0,
+            },
+            feedback: 0.0,
+            mix: 0.5,
+        };
+        let result = phaser.process(&tone(800.0, FS, 5000), FS).unwrap();
+        assert!(peak(&result[1000..]) < 1e-3);
+        let result = phaser.process(&tone(100.0, FS, 5000), FS).unwrap();
+        assert!(peak(&result[1000..]) > 0.9);
+
+        let swept = Phaser {
+            stages: 4,
+            min_freq: 300.0,
+            max_freq: 1600.0,
+            feedback: 0.5,
+            ..phaser
+        };
+        assert!(swept.process(&tone(800.0, FS, 5000), FS).is_ok());
+        assert!(
+            Phaser {
+                max_freq: 6000.0,
+                ..swept
+            }
+            .process(&result, FS)
+            .is_err()
+        );
+        assert!(Phaser { stages: 0, ..swept }.process(&result, FS).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::biquad::{Biquad, BiquadType};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PresetFormat {
+    Toml,
+    Json,
+    Text,
+}
+
+impl PresetFormat {
+    /// Picks format by extension: `.toml`, `.json`, anything else is REW/AutoEQ text
+    pub fn from_filename(filename: &str) -> PresetFormat {
+        match Path::new(filename).extension().and_then(|e| e.to_str()) {
+            Some("toml") => PresetFormat::Toml,
+            Some("json") => PresetFormat::Json,
+            _ => PresetFormat::Text,
+        }
+    }
+}
+
+fn default_q() -> f64 {
+    FRAC_1_SQRT_2
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Band of equalizer, `kind` is a biquad type, e.g. peaking or lowshelf
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Band {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub freq: f64,
+    #[serde(default)]
+    pub gain: f64,
+    #[serde(default = "default_q")]
+    pub q: f64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Chain of bands with preamp in dB
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct Preset {
+    #[serde(default)]
+    pub preamp: f64,
+    #[serde(default, alias = "band")]
+    pub bands: Vec<Band>,
+}
+
+// Ширина полосы в октавах в добротность
+fn bandwidth_to_q(octaves: f64) -> f64 {
+    let ratio = 2f64.powf(octaves);
+    ratio.sqrt() / (ratio - 1.0)
+}
+
+// Типы фильтров REW и AutoEQ
+fn text_kind(name: &str) -> Option<&'static str> {
+    match name {
+        "PK" | "PEQ" | "Modal" => Some("peaking"),
+        "LS" | "LSC" | "LSQ" => Some("lowshelf"),
+        "HS" | "HSC" | "HSQ" => Some("highshelf"),
+        "LP" | "LPQ" => Some("lowpass"),
+        "HP" | "HPQ" => Some("highpass"),
+        "BP" => Some("bandpass"),
+        "NO" => Some("notch"),
+        "AP" => Some("allpass"),
+        _ => None,
+    }
+}
+
+fn parse_number(token: Option<&str>, line: &str) -> Result<f64, String> {
+    token
+        .and_then(|t| t.parse::<f64>().ok())
+        .ok_or_else(|| format!("Bad number in line: {line}"))
+}
+
+// Строка вида "Filter 1: ON PK Fc 100 Hz Gain -3 dB Q 1.41"
+fn parse_filter_line(line: &str) -> Result<Option<Band>, String> {
+    let (_, rest) = line
+        .split_once(':')
+        .ok_or_else(|| format!("Bad filter line: {line}"))?;
+    let mut tokens = rest.split_whitespace();
+    let enabled = match tokens.next() {
+        Some("ON") => true,
+        Some("OFF") => false,
+        _ => return Err(format!("Expected ON or OFF in line: {line}")),
+    };
+    let kind = match tokens.next() {
+        Some("None") | None => return Ok(None),
+        Some(name) => {
+            text_kind(name).ok_or_else(|| format!("Unknown filter {name} in line: {line}"))?
+        }
+    };
+
+    let mut band = Band {
+        kind: kind.to_string(),
+        freq: 0.0,
+        gain: 0.0,
+        q: default_q(),
+        enabled,
+    };
+    while let Some(key) = tokens.next() {
+        match key {
+            "Fc" => band.freq = parse_number(tokens.next(), line)?,
+            "Gain" => band.gain = parse_number(tokens.next(), line)?,
+            "Q" => band.q = parse_number(tokens.next(), line)?,
+            "BW" => {
+                // REW пишет ширину как "BW Oct 1.0"
+                let value = match tokens.next() {
+                    Some("Oct") => tokens.next(),
+                    value => value,
+                };
+                band.q = bandwidth_to_q(parse_number(value, line)?);
+            }
+            // Единицы измерения после значений
+            "Hz" | "dB" => {}
+            _ => return Err(format!("Unknown parameter {key} in line: {line}")),
+        }
+    }
+    if band.freq <= 0.0 {
+        return Err(format!("No frequency Fc in line: {line}"));
+    }
+    Ok(Some(band))
+}
+
+// Строка фильтра начинается с "Filter <n>:", как "Filter Settings file" в заголовке REW
+fn is_filter_line(line: &str) -> bool {
+    line.strip_prefix("Filter")
+        .and_then(|rest| rest.split_once(':'))
+        .is_some_and(|(number, _)| {
+            let number = number.trim();
+            !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Parses REW or AutoEQ parametric EQ text, other lines are skipped
+pub fn parse_text(text: &str) -> Result<Preset, String> {
+    let mut preset = Preset::default();
+    for line in text.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("Preamp:") {
+            let value = rest.trim().trim_end_matches("dB").trim();
+            preset.preamp = parse_number(Some(value), line)?;
+        } else if is_filter_line(line)
+            && let Some(band) = parse_filter_line(line)?
+        {
+            preset.bands.push(band);
+        }
+    }
+    Ok(preset)
+}
+
+impl Preset {
+    /// Reads preset from TOML, JSON or REW/AutoEQ text by file extension
+    pub fn read(filename: &str) -> Result<Preset, String> {
+        let text = std::fs::read_to_string(filename)
+            .map_err(|e| format!("Fail to read {filename}: {e}"))?;
+        let preset = match PresetFormat::from_filename(filename) {
+            PresetFormat::Toml => toml::from_str(&text).map_err(|e| e.to_string()),
+            PresetFormat::Json => serde_json::from_str(&text).map_err(|e| e.to_string()),
+            PresetFormat::Text => parse_text(&text),
+        };
+        preset.map_err(|e| format!("Bad preset {filename}: {e}"))
+    }
+
+    /// Biquads of the enabled bands
+    pub fn design(&self, fs: f64) -> Result<Vec<Biquad>, String> {
+        self.bands
+            .iter()
+            .enumerate()
+            .filter(|(_, band)| band.enabled)
+            .map(|(i, band)| {
+                BiquadType::parse(&band.kind)
+                    .and_then(|kind| Biquad::design(kind, band.freq, band.q, band.gain, fs))
+                    .map_err(|e| format!("Band {}: {e}", i + 1))
+            })
+            .collect()
+    }
+
+    /// Applies preamp and the chain of bands
+    pub fn apply(&self, signal: &[f64], fs: f64) -> Result<Vec<f64>, String> {
+        let mut sections = self.design(fs)?;
+        let preamp = 10f64.powf(self.preamp / 20.0);
+        Ok(signal
+            .iter()
+            .map(|&x| {
+                sections
+                    .iter_mut()
+                    .fold(preamp * x, |y, section| section.process_sample(y))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{rms, tone};
+
+    const FS: f64 = 10000.0;
+
+    fn band(kind: &str, freq: f64, gain: f64, q: f64) -> Band {
+        Band {
+            kind: kind.to_string(),
+            freq,
+            gain,
+            q,
+            enabled: true,
+        }
+    }
+
+    // Усиление цепочки на частоте freq в дБ по установившемуся тону
+    fn gain_at(preset: &Preset, freq: f64) -> f64 {
+        let result = preset.apply(&tone(freq, FS, 20000), FS).unwrap();
+        20.0 * (2f64.sqrt() * rms(&result[10000..])).log10()
+    }
+
+    #[test]
+    fn test_parse_text() {
+        let text = "Preamp: -6.2 dB
+Filter 1: ON PK Fc 100 Hz Gain -3.0 dB Q 1.41
+Filter 2: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70
+Filter 3: OFF HSC Fc 3000 Hz Gain -2 dB Q 0.7
+Filter 4: ON HP Fc 20 Hz
+Filter 5: ON PK Fc 1000 Hz Gain 2 dB BW Oct 1.0
+Filter 6: ON None
+";
+        let preset = parse_text(text).unwrap();
+        assert_eq!(preset.preamp, -6.2);
+        assert_eq!(preset.bands.len(), 5);
+        assert_eq!(preset.bands[0], band("peaking", 100.0, -3.0, 1.41));
+        assert_eq!(preset.bands[1], band("lowshelf", 105.0, 5.5, 0.7));
+        assert!(!preset.bands[2].enabled);
+        assert_eq!(preset.bands[3], band("highpass", 20.0, 0.0, FRAC_1_SQRT_2));
+        assert!((preset.bands[4].q - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(preset.design(FS).unwrap().len(), 4);
+
+        // Заголовок файла REW и колонки, выровненные двойными пробелами
+        let rew = "Filter Settings file
+
+Room EQ V5.31.3
+Dated: 18-Oct-2026 12:00:00
+
+Notes:
+
+Equaliser: Generic
+Filter  1: ON  PK       Fc   63.00 Hz  Gain  -4.50 dB  Q  3.000
+Filter  2: ON  LS       Fc   105.0 Hz  Gain   2.00 dB  Q  0.707
+Filter  3: ON  None
+Filter 10: OFF PK       Fc    1000 Hz  Gain   1.00 dB  Q  1.000
+";
+        let preset = parse_text(rew).unwrap();
+        assert_eq!(preset.preamp, 0.0);
+        assert_eq!(preset.bands.len(), 3);
+        assert_eq!(preset.bands[0], band("peaking", 63.0, -4.5, 3.0));
+        assert_eq!(preset.bands[1], band("lowshelf", 105.0, 2.0, 0.707));
+        assert!(!preset.bands[2].enabled);
+
+        assert!(parse_text("Filter 1: ON XX Fc 100 Hz").is_err());
+        assert!(parse_text("Filter 1: ON PK Gain 3 dB").is_err());
+        assert!(parse_text("Filter 1: ON PK Fc abc Hz").is_err());
+    }
+
+    #[test]
+    fn test_toml_and_json_presets() {
+        let toml_text = r#"
+preamp = -3.0
+
+[[band]]
+type = "peaking"
+freq = 1000
+gain = 6
+q = 2.0
+
+[[band]]
+type = "highshelf"
+freq = 3000
+gain = -4
+enabled = false
+"#;
+        let json_text = r#"{"preamp": -3, "bands": [
+            {"type": "peaking", "freq": 1000, "gain": 6, "q": 2},
+            {"type": "highshelf", "freq": 3000, "gain": -4, "enabled": false}
+        ]}"#;
+        let from_toml: Preset = toml::from_str(toml_text).unwrap();
+        let from_json: Preset = serde_json::from_str(json_text).unwrap();
+        assert_eq!(from_toml, from_json);
+        assert_eq!(from_toml.bands[0], band("peaking", 1000.0, 6.0, 2.0));
+        assert_eq!(from_toml.bands[1].q, FRAC_1_SQRT_2);
+
+        assert_eq!(
+            PresetFormat::from_filename("hd600.toml"),
+            PresetFormat::Toml
+        );
+        assert_eq!(
+            PresetFormat::from_filename("dir/hd600.json"),
+            PresetFormat::Json
+        );
+        assert_eq!(
+            PresetFormat::from_filename("hd600 ParametricEQ.txt"),
+            PresetFormat::Text
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let preset = Preset {
+            preamp: -3.0,
+            bands: vec![
+                band("peaking", 1000.0, 6.0, 2.0),
+                band("lowshelf", 100.0, 4.0, 0.7),
+            ],
+        };
+        assert!((gain_at(&preset, 1000.0) - 3.0).abs() < 0.05);
+        assert!((gain_at(&preset, 20.0) - 1.0).abs() < 0.1);
+        assert!((gain_at(&preset, 4000.0) + 3.0).abs() < 0.1);
+
+        let bad = Preset {
+            preamp: 0.0,
+            bands: vec![
+                band("peaking", 1000.0, 6.0, 2.0),
+                band("bell", 100.0, 4.0, 0.7),
+            ],
+        };
+        assert!(
+            bad.design(FS)
+                .unwrap_err()
+                .starts_with("Band 2: Unknown biquad type bell")
+        );
+        let bad = Preset {
+            preamp: 0.0,
+            bands: vec![band("lowpass", 6000.0, 0.0, 0.7)],
+        };
+        assert!(bad.apply(&[1.0], FS).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::biquad::Biquad;
+
+/// Filtering by transfer function b/a in transposed direct form II,
+/// `zi` is the initial state of length max(len(b), len(a)) - 1
+pub fn lfilter(b: &[f64], a: &[f64], signal: &[f64], zi: &[f64]) -> Vec<f64> {
+    let (b, a) = normalize(b, a);
+    let order = b.len() - 1;
+    let mut z = vec![0.0; order + 1];
+    z[..order].copy_from_slice(&zi[..order]);
+
+    let mut sig_new: Vec<f64> = Vec::with_capacity(signal.len());
+    for &x in signal {
+        let y = b[0] * x + z[0];
+        for k in 1..=order {
+            z[k - 1] = b[k] * x - a[k] * y + z[k];
+        }
+        sig_new.push(y);
+    }
+
+    sig_new
+}
+
+// Делим на a0 и дополняем нулями b и a до одной длины
+fn normalize(b: &[f64], a: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let n = b.len().max(a.len());
+    let mut b_new = vec![0.0; n];
+    let mut a_new = vec![0.0; n];
+    for (i, v) in b.iter().enumerate() {
+        b_new[i] = v / a[0];
+    }
+    for (i, v) in a.iter().enumerate() {
+        a_new[i] = v / a[0];
+    }
+    (b_new, a_new)
+}
+
+/// Initial state for step response steady state (as scipy.signal.lfilter_zi)
+pub fn lfilter_zi(b: &[f64], a: &[f64]) -> Result<Vec<f64>, String> {
+    let (b, a) = normalize(b, a);
+    let a_sum: f64 = a.iter().sum();
+    if a_sum.abs() < 1e-12 {
+        return Err("Filter has a pole at zero frequency".to_string());
+    }
+    // На постоянном входе 1 выход равен усилению на нулевой частоте,
+    // состояние z_i = sum_{k > i} (b_k - a_k * gain)
+    let gain = b.iter().sum::<f64>() / a_sum;
+    let mut zi = vec![0.0; b.len() - 1];
+    let mut acc = 0.0;
+    for k in (1..b.len()).rev() {
+        acc += b[k] - a[k] * gain;
+        zi[k - 1] = acc;
+    }
+    Ok(zi)
+}
+
+/// Odd extension of the signal by `pad` samples on both sides
+pub fn odd_ext(signal: &[f64], pad: usize) -> Vec<f64> {
+    let n = signal.len();
+    let first = signal[0];
+    let last = signal[n - 1];
+
+    let mut ext: Vec<f64> = Vec::with_capacity(n + 2 * pad);
+    ext.extend((1..=pad).rev().map(|i| 2.0 * first - signal[i]));
+    ext.extend_from_slice(signal);
+    ext.extend((1..=pad).map(|i| 2.0 * last - signal[n - 1 - i]));
+    ext
+}
+
+fn check_length(signal: &[f64], pad: usize) -> Result<(), String> {
+    if signal.len() <= pad {
+        return Err(format!(
+            "Signal length {} must be greater than padding {pad}",
+            signal.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Zero-phase forward-backward filtering by transfer function b/a
+pub fn filtfilt(b: &[f64], a: &[f64], signal: &[f64]) -> Result<Vec<f64>, String> {
+    if b.is_empty() || a.is_empty() || a[0] == 0.0 {
+        return Err("Coefficients must be non-empty and a[0] must be nonzero".to_string());
+    }
+    let pad = 3 * b.len().max(a.len());
+    check_length(signal, pad)?;
+
+    let zi = lfilter_zi(b, a)?;
+    let scaled = |x0: f64| zi.iter().map(|z| z * x0).collect::<Vec<f64>>();
+
+    let ext = odd_ext(signal, pad);
+    let mut forward = lfilter(b, a, &ext, &scaled(ext[0]));
+    forward.reverse();
+    let mut backward = lfilter(b, a, &forward, &scaled(forward[0]));
+    backward.reverse();
+
+    Ok(backward[pad..pad + signal.len()].to_vec())
+}
+
+// Проход каскадом секций, начальное состояние каждой секции домножается
+// на усиление предыдущих секций на нулевой частоте
+fn sos_pass(sections: &[Biquad], zis: &[Vec<f64>], signal: &[f64]) -> Vec<f64> {
+    let mut sig_new = signal.to_vec();
+    for (section, zi) in sections.iter().zip(zis.iter()) {
+        let (b, a) = section.coefficients();
+        let x0 = sig_new[0];
+        let state: Vec<f64> = zi.iter().map(|z| z * x0).collect();
+        sig_new = lfilter(&b, &a, &sig_new, &state);
+    }
+    sig_new
+}
+
+/// Zero-phase forward-backward filtering by a cascade of second-order sections
+pub fn sosfiltfilt(sections: &[Biquad], signal: &[f64]) -> Result<Vec<f64>, String> {
+    if sections.is_empty() {
+        return Err("No sections in filter".to_string());
+    }
+    let pad = 3 * (2 * sections.len() + 1);
+    check_length(signal, pad)?;
+
+    let zis = sections
+        .iter()
+        .map(|s| {
+            let (b, a) = s.coefficients();
+            lfilter_zi(&b, &a)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let ext = odd_ext(signal, pad);
+    let mut forward = sos_pass(sections, &zis, &ext);
+    forward.reverse();
+    let mut backward = sos_pass(sections, &zis, &forward);
+    backward.reverse();
+
+    Ok(backward[pad..pad + signal.len()].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biquad::BiquadType;
+    use crate::fir::{self, BandType, Window};
+    use crate::generate;
+    use crate::iir::{self, IirFamily};
+
+    const FS: f64 = 10000.0;
+
+    #[test]
+    fn test_lfilter_matches_fir_and_biquad() {
+        let sig = generate::create_sine(440.0, 30.0, 0.05, 1.0);
+
+        let taps = [0.25, 0.5, 0.25];
+        let zero = [0.0, 0.0];
+        let result = lfilter(&taps, &[1.0], &sig, &zero);
+        for (r, e) in result.iter().zip(fir::fir_filter(&sig, &taps).iter()) {
+            assert!((r - e).abs() < 1e-12);
+        }
+
+        let mut section = Biquad::new([0.2, 0.3, 0.1], [1.0, -0.5, 0.25]);
+        let (b, a) = section.coefficients();
+        let result = lfilter(&b, &a, &sig, &zero);
+        let expected = section.process(&sig);
+        for (r, e) in result.iter().zip(expected.iter()) {
+            assert!((r - e).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_lfilter_zi_gives_steady_state() {
+        let b = [0.2, 0.3, 0.1];
+        let a = [2.0, -1.0, 0.5];
+        let zi = lfilter_zi(&b, &a).unwrap();
+        let step = vec![1.0; 50];
+        let gain = b.iter().sum::<f64>() / a.iter().sum::<f64>();
+        for y in lfilter(&b, &a, &step, &zi) {
+            assert!((y - gain).abs() < 1e-12);
+        }
+
+        assert!(lfilter_zi(&[1.0], &[1.0, -1.0]).is_err());
+    }
+
+    #[test]
+    fn test_odd_ext() {
+        let ext = odd_ext(&[1.0, 2.0, 4.0, 7.0], 2);
+        assert_eq!(ext, vec![-2.0, 0.0, 1.0, 2.0, 4.0, 7.0, 10.0, 12.0]);
+    }
+
+    #[test]
+    fn test_filtfilt_zero_phase() {
+        // Тон в полосе пропускания проходит без сдвига фазы
+        let sig = generate::create_sine(100.0, 0.0, 1.0, 1.0);
+        let taps = fir::firwin(51, &[1000.0], BandType::Lowpass, Window::Hamming, FS).unwrap();
+        let result = filtfilt(&taps, &[1.0], &sig).unwrap();
+        assert_eq!(result.len(), sig.len());
+        // Неравномерность окна Хэмминга в полосе пропускания около 0.2%, проход двойной
+        for (r, s) in result.iter().zip(sig.iter()) {
+            assert!((r - s).abs() < 1e-2, "{r} {s}");
+        }
+
+        let (b, a) = Biquad::design(BiquadType::Lowpass, 2000.0, 0.707, 0.0, FS)
+            .unwrap()
+            .coefficients();
+        let result = filtfilt(&b, &a, &sig).unwrap();
+        for (r, s) in result[100..9900].iter().zip(sig[100..9900].iter()) {
+            assert!((r - s).abs() < 1e-3, "{r} {s}");
+        }
+    }
+
+    #[test]
+    fn test_filtfilt_constant_has_no_transient() {
+        let sig = vec![3.0; 200];
+        let sections = iir::iirfilter(
+            4,
+            &[500.0],
+            BandType::Lowpass,
+            IirFamily::Butterworth,
+            0.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        for y in sosfiltfilt(&sections, &sig).unwrap() {
+            assert!((y - 3.0).abs() < 1e-9);
+        }
+
+        let (b, a) = Biquad::design(BiquadType::Lowpass, 500.0, 0.707, 0.0, FS)
+            .unwrap()
+            .coefficients();
+        for y in filtfilt(&b, &a, &sig).unwrap() {
+            assert!((y - 3.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_sosfiltfilt_removes_tone_without_delay() {
+        let low = generate::create_sine(50.0, 0.0, 1.0, 1.0);
+        let high = generate::create_sine(2000.0, 0.0, 1.0, 1.0);
+        let mixed: Vec<f64> = low.iter().zip(high.iter()).map(|(a, b)| a + b).collect();
+
+        let sections = iir::iirfilter(
+            6,
+            &[500.0],
+            BandType::Lowpass,
+            IirFamily::Elliptic,
+            0.1,
+            80.0,
+            FS,
+        )
+        .unwrap();
+        let result = sosfiltfilt(&sections, &mixed).unwrap();
+        for (r, s) in result[500..9500].iter().zip(low[500..9500].iter()) {
+            assert!((r - s).abs() < 0.03, "{r} {s}");
+        }
+    }
+
+    #[test]
+    fn test_filtfilt_short_signal() {
+        assert!(filtfilt(&[0.5, 0.5], &[1.0], &[1.0; 6]).is_err());
+        assert!(filtfilt(&[0.5, 0.5], &[1.0], &[1.0; 7]).is_ok());
+        assert!(filtfilt(&[1.0], &[0.0], &[1.0; 7]).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BandType {
+    Lowpass,
+    Highpass,
+    Bandpass,
+    Bandstop,
+}
+
+impl BandType {
+    pub fn parse(name: &str) -> Result<BandType, String> {
+        match name {
+            "lowpass" => Ok(BandType::Lowpass),
+            "highpass" => Ok(BandType::Highpass),
+            "bandpass" => Ok(BandType::Bandpass),
+            "bandstop" => Ok(BandType::Bandstop),
+            _ => Err(format!(
+                "Unknown band type {name}, expected lowpass, highpass, bandpass or bandstop"
+            )),
+        }
+    }
+
+    pub fn cutoff_count(&self) -> usize {
+        match self {
+            BandType::Lowpass | BandType::Highpass => 1,
+            BandType::Bandpass | BandType::Bandstop => 2,
+        }
+    }
+
+    // Пропускает ли фильтр частоту Найквиста
+    fn passes_nyquist(&self) -> bool {
+        matches!(self, BandType::Highpass | BandType::Bandstop)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    Kaiser(f64),
+}
+
+impl Window {
+    /// `attenuation` in dB is used only to pick beta of the Kaiser window
+    pub fn parse(name: &str, attenuation: f64) -> Result<Window, String> {
+        match name {
+            "rectangular" => Ok(Window::Rectangular),
+            "hann" => Ok(Window::Hann),
+            "hamming" => Ok(Window::Hamming),
+            "blackman" => Ok(Window::Blackman),
+            "kaiser" => Ok(Window::Kaiser(kaiser_beta(attenuation))),
+            _ => Err(format!(
+                "Unknown window {name}, expected rectangular, hann, hamming, blackman or kaiser"
+            )),
+        }
+    }
+
+    pub fn coefficients(&self, length: usize) -> Vec<f64> {
+        if length == 1 {
+            return vec![1.0];
+        }
+        let m = (length - 1) as f64;
+
+        (0..length)
+            .map(|i| {
+                let x = i as f64 / m;
+                match self {
+                    Window::Rectangular => 1.0,
+                    Window::Hann => 0.5 - 0.5 * (2.0 * PI * x).cos(),
+                    Window::Hamming => 0.54 - 0.46 * (2.0 * PI * x).cos(),
+                    Window::Blackman => {
+                        0.42 - 0.5 * (2.0 * PI * x).cos() + 0.08 * (4.0 * PI * x).cos()
+                    }
+                    Window::Kaiser(beta) => {
+                        let r = 2.0 * x - 1.0;
+                        bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(*beta)
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Modified Bessel function of the first kind, order zero
+pub fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+    let mut k = 1.0;
+
+    while term > sum * 1e-16 {
+        term *= (half / k) * (half / k);
+        sum += term;
+        k += 1.0;
+    }
+
+    sum
+}
+
+/// Kaiser window beta for the given stopband attenuation in dB
+pub fn kaiser_beta(attenuation: f64) -> f64 {
+    if attenuation > 50.0 {
+        0.1102 * (attenuation - 8.7)
+    } else if attenuation > 21.0 {
+        0.5842 * (attenuation - 21.0).powf(0.4) + 0.07886 * (attenuation - 21.0)
+    } else {
+        0.0
+    }
+}
+
+/// Kaiser estimate of the filter length and beta for the given attenuation (dB)
+/// and transition width (Hz)
+pub fn kaiser_order(attenuation: f64, transition: f64, fs: f64) -> Result<(usize, f64), String> {
+    if transition <= 0.0 || transition >= fs / 2.0 {
+        return Err(format!(
+            "Transition width must be in (0, {}) Hz, got {transition}",
+            fs / 2.0
+        ));
+    }
+    let width = 2.0 * transition / fs;
+    let length = ((attenuation - 7.95) / (2.285 * PI * width))
+        .ceil()
+        .max(0.0) as usize
+        + 1;
+
+    Ok((length, kaiser_beta(attenuation)))
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Windowed-sinc FIR design, cutoffs are given in Hz.
+///
+/// Highpass and bandstop filters pass Nyquist, so they need an odd length
+/// (even symmetric filters always have a zero at Nyquist).
+/// Taps are scaled to unit gain in the centre of the first passband.
+pub fn firwin(
+    length: usize,
+    cutoffs: &[f64],
+    band: BandType,
+    window: Window,
+    fs: f64,
+) -> Result<Vec<f64>, String> {
+    if length == 0 {
+        return Err("Filter length must be positive".to_string());
+    }
+    if cutoffs.len() != band.cutoff_count() {
+        return Err(format!(
+            "{:?} filter needs {} cutoff frequencies, got {}",
+            band,
+            band.cutoff_count(),
+            cutoffs.len()
+        ));
+    }
+    let nyquist = fs / 2.0;
+    if cutoffs.iter().any(|&f| f <= 0.0 || f >= nyquist) {
+        return Err(format!("Cutoff frequencies must be in (0, {nyquist}) Hz"));
+    }
+    if cutoffs.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Cutoff frequencies must be increasing".to_string());
+    }
+    if band.passes_nyquist() && length.is_multiple_of(2) {
+        return Err(format!(
+            "{band:?} filter must have odd length, got {length}"
+        ));
+    }
+
+    // Границы полос пропускания в долях частоты Найквиста
+    let norm: Vec<f64> = cutoffs.iter().map(|f| f / nyquist).collect();
+    let bands: Vec<(f64, f64)> = match band {
+        BandType::Lowpass => vec![(0.0, norm[0])],
+        BandType::Highpass => vec![(norm[0], 1.0)],
+        BandType::Bandpass => vec![(norm[0], norm[1])],
+        BandType::Bandstop => vec![(0.0, norm[0]), (norm[1], 1.0)],
+    };
+
+    let alpha = (length - 1) as f64 / 2.0;
+    let win = window.coefficients(length);
+    let mut taps: Vec<f64> = (0..length)
+        .map(|i| {
+            let m = i as f64 - alpha;
+            let h: f64 = bands
+                .iter()
+                .map(|&(left, right)| right * sinc(right * m) - left * sinc(left * m))
+                .sum();
+            h * win[i]
+        })
+        .collect();
+
+    let (left, right) = bands[0];
+    let scale_freq = if left == 0.0 {
+        0.0
+    } else if right == 1.0 {
+        1.0
+    } else {
+        (left + right) / 2.0
+    };
+    let gain: f64 = taps
+        .iter()
+        .enumerate()
+        .map(|(i, h)| h * (PI * (i as f64 - alpha) * scale_freq).cos())
+        .sum();
+    for h in taps.iter_mut() {
+        *h /= gain;
+    }
+
+    Ok(taps)
+}
+
+/// Windowed-sinc design with Kaiser window and automatic length selection.
+/// The length is bumped to odd when the band type requires it.
+pub fn firwin_kaiser(
+    cutoffs: &[f64],
+    band: BandType,
+    attenuation: f64,
+    transition: f64,
+    fs: f64,
+) -> Result<Vec<f64>, String> {
+    let (mut length, beta) = kaiser_order(attenuation, transition, fs)?;
+    if band.passes_nyquist() && length.is_multiple_of(2) {
+        length += 1;
+    }
+
+    firwin(length, cutoffs, band, Window::Kaiser(beta), fs)
+}
+
+/// Causal FIR filtering, output has the same length as the input
+pub fn fir_filter(signal: &[f64], taps: &[f64]) -> Vec<f64> {
+    let mut sig_new: Vec<f64> = Vec::with_capacity(signal.len());
+
+    for i in 0..signal.len() {
+        let mut acc = 0.0;
+        for (k, h) in taps.iter().enumerate().take(i + 1) {
+            acc += h * signal[i - k];
+        }
+        sig_new.push(acc);
+    }
+
+    sig_new
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+    use crate::signal;
+    use crate::test_util::{magnitude, rms};
+
+    const FS: f64 = signal::SAMPLE_RATE as f64;
+
+    // Амплитудная характеристика КИХ-фильтра на частоте freq
+    fn gain_at(taps: &[f64], freq: f64) -> f64 {
+        magnitude(taps, freq, FS)
+    }
+
+    #[test]
+    fn test_band_type_parse() {
+        assert_eq!(BandType::parse("lowpass"), Ok(BandType::Lowpass));
+        assert_eq!(BandType::parse("bandstop"), Ok(BandType::Bandstop));
+        assert!(BandType::parse("allpass").is_err());
+    }
+
+    #[test]
+    fn test_window_symmetric_and_peak() {
+        for window in [
+            Window::Hann,
+            Window::Hamming,
+            Window::Blackman,
+            Window::Kaiser(5.0),
+        ] {
+            let w = window.coefficients(31);
+            for i in 0..31 {
+                assert!((w[i] - w[30 - i]).abs() < 1e-12, "{window:?} not symmetric");
+            }
+            assert!((w[15] - 1.0).abs() < 1e-12, "{window:?} peak is {}", w[15]);
+        }
+        assert_eq!(Window::Rectangular.coefficients(4), vec![1.0; 4]);
+        assert_eq!(Window::Hann.coefficients(1), vec![1.0]);
+    }
+
+    #[test]
+    fn test_bessel_i0_known_values() {
+        assert!((bessel_i0(0.0) - 1.0).abs() < 1e-12);
+        assert!((bessel_i0(1.0) - 1.2660658777520082).abs() < 1e-12);
+        assert!((bessel_i0(5.0) - 27.239871823604442).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_kaiser_beta_and_order() {
+        assert_eq!(kaiser_beta(20.0), 0.0);
+        assert!((kaiser_beta(60.0) - 5.65326).abs() < 1e-5);
+
+        // scipy.signal.kaiserord(60, 0.02) -> (364, 5.653)
+        let (length, beta) = kaiser_order(60.0, 100.0, FS).unwrap();
+        assert_eq!(length, 364);
+        assert!((beta - 5.65326).abs() < 1e-5);
+
+        assert!(kaiser_order(60.0, 0.0, FS).is_err());
+        assert!(kaiser_order(60.0, FS, FS).is_err());
+    }
+
+    #[test]
+    fn test_firwin_lowpass() {
+        let taps = firwin(101, &[1000.0], BandType::Lowpass, Window::Hamming, FS).unwrap();
+        assert_eq!(taps.len(), 101);
+        assert!((taps.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(gain_at(&taps, 2000.0) < 0.01);
+        for i in 0..101 {
+            assert!((taps[i] - taps[100 - i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_firwin_highpass() {
+        let taps = firwin(101, &[1000.0], BandType::Highpass, Window::Hamming, FS).unwrap();
+        assert!((gain_at(&taps, FS / 2.0) - 1.0).abs() < 1e-9);
+        assert!(gain_at(&taps, 0.0) < 0.01);
+        assert!(gain_at(&taps, 500.0) < 0.01);
+    }
+
+    #[test]
+    fn test_firwin_highpass_even_length_rejected() {
+        let result = firwin(100, &[1000.0], BandType::Highpass, Window::Hamming, FS);
+        assert!(result.is_err());
+        let result = firwin(
+            100,
+            &[500.0, 1000.0],
+            BandType::Bandstop,
+            Window::Hamming,
+            FS,
+        );
+        assert!(result.is_err());
+        // Для ФНЧ и полосового чётная длина допустима
+        assert!(firwin(100, &[1000.0], BandType::Lowpass, Window::Hamming, FS).is_ok());
+        assert!(
+            firwin(
+                100,
+                &[500.0, 1000.0],
+                BandType::Bandpass,
+                Window::Hamming,
+                FS
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_firwin_bandpass_and_bandstop() {
+        let bp = firwin(
+            201,
+            &[1000.0, 2000.0],
+            BandType::Bandpass,
+            Window::Blackman,
+            FS,
+        )
+        .unwrap();
+        assert!((gain_at(&bp, 1500.0) - 1.0).abs() < 1e-3);
+        assert!(gain_at(&bp, 300.0) < 1e-3);
+        assert!(gain_at(&bp, 3000.0) < 1e-3);
+
+        let bs = firwin(
+            201,
+            &[1000.0, 2000.0],
+            BandType::Bandstop,
+            Window::Blackman,
+            FS,
+        )
+        .unwrap();
+        assert!((gain_at(&bs, 0.0) - 1.0).abs() < 1e-9);
+        assert!(gain_at(&bs, 1500.0) < 1e-3);
+        assert!((gain_at(&bs, 4000.0) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_firwin_invalid_cutoffs() {
+        let lp = BandType::Lowpass;
+        let bp = BandType::Bandpass;
+        assert!(firwin(11, &[6000.0], lp, Window::Hann, FS).is_err());
+        assert!(firwin(11, &[0.0], lp, Window::Hann, FS).is_err());
+        assert!(firwin(11, &[100.0, 200.0], lp, Window::Hann, FS).is_err());
+        assert!(firwin(11, &[200.0, 100.0], bp, Window::Hann, FS).is_err());
+        assert!(firwin(0, &[100.0], lp, Window::Hann, FS).is_err());
+    }
+
+    #[test]
+    fn test_firwin_kaiser_meets_spec() {
+        let taps = firwin_kaiser(&[1000.0], BandType::Lowpass, 60.0, 200.0, FS).unwrap();
+        // В полосе задерживания (после перехода) подавление не хуже ~60 дБ
+        for freq in [1150.0, 1500.0, 3000.0, 4900.0] {
+            let db = 20.0 * gain_at(&taps, freq).log10();
+            assert!(db < -58.0, "gain at {freq} Hz is {db} dB");
+        }
+        assert!((gain_at(&taps, 500.0) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_firwin_kaiser_highpass_is_odd() {
+        // Оценка Кайзера для 60 дБ и 100 Гц даёт чётную длину 364
+        let taps = firwin_kaiser(&[1000.0], BandType::Highpass, 60.0, 100.0, FS).unwrap();
+        assert_eq!(taps.len(), 365);
+        assert!((gain_at(&taps, FS / 2.0) - 1.0).abs() < 1e-9);
+
+        let taps = firwin_kaiser(&[1000.0], BandType::Lowpass, 60.0, 100.0, FS).unwrap();
+        assert_eq!(taps.len(), 364);
+    }
+
+    #[test]
+    fn test_fir_filter_impulse_response() {
+        let taps = vec![0.5, 0.25, 0.125];
+        let impulse = vec![1.0, 0.0, 0.0, 0.0, 0.0];
+        assert_eq!(
+            fir_filter(&impulse, &taps),
+            vec![0.5, 0.25, 0.125, 0.0, 0.0]
+        );
+        assert!(fir_filter(&[], &taps).is_empty());
+    }
+
+    #[test]
+    fn test_fir_filter_removes_high_tone() {
+        let low = generate::create_sine(100.0, 0.0, 1.0, 1.0);
+        let high = generate::create_sine(3000.0, 0.0, 1.0, 1.0);
+        let mixed: Vec<f64> = low.iter().zip(high.iter()).map(|(a, b)| a + b).collect();
+
+        let window = Window::Kaiser(kaiser_beta(60.0));
+        let taps = firwin(101, &[1000.0], BandType::Lowpass, window, FS).unwrap();
+        let filtered = fir_filter(&mixed, &taps);
+
+        // Сравниваем после установления, с учётом задержки (N-1)/2
+        let delay = (taps.len() - 1) / 2;
+        let residual: Vec<f64> = filtered[taps.len()..]
+            .iter()
+            .zip(low[taps.len() - delay..].iter())
+            .map(|(y, x)| y - x)
+            .collect();
+        assert!(rms(&residual) < 1e-2, "residual rms {}", rms(&residual));
+    }
+}
@@ -187,7 +187,7 @@ mod tests {
         
         let mut measured_freqs = Vec::new();
         for i in 0..num_points {
-            let idx = (i * (signal.len() - window_size) / (num_points - 1)) as usize;
+            let idx = i * (signal.len() - window_size) / (num_points - 1);
             let freq = compute_freq(&signal, window_size as i32, idx as i32);
             measured_freqs.push(freq);
         }
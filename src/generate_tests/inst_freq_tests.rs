@@ -0,0 +1,44 @@
+use crate::generate;
+use crate::hilbert;
+use crate::signal;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Мгновенная частота между отсчётами n и n+1 относится к моменту (n + 0.5) / fs.
+    // КИХ-преобразователь локален, у БПФ-варианта разрыв на краях свипа
+    // даёт пульсации по всей длине
+    fn measured(signal: &[f64]) -> Vec<(f64, f64)> {
+        let fs = signal::SAMPLE_RATE as f64;
+        hilbert::inst_freq(&hilbert::analytic_fir(signal, 201).unwrap(), fs)
+            .into_iter()
+            .enumerate()
+            .map(|(n, f)| ((n as f64 + 0.5) / fs, f))
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_sweep_inst_freq_law() {
+        let (f0, f1, t1) = (100.0, 1000.0, 1.0);
+        let signal = generate::create_linear_sweep(f0, f1, t1);
+
+        // Ниже 250 Гц полоса преобразователя заканчивается, на краях его задержка
+        for (t, f) in measured(&signal).into_iter().skip(2000).take(7800) {
+            let expected = f0 + (f1 - f0) * t / t1;
+            assert!((f - expected).abs() < 1e-3, "t={t} f={f} expected={expected}");
+        }
+    }
+
+    #[test]
+    fn test_quadratic_sweep_inst_freq_law() {
+        let (f0, f1, t1) = (300.0, 900.0, 1.0);
+        let signal = generate::create_quadratic_sweep(f0, f1, t1, true);
+        let k = (f1 - f0) / t1.powi(2);
+
+        for (t, f) in measured(&signal).into_iter().skip(200).take(9600) {
+            let expected = f0 + k * t.powi(2);
+            assert!((f - expected).abs() < 1e-3, "t={t} f={f} expected={expected}");
+        }
+    }
+}
@@ -4,3 +4,4 @@ pub mod hyperbolic_sweep_tests;
 pub mod quadratic_sweep_tests;
 pub mod noise_tests;
 pub mod test_utils;
+pub mod inst_freq_tests;
@@ -0,0 +1,180 @@
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+use std::f64::consts::PI;
+
+use crate::analysis;
+use crate::dsp::{self, ConvolveMethod, ConvolveMode};
+use crate::remez::{self, RemezType};
+
+// Полоса КИХ-преобразователя Гильберта в долях частоты дискретизации,
+// у нуля и частоты Найквиста его усиление неизбежно падает
+const FIR_BAND: [f64; 2] = [0.025, 0.475];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HilbertMethod {
+    Fft,
+    Fir,
+}
+
+impl HilbertMethod {
+    pub fn parse(name: &str) -> Result<HilbertMethod, String> {
+        match name {
+            "fft" => Ok(HilbertMethod::Fft),
+            "fir" => Ok(HilbertMethod::Fir),
+            _ => Err(format!(
+                "Unknown Hilbert method {name}, expected fft or fir"
+            )),
+        }
+    }
+}
+
+/// Analytic signal by zeroing negative frequencies of the whole signal
+/// spectrum (as scipy.signal.hilbert)
+pub fn analytic_fft(signal: &[f64]) -> Vec<Complex<f64>> {
+    let n = signal.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut planner = FftPlanner::new();
+    let forward = planner.plan_fft_forward(n);
+    let inverse = planner.plan_fft_inverse(n);
+
+    let mut buffer: Vec<Complex<f64>> = signal.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    forward.process(&mut buffer);
+    // Положительные частоты удваиваются, нулевая и Найквиста остаются
+    for (k, c) in buffer.iter_mut().enumerate() {
+        let weight = if k == 0 || 2 * k == n {
+            1.0
+        } else if 2 * k < n {
+            2.0
+        } else {
+            0.0
+        };
+        *c *= weight / n as f64;
+    }
+    inverse.process(&mut buffer);
+    buffer
+}
+
+/// Equiripple FIR Hilbert transformer of odd length
+pub fn hilbert_taps(length: usize) -> Result<Vec<f64>, String> {
+    if length.is_multiple_of(2) {
+        return Err(format!(
+            "Hilbert transformer must have odd length, got {length}"
+        ));
+    }
+    remez::remez(length, &FIR_BAND, &[1.0], &[1.0], RemezType::Hilbert, 1.0)
+}
+
+/// Analytic signal with the imaginary part from the FIR Hilbert transformer,
+/// its delay is compensated
+pub fn analytic_fir(signal: &[f64], length: usize) -> Result<Vec<Complex<f64>>, String> {
+    let taps = hilbert_taps(length)?;
+    let imag = dsp::convolve(signal, &taps, ConvolveMode::Same, ConvolveMethod::Auto);
+    Ok(signal
+        .iter()
+        .zip(imag.iter())
+        .map(|(&re, &im)| Complex::new(re, im))
+        .collect())
+}
+
+pub fn analytic(
+    signal: &[f64],
+    method: HilbertMethod,
+    fir_length: usize,
+) -> Result<Vec<Complex<f64>>, String> {
+    match method {
+        HilbertMethod::Fft => Ok(analytic_fft(signal)),
+        HilbertMethod::Fir => analytic_fir(signal, fir_length),
+    }
+}
+
+pub fn envelope(analytic: &[Complex<f64>]) -> Vec<f64> {
+    analytic.iter().map(|z| z.norm()).collect()
+}
+
+/// Unwrapped instantaneous phase in radians
+pub fn inst_phase(analytic: &[Complex<f64>]) -> Vec<f64> {
+    analysis::unwrapped_phase(analytic)
+}
+
+/// Instantaneous frequency in Hz between neighbouring samples,
+/// the result is one sample shorter than the signal
+pub fn inst_freq(analytic: &[Complex<f64>], fs: f64) -> Vec<f64> {
+    analytic
+        .windows(2)
+        .map(|pair| (pair[1] * pair[0].conj()).arg() * fs / (2.0 * PI))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    const FS: f64 = 10000.0;
+
+    #[test]
+    fn test_analytic_fft_of_cosine() {
+        // Аналитический сигнал cos это exp(j*phi), при целом числе периодов точно
+        let sig: Vec<f64> = (0..1000)
+            .map(|n| (2.0 * PI * 50.0 * n as f64 / 1000.0).cos())
+            .collect();
+        let z = analytic_fft(&sig);
+        for (n, c) in z.iter().enumerate() {
+            let phi = 2.0 * PI * 50.0 * n as f64 / 1000.0;
+            assert!((c.re - phi.cos()).abs() < 1e-9);
+            assert!((c.im - phi.sin()).abs() < 1e-9);
+        }
+        assert!(analytic_fft(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_analytic_fir_matches_fft() {
+        let sig = generate::create_sine(700.0, 0.0, 0.2, 1.0);
+        let fft = analytic_fft(&sig);
+        let fir = analytic_fir(&sig, 101).unwrap();
+        for (a, b) in fft[200..1800].iter().zip(fir[200..1800].iter()) {
+            assert!((a - b).norm() < 0.01, "{a} {b}");
+        }
+        assert!(hilbert_taps(100).is_err());
+    }
+
+    #[test]
+    fn test_envelope_of_am_signal() {
+        // Тон 1 кГц, модулированный по амплитуде тоном 20 Гц
+        let sig: Vec<f64> = (0..2000)
+            .map(|n| {
+                let t = n as f64 / FS;
+                (1.0 + 0.5 * (2.0 * PI * 20.0 * t).cos()) * (2.0 * PI * 1000.0 * t).cos()
+            })
+            .collect();
+        let env = envelope(&analytic_fft(&sig));
+        for (n, e) in env.iter().enumerate().take(1800).skip(200) {
+            let expected = 1.0 + 0.5 * (2.0 * PI * 20.0 * n as f64 / FS).cos();
+            assert!((e - expected).abs() < 1e-3, "{e} {expected}");
+        }
+    }
+
+    #[test]
+    fn test_inst_phase_and_freq() {
+        let sig = generate::create_sine(440.0, 0.0, 0.1, 1.0);
+        let z = analytic_fft(&sig);
+
+        let phase = inst_phase(&z);
+        // sin = cos(phi - pi/2), фаза растёт линейно без скачков
+        let slope = 2.0 * PI * 440.0 / FS;
+        for n in 100..900 {
+            let expected = phase[100] + slope * (n - 100) as f64;
+            assert!((phase[n] - expected).abs() < 1e-2);
+        }
+
+        let freq = inst_freq(&z, FS);
+        assert_eq!(freq.len(), sig.len() - 1);
+        for f in &freq[100..900] {
+            assert!((f - 440.0).abs() < 1.0, "{f}");
+        }
+        assert_eq!(HilbertMethod::parse("fir"), Ok(HilbertMethod::Fir));
+        assert!(HilbertMethod::parse("dft").is_err());
+    }
+}
@@ -0,0 +1,879 @@
+use rustfft::num_complex::Complex;
+use std::f64::consts::PI;
+
+use crate::biquad::Biquad;
+use crate::fir::BandType;
+use crate::poly;
+
+// Порядок, выше которого корни полинома Бесселя считаются неточно
+const MAX_BESSEL_ORDER: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IirFamily {
+    Butterworth,
+    Chebyshev1,
+    Chebyshev2,
+    Elliptic,
+    Bessel,
+}
+
+impl IirFamily {
+    pub fn parse(name: &str) -> Result<IirFamily, String> {
+        match name {
+            "butter" => Ok(IirFamily::Butterworth),
+            "cheby1" => Ok(IirFamily::Chebyshev1),
+            "cheby2" => Ok(IirFamily::Chebyshev2),
+            "ellip" => Ok(IirFamily::Elliptic),
+            "bessel" => Ok(IirFamily::Bessel),
+            _ => Err(format!(
+                "Unknown filter family {name}, expected butter, cheby1, cheby2, ellip or bessel"
+            )),
+        }
+    }
+}
+
+/// Zeros, poles and gain of a transfer function
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zpk {
+    pub zeros: Vec<Complex<f64>>,
+    pub poles: Vec<Complex<f64>>,
+    pub gain: f64,
+}
+
+impl Zpk {
+    fn degree(&self) -> usize {
+        self.poles.len() - self.zeros.len()
+    }
+}
+
+fn product(values: &[Complex<f64>]) -> Complex<f64> {
+    values.iter().fold(Complex::new(1.0, 0.0), |acc, v| acc * v)
+}
+
+// Полюса на окружности как у Баттерворта: -exp(j pi m / 2N), m = -N+1..N-1 с шагом 2
+fn butterworth_circle(order: usize) -> Vec<Complex<f64>> {
+    (0..order)
+        .map(|i| {
+            let m = 2.0 * i as f64 - order as f64 + 1.0;
+            -Complex::from_polar(1.0, PI * m / (2.0 * order as f64))
+        })
+        .collect()
+}
+
+/// Analog Butterworth prototype with -3 dB at 1 rad/s
+pub fn butterworth_prototype(order: usize) -> Zpk {
+    Zpk {
+        zeros: Vec::new(),
+        poles: butterworth_circle(order),
+        gain: 1.0,
+    }
+}
+
+/// Analog Chebyshev type I prototype, `ripple` dB in the passband up to 1 rad/s
+pub fn chebyshev1_prototype(order: usize, ripple: f64) -> Zpk {
+    let eps = (10.0_f64.powf(0.1 * ripple) - 1.0).sqrt();
+    let mu = (1.0 / eps).asinh() / order as f64;
+
+    let poles: Vec<Complex<f64>> = (0..order)
+        .map(|i| {
+            let m = 2.0 * i as f64 - order as f64 + 1.0;
+            let theta = PI * m / (2.0 * order as f64);
+            -Complex::new(mu, theta).sinh()
+        })
+        .collect();
+
+    let mut gain = product(&poles.iter().map(|p| -p).collect::<Vec<_>>()).re;
+    if order.is_multiple_of(2) {
+        gain /= (1.0 + eps * eps).sqrt();
+    }
+
+    Zpk {
+        zeros: Vec::new(),
+        poles,
+        gain,
+    }
+}
+
+/// Analog Chebyshev type II prototype, `attenuation` dB from 1 rad/s
+pub fn chebyshev2_prototype(order: usize, attenuation: f64) -> Zpk {
+    let de = 1.0 / (10.0_f64.powf(0.1 * attenuation) - 1.0).sqrt();
+    let mu = (1.0 / de).asinh() / order as f64;
+    let n = order as f64;
+
+    // Нули на мнимой оси, для нечётного порядка один уходит в бесконечность
+    let zeros: Vec<Complex<f64>> = (0..order)
+        .map(|i| 2.0 * i as f64 - n + 1.0)
+        .filter(|m| *m != 0.0)
+        .map(|m| -(Complex::new(0.0, 1.0) / (m * PI / (2.0 * n)).sin()).conj())
+        .collect();
+
+    let poles: Vec<Complex<f64>> = butterworth_circle(order)
+        .into_iter()
+        .map(|p| Complex::new(mu.sinh() * p.re, mu.cosh() * p.im).inv())
+        .collect();
+
+    let gain = (product(&poles.iter().map(|p| -p).collect::<Vec<_>>())
+        / product(&zeros.iter().map(|z| -z).collect::<Vec<_>>()))
+    .re;
+
+    Zpk { zeros, poles, gain }
+}
+
+/// Analog Bessel prototype normalized by phase (same asymptote as Butterworth)
+pub fn bessel_prototype(order: usize) -> Result<Zpk, String> {
+    if order > MAX_BESSEL_ORDER {
+        return Err(format!(
+            "Bessel filter order must be at most {MAX_BESSEL_ORDER}"
+        ));
+    }
+    // Коэффициенты обратного полинома Бесселя a_k = (2n-k)! / (2^(n-k) k! (n-k)!)
+    let n = order;
+    let factorial = |k: usize| (1..=k).map(|i| i as f64).product::<f64>();
+    let a: Vec<f64> = (0..=n)
+        .map(|k| {
+            factorial(2 * n - k) / (2.0_f64.powi((n - k) as i32) * factorial(k) * factorial(n - k))
+        })
+        .collect();
+    // Замена s = a0^(1/n) x переводит нормировку по задержке в нормировку по фазе
+    let scale = a[0].powf(1.0 / n as f64);
+    let coeffs: Vec<f64> = (0..=n)
+        .rev()
+        .map(|k| a[k] * scale.powi(k as i32) / a[0])
+        .collect();
+
+    let poles = poly::roots(&coeffs);
+    let gain = product(&poles.iter().map(|p| -p).collect::<Vec<_>>()).re;
+
+    Ok(Zpk {
+        zeros: Vec::new(),
+        poles,
+        gain,
+    })
+}
+
+fn ellipk(m: f64) -> f64 {
+    // Полный эллиптический интеграл первого рода через арифметико-геометрическое среднее
+    PI / (2.0 * agm(1.0, (1.0 - m).sqrt()))
+}
+
+// K(1 - p), точнее чем ellipk(1 - p) для малых p
+fn ellipkm1(p: f64) -> f64 {
+    PI / (2.0 * agm(1.0, p.sqrt()))
+}
+
+fn agm(mut a: f64, mut b: f64) -> f64 {
+    while (a - b).abs() > 1e-15 * a {
+        let next = (a + b) / 2.0;
+        b = (a * b).sqrt();
+        a = next;
+    }
+    a
+}
+
+/// Jacobi elliptic functions sn, cn, dn by the descending Landen transform
+fn ellipj(u: f64, m: f64) -> (f64, f64, f64) {
+    if m < 1e-12 {
+        return (u.sin(), u.cos(), 1.0);
+    }
+    if m > 1.0 - 1e-12 {
+        let sech = 1.0 / u.cosh();
+        return (u.tanh(), sech, sech);
+    }
+
+    let mut a = vec![1.0];
+    let mut c = vec![m.sqrt()];
+    let mut b = (1.0 - m).sqrt();
+    while c[c.len() - 1].abs() > 1e-16 && a.len() < 40 {
+        let an = a[a.len() - 1];
+        let next = (an + b) / 2.0;
+        c.push((an - b) / 2.0);
+        b = (an * b).sqrt();
+        a.push(next);
+    }
+
+    let steps = a.len() - 1;
+    let mut phi = 2.0_f64.powi(steps as i32) * a[steps] * u;
+    let mut prev = phi;
+    for n in (1..=steps).rev() {
+        prev = phi;
+        phi = (phi + (c[n] / a[n] * phi.sin()).asin()) / 2.0;
+    }
+
+    let sn = phi.sin();
+    let cn = phi.cos();
+    let dn = cn / (prev - phi).cos();
+    (sn, cn, dn)
+}
+
+// Степень эллиптического модуля по порядку и модулю m1 (через ряды тета-функций)
+fn ellipdeg(order: usize, m1: f64) -> f64 {
+    let k1 = ellipk(m1);
+    let k1p = ellipkm1(m1);
+    let q1 = (-PI * k1p / k1).exp();
+    let q = q1.powf(1.0 / order as f64);
+
+    let num: f64 = (0..=7).map(|m| q.powi(m * (m + 1))).sum();
+    let den: f64 = 1.0 + 2.0 * (1..=8).map(|m| q.powi(m * m)).sum::<f64>();
+    16.0 * q * (num / den).powi(4)
+}
+
+// Обратная функция sc(u, 1 - m) для вещественного аргумента
+fn arc_jac_sc1(w: f64, m: f64) -> f64 {
+    let complement = |k: f64| ((1.0 - k) * (1.0 + k)).sqrt();
+    let mut ks = vec![m.sqrt()];
+    while ks[ks.len() - 1] != 0.0 && ks.len() < 20 {
+        let kp = complement(ks[ks.len() - 1]);
+        ks.push((1.0 - kp) / (1.0 + kp));
+    }
+    let capk: f64 = ks[1..].iter().map(|k| 1.0 + k).product::<f64>() * PI / 2.0;
+
+    // Аргумент чисто мнимый, поэтому итерации ведём по мнимой части
+    let mut wn = w;
+    for pair in ks.windows(2) {
+        let (kn, knext) = (pair[0], pair[1]);
+        wn = 2.0 * wn / ((1.0 + knext) * (1.0 + (1.0 + kn * kn * wn * wn).sqrt()));
+    }
+
+    capk * 2.0 / PI * wn.asinh()
+}
+
+/// Analog elliptic prototype, `ripple` dB in the passband up to 1 rad/s and
+/// at least `attenuation` dB in the stopband
+pub fn elliptic_prototype(order: usize, ripple: f64, attenuation: f64) -> Zpk {
+    let eps_sq = 10.0_f64.powf(0.1 * ripple) - 1.0;
+    if order == 1 {
+        let p = -(1.0 / eps_sq).sqrt();
+        return Zpk {
+            zeros: Vec::new(),
+            poles: vec![Complex::new(p, 0.0)],
+            gain: -p,
+        };
+    }
+
+    let eps = eps_sq.sqrt();
+    let ck1_sq = eps_sq / (10.0_f64.powf(0.1 * attenuation) - 1.0);
+    let k1 = ellipk(ck1_sq);
+    let m = ellipdeg(order, ck1_sq);
+    let capk = ellipk(m);
+
+    let j: Vec<f64> = ((1 - order % 2)..order)
+        .step_by(2)
+        .map(|j| j as f64)
+        .collect();
+    let jacobi: Vec<(f64, f64, f64)> = j
+        .iter()
+        .map(|&j| ellipj(j * capk / order as f64, m))
+        .collect();
+
+    let mut zeros: Vec<Complex<f64>> = jacobi
+        .iter()
+        .filter(|(s, _, _)| s.abs() > 1e-12)
+        .map(|(s, _, _)| Complex::new(0.0, 1.0 / (m.sqrt() * s)))
+        .collect();
+    let conj: Vec<Complex<f64>> = zeros.iter().map(|z| z.conj()).collect();
+    zeros.extend(conj);
+
+    let r = arc_jac_sc1(1.0 / eps, ck1_sq);
+    let v0 = capk * r / (order as f64 * k1);
+    let (sv, cv, dv) = ellipj(v0, 1.0 - m);
+
+    let mut poles: Vec<Complex<f64>> = jacobi
+        .iter()
+        .map(|&(s, c, d)| Complex::new(-c * d * sv * cv, -s * dv) / (1.0 - (d * sv).powi(2)))
+        .collect();
+    let conj: Vec<Complex<f64>> = poles
+        .iter()
+        .filter(|p| p.im.abs() > 1e-12 * p.norm())
+        .map(|p| p.conj())
+        .collect();
+    poles.extend(conj);
+
+    let mut gain = (product(&poles.iter().map(|p| -p).collect::<Vec<_>>())
+        / product(&zeros.iter().map(|z| -z).collect::<Vec<_>>()))
+    .re;
+    if order.is_multiple_of(2) {
+        gain /= (1.0 + eps_sq).sqrt();
+    }
+
+    Zpk { zeros, poles, gain }
+}
+
+fn lp2lp(zpk: &Zpk, wo: f64) -> Zpk {
+    Zpk {
+        zeros: zpk.zeros.iter().map(|z| z * wo).collect(),
+        poles: zpk.poles.iter().map(|p| p * wo).collect(),
+        gain: zpk.gain * wo.powi(zpk.degree() as i32),
+    }
+}
+
+fn lp2hp(zpk: &Zpk, wo: f64) -> Zpk {
+    let mut zeros: Vec<Complex<f64>> = zpk.zeros.iter().map(|z| wo / z).collect();
+    zeros.extend(vec![Complex::new(0.0, 0.0); zpk.degree()]);
+    let ratio = product(&zpk.zeros.iter().map(|z| -z).collect::<Vec<_>>())
+        / product(&zpk.poles.iter().map(|p| -p).collect::<Vec<_>>());
+
+    Zpk {
+        zeros,
+        poles: zpk.poles.iter().map(|p| wo / p).collect(),
+        gain: zpk.gain * ratio.re,
+    }
+}
+
+// Каждый корень r даёт пару r ± sqrt(r^2 - wo^2)
+fn split_roots(roots: &[Complex<f64>], wo: f64) -> Vec<Complex<f64>> {
+    let mut out: Vec<Complex<f64>> = Vec::with_capacity(2 * roots.len());
+    for r in roots {
+        out.push(r + (r * r - wo * wo).sqrt());
+    }
+    for r in roots {
+        out.push(r - (r * r - wo * wo).sqrt());
+    }
+    out
+}
+
+fn lp2bp(zpk: &Zpk, wo: f64, bw: f64) -> Zpk {
+    let z: Vec<Complex<f64>> = zpk.zeros.iter().map(|z| z * bw / 2.0).collect();
+    let p: Vec<Complex<f64>> = zpk.poles.iter().map(|p| p * bw / 2.0).collect();
+    let mut zeros = split_roots(&z, wo);
+    zeros.extend(vec![Complex::new(0.0, 0.0); zpk.degree()]);
+
+    Zpk {
+        zeros,
+        poles: split_roots(&p, wo),
+        gain: zpk.gain * bw.powi(zpk.degree() as i32),
+    }
+}
+
+fn lp2bs(zpk: &Zpk, wo: f64, bw: f64) -> Zpk {
+    let z: Vec<Complex<f64>> = zpk.zeros.iter().map(|z| (bw / 2.0) / z).collect();
+    let p: Vec<Complex<f64>> = zpk.poles.iter().map(|p| (bw / 2.0) / p).collect();
+    let mut zeros = split_roots(&z, wo);
+    zeros.extend(vec![Complex::new(0.0, wo); zpk.degree()]);
+    zeros.extend(vec![Complex::new(0.0, -wo); zpk.degree()]);
+    let ratio = product(&zpk.zeros.iter().map(|z| -z).collect::<Vec<_>>())
+        / product(&zpk.poles.iter().map(|p| -p).collect::<Vec<_>>());
+
+    Zpk {
+        zeros,
+        poles: split_roots(&p, wo),
+        gain: zpk.gain * ratio.re,
+    }
+}
+
+/// Bilinear transform of an analog filter to a digital one
+pub fn bilinear(zpk: &Zpk, fs: f64) -> Zpk {
+    let fs2 = 2.0 * fs;
+    let mut zeros: Vec<Complex<f64>> = zpk.zeros.iter().map(|z| (fs2 + z) / (fs2 - z)).collect();
+    zeros.extend(vec![Complex::new(-1.0, 0.0); zpk.degree()]);
+    let ratio = product(&zpk.zeros.iter().map(|z| fs2 - z).collect::<Vec<_>>())
+        / product(&zpk.poles.iter().map(|p| fs2 - p).collect::<Vec<_>>());
+
+    Zpk {
+        zeros,
+        poles: zpk.poles.iter().map(|p| (fs2 + p) / (fs2 - p)).collect(),
+        gain: zpk.gain * ratio.re,
+    }
+}
+
+// Разбивает корни на комплексно-сопряжённые пары (по одному представителю с im > 0)
+// и вещественные корни
+fn split_conjugates(roots: &[Complex<f64>]) -> (Vec<Complex<f64>>, Vec<f64>) {
+    let tol = 1e-9;
+    let pairs = roots
+        .iter()
+        .filter(|r| r.im > tol * r.norm().max(1.0))
+        .cloned()
+        .collect();
+    let reals = roots
+        .iter()
+        .filter(|r| r.im.abs() <= tol * r.norm().max(1.0))
+        .map(|r| r.re)
+        .collect();
+    (pairs, reals)
+}
+
+// Полином второго порядка [1, c1, c2] по не более чем двум корням
+enum RootGroup {
+    Pair(Complex<f64>),
+    Reals(Vec<f64>),
+}
+
+impl RootGroup {
+    fn polynomial(&self) -> [f64; 3] {
+        match self {
+            RootGroup::Pair(r) => [1.0, -2.0 * r.re, r.norm_sqr()],
+            RootGroup::Reals(reals) => match reals.as_slice() {
+                [] => [1.0, 0.0, 0.0],
+                [r] => [1.0, -r, 0.0],
+                [r1, r2, ..] => [1.0, -(r1 + r2), r1 * r2],
+            },
+        }
+    }
+
+    fn location(&self) -> Complex<f64> {
+        match self {
+            RootGroup::Pair(r) => *r,
+            RootGroup::Reals(reals) => Complex::new(reals.first().cloned().unwrap_or(0.0), 0.0),
+        }
+    }
+}
+
+/// Pairs poles and zeros into second-order sections. Sections with poles
+/// closer to the unit circle go last, the gain is put into the first one.
+pub fn zpk_to_sos(zpk: &Zpk) -> Vec<Biquad> {
+    let (pole_pairs, mut pole_reals) = split_conjugates(&zpk.poles);
+    let (mut zero_pairs, mut zero_reals) = split_conjugates(&zpk.zeros);
+
+    let mut pole_groups: Vec<RootGroup> = pole_pairs.into_iter().map(RootGroup::Pair).collect();
+    pole_reals.sort_by(|a, b| b.abs().partial_cmp(&a.abs()).unwrap());
+    for chunk in pole_reals.chunks(2) {
+        pole_groups.push(RootGroup::Reals(chunk.to_vec()));
+    }
+    // Начинаем с полюсов, ближайших к единичной окружности
+    pole_groups.sort_by(|a, b| {
+        b.location()
+            .norm()
+            .partial_cmp(&a.location().norm())
+            .unwrap()
+    });
+
+    let mut sections: Vec<Biquad> = Vec::with_capacity(pole_groups.len());
+    for group in pole_groups.iter() {
+        let target = group.location();
+        let need = match group {
+            RootGroup::Pair(_) => 2,
+            RootGroup::Reals(r) => r.len(),
+        };
+
+        let nearest_pair = zero_pairs
+            .iter()
+            .enumerate()
+            .min_by(|a, b| {
+                (a.1 - target)
+                    .norm()
+                    .partial_cmp(&(b.1 - target).norm())
+                    .unwrap()
+            })
+            .map(|(i, _)| i);
+
+        let zeros = if need == 2
+            && (zero_reals.len() < 2 || matches!(group, RootGroup::Pair(_)))
+            && let Some(i) = nearest_pair
+        {
+            RootGroup::Pair(zero_pairs.remove(i))
+        } else {
+            let mut chosen = Vec::new();
+            for _ in 0..need {
+                let nearest = zero_reals
+                    .iter()
+                    .enumerate()
+                    .min_by(|a, b| {
+                        (Complex::new(*a.1, 0.0) - target)
+                            .norm()
+                            .partial_cmp(&(Complex::new(*b.1, 0.0) - target).norm())
+                            .unwrap()
+                    })
+                    .map(|(i, _)| i);
+                if let Some(i) = nearest {
+                    chosen.push(zero_reals.remove(i));
+                }
+            }
+            RootGroup::Reals(chosen)
+        };
+
+        sections.push(Biquad::new(zeros.polynomial(), group.polynomial()));
+    }
+
+    sections.reverse();
+    if let Some(first) = sections.first_mut() {
+        first.b0 *= zpk.gain;
+        first.b1 *= zpk.gain;
+        first.b2 *= zpk.gain;
+    } else {
+        sections.push(Biquad::new([zpk.gain, 0.0, 0.0], [1.0, 0.0, 0.0]));
+    }
+
+    sections
+}
+
+/// Digital IIR filter as a cascade of second-order sections.
+///
+/// For bandpass and bandstop the resulting order is twice `order`.
+/// Cutoff meaning depends on the family: -3 dB for Butterworth, passband
+/// edge for Chebyshev I and elliptic, stopband edge for Chebyshev II and
+/// phase-normalized frequency for Bessel.
+#[allow(clippy::too_many_arguments)]
+pub fn iirfilter(
+    order: usize,
+    cutoffs: &[f64],
+    band: BandType,
+    family: IirFamily,
+    ripple: f64,
+    attenuation: f64,
+    fs: f64,
+) -> Result<Vec<Biquad>, String> {
+    if order == 0 {
+        return Err("Filter order must be positive".to_string());
+    }
+    if cutoffs.len() != band.cutoff_count() {
+        return Err(format!(
+            "{:?} filter needs {} cutoff frequencies, got {}",
+            band,
+            band.cutoff_count(),
+            cutoffs.len()
+        ));
+    }
+    let nyquist = fs / 2.0;
+    if cutoffs.iter().any(|&f| f <= 0.0 || f >= nyquist) {
+        return Err(format!("Cutoff frequencies must be in (0, {nyquist}) Hz"));
+    }
+    if cutoffs.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Cutoff frequencies must be increasing".to_string());
+    }
+    if matches!(family, IirFamily::Chebyshev1 | IirFamily::Elliptic) && ripple <= 0.0 {
+        return Err("Passband ripple must be positive".to_string());
+    }
+    if matches!(family, IirFamily::Chebyshev2 | IirFamily::Elliptic) && attenuation <= 0.0 {
+        return Err("Stopband attenuation must be positive".to_string());
+    }
+    if family == IirFamily::Elliptic && attenuation <= ripple {
+        return Err("Stopband attenuation must be greater than passband ripple".to_string());
+    }
+
+    let prototype = match family {
+        IirFamily::Butterworth => butterworth_prototype(order),
+        IirFamily::Chebyshev1 => chebyshev1_prototype(order, ripple),
+        IirFamily::Chebyshev2 => chebyshev2_prototype(order, attenuation),
+        IirFamily::Elliptic => elliptic_prototype(order, ripple, attenuation),
+        IirFamily::Bessel => bessel_prototype(order)?,
+    };
+
+    // Предыскажение частот среза для билинейного преобразования
+    let warped: Vec<f64> = cutoffs
+        .iter()
+        .map(|f| 2.0 * fs * (PI * f / fs).tan())
+        .collect();
+    let analog = match band {
+        BandType::Lowpass => lp2lp(&prototype, warped[0]),
+        BandType::Highpass => lp2hp(&prototype, warped[0]),
+        BandType::Bandpass => lp2bp(
+            &prototype,
+            (warped[0] * warped[1]).sqrt(),
+            warped[1] - warped[0],
+        ),
+        BandType::Bandstop => lp2bs(
+            &prototype,
+            (warped[0] * warped[1]).sqrt(),
+            warped[1] - warped[0],
+        ),
+    };
+
+    Ok(zpk_to_sos(&bilinear(&analog, fs)))
+}
+
+/// Runs the signal through the cascade, states of sections are kept
+pub fn sosfilt(sections: &mut [Biquad], signal: &[f64]) -> Vec<f64> {
+    let mut sig_new = signal.to_vec();
+    for section in sections.iter_mut() {
+        sig_new = section.process(&sig_new);
+    }
+    sig_new
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    const FS: f64 = 10000.0;
+
+    fn gain_db(sections: &[Biquad], freq: f64) -> f64 {
+        let w = 2.0 * PI * freq / FS;
+        let z = Complex::from_polar(1.0, -w);
+        let h = sections.iter().fold(Complex::new(1.0, 0.0), |acc, s| {
+            let (b, a) = s.coefficients();
+            let num = b[0] + b[1] * z + b[2] * z * z;
+            let den = a[0] + a[1] * z + a[2] * z * z;
+            acc * num / den
+        });
+        20.0 * h.norm().log10()
+    }
+
+    fn is_stable(sections: &[Biquad]) -> bool {
+        sections.iter().all(|s| {
+            let (_, a) = s.coefficients();
+            poly::roots(&a).iter().all(|p| p.norm() < 1.0)
+        })
+    }
+
+    #[test]
+    fn test_family_parse() {
+        assert_eq!(IirFamily::parse("butter"), Ok(IirFamily::Butterworth));
+        assert_eq!(IirFamily::parse("ellip"), Ok(IirFamily::Elliptic));
+        assert!(IirFamily::parse("chebyshev").is_err());
+    }
+
+    #[test]
+    fn test_butterworth_prototype() {
+        let zpk = butterworth_prototype(3);
+        assert_eq!(zpk.poles.len(), 3);
+        for p in zpk.poles.iter() {
+            assert!((p.norm() - 1.0).abs() < 1e-12);
+            assert!(p.re < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_chebyshev1_prototype_known_poles() {
+        // scipy.signal.cheb1ap(2, 1): poles -0.54887 ± 0.89513j, k = 0.98261
+        let zpk = chebyshev1_prototype(2, 1.0);
+        assert!((zpk.poles[0] - Complex::new(-0.548867, 0.895129)).norm() < 1e-5);
+        assert!((zpk.gain - 0.982613).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_bessel_prototype_known_poles() {
+        // s^2 + 3s + 3 с нормировкой по фазе: полюса -sqrt(3)/2 ± 0.5j
+        let zpk = bessel_prototype(2).unwrap();
+        let mut poles = zpk.poles.clone();
+        poles.sort_by(|a, b| a.im.partial_cmp(&b.im).unwrap());
+        assert!((poles[0] - Complex::new(-0.75_f64.sqrt(), -0.5)).norm() < 1e-12);
+        assert!((zpk.gain - 1.0).abs() < 1e-12);
+        assert!(bessel_prototype(MAX_BESSEL_ORDER + 1).is_err());
+    }
+
+    #[test]
+    fn test_elliptic_helpers() {
+        assert!((ellipk(0.0) - PI / 2.0).abs() < 1e-14);
+        assert!((ellipk(0.5) - 1.8540746773013719).abs() < 1e-12);
+        assert!((ellipkm1(0.5) - 1.8540746773013719).abs() < 1e-12);
+
+        let (sn, cn, dn) = ellipj(0.7, 0.3);
+        // Значения получены численным обращением интеграла F(phi, 0.3) = 0.7
+        assert!((sn - 0.6323048).abs() < 1e-6);
+        assert!((cn - 0.7747197).abs() < 1e-6);
+        assert!((dn - 0.9381136).abs() < 1e-6);
+        assert!((sn * sn + cn * cn - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_butterworth_lowpass() {
+        let sos = iirfilter(
+            4,
+            &[1000.0],
+            BandType::Lowpass,
+            IirFamily::Butterworth,
+            0.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        assert_eq!(sos.len(), 2);
+        assert!(gain_db(&sos, 0.0).abs() < 1e-9);
+        assert!((gain_db(&sos, 1000.0) + 3.0103).abs() < 1e-3);
+        // 24 дБ на октаву плюс сжатие частот билинейным преобразованием
+        assert!(gain_db(&sos, 2000.0) < -24.0);
+        assert!(is_stable(&sos));
+    }
+
+    #[test]
+    fn test_butterworth_highpass_odd_order() {
+        let sos = iirfilter(
+            5,
+            &[500.0],
+            BandType::Highpass,
+            IirFamily::Butterworth,
+            0.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        assert_eq!(sos.len(), 3);
+        assert!(gain_db(&sos, FS / 2.0).abs() < 1e-9);
+        assert!((gain_db(&sos, 500.0) + 3.0103).abs() < 1e-3);
+        assert!(gain_db(&sos, 100.0) < -60.0);
+    }
+
+    #[test]
+    fn test_chebyshev1_ripple() {
+        let sos = iirfilter(
+            5,
+            &[1000.0],
+            BandType::Lowpass,
+            IirFamily::Chebyshev1,
+            1.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        // Пульсации в полосе пропускания не больше 1 дБ, на краю ровно -1 дБ
+        for i in 0..100 {
+            let g = gain_db(&sos, 10.0 * i as f64);
+            assert!((-1.0 - 1e-9..=1e-9).contains(&g), "gain {g}");
+        }
+        assert!((gain_db(&sos, 1000.0) + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_chebyshev2_stopband() {
+        let sos = iirfilter(
+            6,
+            &[1500.0],
+            BandType::Lowpass,
+            IirFamily::Chebyshev2,
+            0.0,
+            40.0,
+            FS,
+        )
+        .unwrap();
+        assert!(gain_db(&sos, 0.0).abs() < 1e-9);
+        for i in 0..350 {
+            let g = gain_db(&sos, 1500.0 + 10.0 * i as f64);
+            assert!(g <= -40.0 + 1e-6, "gain {g}");
+        }
+    }
+
+    #[test]
+    fn test_elliptic_lowpass() {
+        for order in [3, 4, 7] {
+            let sos = iirfilter(
+                order,
+                &[1000.0],
+                BandType::Lowpass,
+                IirFamily::Elliptic,
+                0.5,
+                60.0,
+                FS,
+            )
+            .unwrap();
+            for i in 0..100 {
+                let g = gain_db(&sos, 10.0 * i as f64);
+                assert!((-0.5 - 1e-6..=1e-6).contains(&g), "order {order}: gain {g}");
+            }
+            assert!((gain_db(&sos, 1000.0) + 0.5).abs() < 1e-5);
+            assert!(is_stable(&sos));
+        }
+        // Эллиптический 7 порядка очень быстро уходит в полосу задерживания
+        let sos = iirfilter(
+            7,
+            &[1000.0],
+            BandType::Lowpass,
+            IirFamily::Elliptic,
+            0.5,
+            60.0,
+            FS,
+        )
+        .unwrap();
+        for i in 0..300 {
+            let g = gain_db(&sos, 1210.0 + 10.0 * i as f64);
+            assert!(g <= -60.0 + 1e-6, "gain {g}");
+        }
+    }
+
+    #[test]
+    fn test_bessel_lowpass() {
+        let sos = iirfilter(
+            4,
+            &[1000.0],
+            BandType::Lowpass,
+            IirFamily::Bessel,
+            0.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        assert!(gain_db(&sos, 0.0).abs() < 1e-9);
+        assert!(gain_db(&sos, 3000.0) < -20.0);
+        assert!(is_stable(&sos));
+    }
+
+    #[test]
+    fn test_bandpass_and_bandstop() {
+        let bp = iirfilter(
+            4,
+            &[1000.0, 2000.0],
+            BandType::Bandpass,
+            IirFamily::Butterworth,
+            0.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        assert_eq!(bp.len(), 4);
+        assert!((gain_db(&bp, 1000.0) + 3.0103).abs() < 1e-3);
+        assert!((gain_db(&bp, 2000.0) + 3.0103).abs() < 1e-3);
+        assert!(gain_db(&bp, 200.0) < -40.0);
+        assert!(is_stable(&bp));
+
+        let bs = iirfilter(
+            3,
+            &[1000.0, 2000.0],
+            BandType::Bandstop,
+            IirFamily::Chebyshev1,
+            0.5,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        assert_eq!(bs.len(), 3);
+        assert!(gain_db(&bs, 0.0).abs() < 1e-9);
+        assert!(gain_db(&bs, 1450.0) < -30.0);
+        assert!(is_stable(&bs));
+    }
+
+    #[test]
+    fn test_high_order_is_stable() {
+        let sos = iirfilter(
+            20,
+            &[100.0],
+            BandType::Lowpass,
+            IirFamily::Butterworth,
+            0.0,
+            0.0,
+            FS,
+        )
+        .unwrap();
+        assert_eq!(sos.len(), 10);
+        assert!(is_stable(&sos));
+
+        let mut filter = sos.clone();
+        let impulse: Vec<f64> = (0..20000).map(|i| if i == 0 { 1.0 } else { 0.0 }).collect();
+        let response = sosfilt(&mut filter, &impulse);
+        assert!(response.iter().all(|x| x.is_finite()));
+        assert!(response[19000..].iter().all(|x| x.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let lp = BandType::Lowpass;
+        let butter = IirFamily::Butterworth;
+        assert!(iirfilter(0, &[1000.0], lp, butter, 0.0, 0.0, FS).is_err());
+        assert!(iirfilter(2, &[6000.0], lp, butter, 0.0, 0.0, FS).is_err());
+        assert!(iirfilter(2, &[1000.0, 2000.0], lp, butter, 0.0, 0.0, FS).is_err());
+        assert!(iirfilter(2, &[1000.0], lp, IirFamily::Chebyshev1, 0.0, 0.0, FS).is_err());
+        assert!(iirfilter(2, &[1000.0], lp, IirFamily::Elliptic, 3.0, 2.0, FS).is_err());
+    }
+
+    #[test]
+    fn test_sosfilt_removes_high_tone() {
+        let low = generate::create_sine(100.0, 0.0, 1.0, 1.0);
+        let high = generate::create_sine(3000.0, 0.0, 1.0, 1.0);
+        let mixed: Vec<f64> = low.iter().zip(high.iter()).map(|(a, b)| a + b).collect();
+
+        let mut sos = iirfilter(
+            6,
+            &[1000.0],
+            BandType::Lowpass,
+            IirFamily::Elliptic,
+            0.1,
+            80.0,
+            FS,
+        )
+        .unwrap();
+        let result = sosfilt(&mut sos, &mixed);
+
+        let tail = &result[5000..];
+        let max = tail.iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+        assert!((max - 1.0).abs() < 0.02, "max {max}");
+    }
+}
@@ -0,0 +1,463 @@
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::biquad::Biquad;
+use crate::resample::{self, Quality};
+
+// Блок измерения громкости 400 мс с перекрытием 75%
+const BLOCK_SECONDS: f64 = 0.4;
+const BLOCK_OVERLAP: usize = 4;
+// Абсолютный и относительный пороги стробирования по BS.1770-4
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+// Кратковременная громкость за 3 с, ряды громкости обновляются 10 раз в секунду
+const SHORT_TERM_SECONDS: f64 = 3.0;
+const UPDATE_RATE: f64 = 10.0;
+// Диапазон громкости по EBU Tech 3342
+const RANGE_RELATIVE_GATE: f64 = -20.0;
+const RANGE_LOW: f64 = 0.10;
+const RANGE_HIGH: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalizeMode {
+    Peak,
+    TruePeak,
+    Rms,
+    Lufs,
+}
+
+impl NormalizeMode {
+    pub fn parse(name: &str) -> Result<NormalizeMode, String> {
+        match name {
+            "peak" => Ok(NormalizeMode::Peak),
+            "true-peak" => Ok(NormalizeMode::TruePeak),
+            "rms" => Ok(NormalizeMode::Rms),
+            "lufs" => Ok(NormalizeMode::Lufs),
+            _ => Err(format!(
+                "Unknown normalization {name}, expected peak, true-peak, rms or lufs"
+            )),
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            NormalizeMode::Peak | NormalizeMode::Rms => "dBFS",
+            NormalizeMode::TruePeak => "dBTP",
+            NormalizeMode::Lufs => "LUFS",
+        }
+    }
+}
+
+fn to_db(value: f64) -> f64 {
+    20.0 * value.log10()
+}
+
+/// K-weighting of BS.1770: high shelf for the head and high-pass (RLB) stages.
+/// The analog parameters reproduce the coefficients of the standard at 48 kHz
+/// and give the same response at any other sample rate.
+pub fn k_weighting(fs: f64) -> Vec<Biquad> {
+    let (f0, gain, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / fs).tan();
+    let vh = 10f64.powf(gain / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [
+            vh + vb * k / q + k * k,
+            2.0 * (k * k - vh),
+            vh - vb * k / q + k * k,
+        ],
+        [a0, 2.0 * (k * k - 1.0), 1.0 - k / q + k * k],
+    );
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / fs).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Biquad::new(
+        [1.0, -2.0, 1.0],
+        [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+    vec![shelf, highpass]
+}
+
+pub fn k_weighted(signal: &[f64], fs: f64) -> Vec<f64> {
+    k_weighting(fs)
+        .iter_mut()
+        .fold(signal.to_vec(), |sig, section| section.process(&sig))
+}
+
+/// Loudness in LUFS of the mean square of the K-weighted signal (mono, G = 1)
+pub fn lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+/// Mean squares of the K-weighted signal over blocks of `seconds`,
+/// blocks start every `seconds / overlap`
+pub fn block_powers(weighted: &[f64], fs: f64, seconds: f64, overlap: usize) -> Vec<f64> {
+    let length = (seconds * fs).round() as usize;
+    let step = (length / overlap).max(1);
+    if length == 0 || weighted.len() < length {
+        return Vec::new();
+    }
+    // Накопленные суммы квадратов, мощность блока считается разностью
+    let mut cumulative = Vec::with_capacity(weighted.len() + 1);
+    cumulative.push(0.0);
+    for x in weighted {
+        cumulative.push(cumulative.last().unwrap() + x * x);
+    }
+    (0..=(weighted.len() - length) / step)
+        .map(|j| ((cumulative[j * step + length] - cumulative[j * step]) / length as f64).max(0.0))
+        .collect()
+}
+
+// Средняя мощность блоков громче порога
+fn gated_mean(powers: &[f64], threshold: f64) -> Option<f64> {
+    let passed: Vec<f64> = powers
+        .iter()
+        .cloned()
+        .filter(|&p| lufs(p) > threshold)
+        .collect();
+    if passed.is_empty() {
+        None
+    } else {
+        Some(passed.iter().sum::<f64>() / passed.len() as f64)
+    }
+}
+
+fn integrated(weighted: &[f64], fs: f64) -> f64 {
+    let powers = block_powers(weighted, fs, BLOCK_SECONDS, BLOCK_OVERLAP);
+    let Some(absolute) = gated_mean(&powers, ABSOLUTE_GATE) else {
+        return f64::NEG_INFINITY;
+    };
+    match gated_mean(&powers, lufs(absolute) + RELATIVE_GATE) {
+        Some(relative) => lufs(relative),
+        None => f64::NEG_INFINITY,
+    }
+}
+
+/// Integrated loudness of BS.1770-4 with absolute (-70 LUFS) and
+/// relative (-10 LU) gating, minus infinity if every block is gated
+pub fn integrated_loudness(signal: &[f64], fs: f64) -> f64 {
+    integrated(&k_weighted(signal, fs), fs)
+}
+
+// Значение перцентиля с линейной интерполяцией между соседними элементами
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let position = p * (sorted.len() - 1) as f64;
+    let (low, high) = (position.floor() as usize, position.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (position - low as f64)
+}
+
+/// Loudness range of EBU Tech 3342 in LU: spread between the 10th and 95th
+/// percentiles of short-term loudness, gated at -70 LUFS and at -20 LU
+/// below the mean of the absolutely gated values
+fn range(short_term_powers: &[f64]) -> f64 {
+    let Some(absolute) = gated_mean(short_term_powers, ABSOLUTE_GATE) else {
+        return 0.0;
+    };
+    let threshold = lufs(absolute) + RANGE_RELATIVE_GATE;
+    let mut values: Vec<f64> = short_term_powers
+        .iter()
+        .map(|&p| lufs(p))
+        .filter(|&l| l > ABSOLUTE_GATE && l > threshold)
+        .collect();
+    values.sort_by(f64::total_cmp);
+    percentile(&values, RANGE_HIGH) - percentile(&values, RANGE_LOW)
+}
+
+/// Loudness meter of EBU R128: momentary (400 ms) and short-term (3 s)
+/// loudness every 100 ms, integrated loudness, loudness range and true peak
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoudnessReport {
+    pub integrated: f64,
+    pub momentary: Vec<f64>,
+    pub short_term: Vec<f64>,
+    pub range: f64,
+    pub true_peak: f64,
+}
+
+impl LoudnessReport {
+    pub fn measure(signal: &[f64], fs: f64) -> Result<LoudnessReport, String> {
+        let weighted = k_weighted(signal, fs);
+        let series = |seconds: f64| {
+            let overlap = (seconds * UPDATE_RATE).round() as usize;
+            block_powers(&weighted, fs, seconds, overlap)
+        };
+        let short_term = series(SHORT_TERM_SECONDS);
+        Ok(LoudnessReport {
+            integrated: integrated(&weighted, fs),
+            momentary: series(BLOCK_SECONDS).into_iter().map(lufs).collect(),
+            range: range(&short_term),
+            short_term: short_term.into_iter().map(lufs).collect(),
+            true_peak: to_db(true_peak(signal, fs)?),
+        })
+    }
+
+    pub fn max_momentary(&self) -> f64 {
+        self.momentary
+            .iter()
+            .cloned()
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    pub fn max_short_term(&self) -> f64 {
+        self.short_term
+            .iter()
+            .cloned()
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Save momentary and short-term loudness with the end time of each block,
+    /// short-term is empty during the first 3 seconds
+    pub fn save_csv(&self, filename: &str) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        writeln!(writer, "time,momentary,short_term")?;
+        let offset = self.momentary.len().saturating_sub(self.short_term.len());
+        for (i, m) in self.momentary.iter().enumerate() {
+            let time = BLOCK_SECONDS + i as f64 / UPDATE_RATE;
+            match i.checked_sub(offset) {
+                Some(j) => writeln!(writer, "{time:.1},{m},{}", self.short_term[j])?,
+                None => writeln!(writer, "{time:.1},{m},")?,
+            }
+        }
+        writer.flush()
+    }
+}
+
+pub fn peak(signal: &[f64]) -> f64 {
+    signal.iter().fold(0.0, |m, x| m.max(x.abs()))
+}
+
+/// True peak by oversampling to at least 192 kHz (4 times for 48 kHz,
+/// 20 times for 10 kHz)
+pub fn true_peak(signal: &[f64], fs: f64) -> Result<f64, String> {
+    let factor = ((192000.0 / fs).ceil() as usize).max(1);
+    let oversampled = resample::resample_poly(signal, factor, 1, Quality::Medium)?;
+    Ok(peak(&oversampled).max(peak(signal)))
+}
+
+pub fn rms(signal: &[f64]) -> f64 {
+    if signal.is_empty() {
+        return 0.0;
+    }
+    (signal.iter().map(|x| x * x).sum::<f64>() / signal.len() as f64).sqrt()
+}
+
+/// Level of the signal for the normalization mode in dBFS, dBTP or LUFS.
+/// RMS of a full-scale sine is -3.01 dBFS.
+pub fn level(signal: &[f64], fs: f64, mode: NormalizeMode) -> Result<f64, String> {
+    Ok(match mode {
+        NormalizeMode::Peak => to_db(peak(signal)),
+        NormalizeMode::TruePeak => to_db(true_peak(signal, fs)?),
+        NormalizeMode::Rms => to_db(rms(signal)),
+        NormalizeMode::Lufs => integrated_loudness(signal, fs),
+    })
+}
+
+/// Scale the signal in place to `target` level, returns measured level and gain in dB
+pub fn normalize(
+    signal: &mut [f64],
+    fs: f64,
+    mode: NormalizeMode,
+    target: f64,
+) -> Result<(f64, f64), String> {
+    let measured = level(signal, fs, mode)?;
+    if !measured.is_finite() {
+        return Err("Signal is silent, nothing to normalize".to_string());
+    }
+    let gain = target - measured;
+    let amplitude = 10f64.powf(gain / 20.0);
+    signal.iter_mut().for_each(|x| *x *= amplitude);
+    Ok((measured, gain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, signal};
+
+    fn sine(freq: f64, seconds: f64, amplitude: f64, fs: f64) -> Vec<f64> {
+        (0..(seconds * fs) as usize)
+            .map(|n| amplitude * (2.0 * PI * freq * n as f64 / fs).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_k_weighting_matches_standard_at_48k() {
+        let sections = k_weighting(48000.0);
+        let expected = [
+            [
+                1.53512485958697,
+                -2.69169618940638,
+                1.19839281085285,
+                -1.69065929318241,
+                0.73248077421585,
+            ],
+            [1.0, -2.0, 1.0, -1.99004745483398, 0.99007225036621],
+        ];
+        for (section, e) in sections.iter().zip(expected.iter()) {
+            let (b, a) = section.coefficients();
+            let actual = [b[0], b[1], b[2], a[1], a[2]];
+            for (x, y) in actual.iter().zip(e.iter()) {
+                assert!((x - y).abs() < 1e-10, "{x} {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_integrated_loudness_of_sine() {
+        // Синус 1 кГц с пиком 0 dBFS в одном канале дает -3.01 LUFS
+        let fs = 48000.0;
+        let loudness = integrated_loudness(&sine(1000.0, 2.0, 1.0, fs), fs);
+        assert!((loudness + 3.01).abs() < 0.02, "{loudness}");
+
+        // Тишина отсекается абсолютным порогом, тихий тон -63 LUFS относительным
+        let mut gated = sine(1000.0, 10.0, 0.1, fs);
+        gated.extend(vec![0.0; 480000]);
+        gated.extend(sine(1000.0, 10.0, 0.001, fs));
+        let loudness = integrated_loudness(&gated, fs);
+        assert!((loudness + 23.01).abs() < 0.1, "{loudness}");
+
+        assert_eq!(integrated_loudness(&[0.0; 48000], fs), f64::NEG_INFINITY);
+        assert_eq!(integrated_loudness(&[0.5; 100], fs), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_true_peak_between_samples() {
+        // Синус fs/4 с фазой 45 градусов: отсчёты на 0.707, истинный пик 1
+        let fs = 48000.0;
+        // Плавные края, иначе обрыв синуса даёт выброс интерполятора
+        let signal: Vec<f64> = (0..4800)
+            .map(|n| {
+                let fade = (n.min(4799 - n) as f64 / 480.0).min(1.0);
+                fade * (PI / 2.0 * n as f64 + PI / 4.0).sin()
+            })
+            .collect();
+        assert!((peak(&signal) - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        let tp = true_peak(&signal, fs).unwrap();
+        assert!((tp - 1.0).abs() < 0.01, "{tp}");
+    }
+
+    #[test]
+    fn test_true_peak_at_low_rate() {
+        // При 10 кГц нужна передискретизация в 20 раз: при 40 кГц отсчёты
+        // синуса 2.5 кГц с фазой 11.25 градуса на 2% ниже пика
+        let fs = 10000.0;
+        let signal: Vec<f64> = (0..10000)
+            .map(|n| {
+                let fade = (n.min(9999 - n) as f64 / 1000.0).min(1.0);
+                fade * (2.0 * PI * 2500.0 * n as f64 / fs + PI / 16.0).sin()
+            })
+            .collect();
+        let tp = true_peak(&signal, fs).unwrap();
+        assert!((tp - 1.0).abs() < 0.005, "{tp}");
+    }
+
+    #[test]
+    fn test_normalize_modes() {
+        let fs = 48000.0;
+        let original = sine(997.0, 1.0, 0.25, fs);
+
+        let mut signal = original.clone();
+        let (measured, gain) = normalize(&mut signal, fs, NormalizeMode::Peak, -1.0).unwrap();
+        assert!((measured - to_db(0.25)).abs() < 1e-3);
+        assert!((gain - (-1.0 - to_db(0.25))).abs() < 1e-3);
+        assert!((to_db(peak(&signal)) + 1.0).abs() < 1e-9);
+
+        let mut signal = original.clone();
+        normalize(&mut signal, fs, NormalizeMode::Rms, -20.0).unwrap();
+        assert!((to_db(rms(&signal)) + 20.0).abs() < 1e-9);
+
+        let mut signal = original.clone();
+        normalize(&mut signal, fs, NormalizeMode::Lufs, -23.0).unwrap();
+        assert!((integrated_loudness(&signal, fs) + 23.0).abs() < 1e-9);
+
+        let mut silence = vec![0.0; 100];
+        assert!(normalize(&mut silence, fs, NormalizeMode::Rms, -20.0).is_err());
+        assert_eq!(
+            NormalizeMode::parse("true-peak"),
+            Ok(NormalizeMode::TruePeak)
+        );
+        assert!(NormalizeMode::parse("lkfs").is_err());
+    }
+
+    // Тон 1 кГц по отрезкам (громкость LUFS, длительность с). Стереотон X dBFS
+    // из тестов EBU даёт X LUFS, в одном канале ему соответствует вдвое большая мощность
+    fn program(segments: &[(f64, f64)]) -> Vec<f64> {
+        segments
+            .iter()
+            .flat_map(|&(loudness, seconds)| {
+                let amplitude = (2.0 * 10f64.powf(loudness / 10.0)).sqrt();
+                generate::create_sine(1000.0, 0.0, seconds, amplitude)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ebu_tech_3341_conformance() {
+        let fs = signal::SAMPLE_RATE as f64;
+
+        // Тест 1: стационарный тон, все показания -23 LUFS
+        let report = LoudnessReport::measure(&program(&[(-23.0, 20.0)]), fs).unwrap();
+        assert!(
+            (report.integrated + 23.0).abs() < 0.1,
+            "{}",
+            report.integrated
+        );
+        assert_eq!(report.momentary.len(), 197);
+        assert_eq!(report.short_term.len(), 171);
+        for l in report.momentary.iter().chain(report.short_term.iter()) {
+            assert!((l + 23.0).abs() < 0.1, "{l}");
+        }
+        assert!((report.max_short_term() + 23.0).abs() < 0.1);
+
+        // Тест 2
+        let loudness = integrated_loudness(&program(&[(-33.0, 20.0)]), fs);
+        assert!((loudness + 33.0).abs() < 0.1, "{loudness}");
+
+        // Тесты 3 и 4: тихие участки отсекаются порогами
+        let loudness =
+            integrated_loudness(&program(&[(-36.0, 10.0), (-23.0, 60.0), (-36.0, 10.0)]), fs);
+        assert!((loudness + 23.0).abs() < 0.1, "{loudness}");
+        let segments = [
+            (-72.0, 10.0),
+            (-36.0, 10.0),
+            (-23.0, 60.0),
+            (-36.0, 10.0),
+            (-72.0, 10.0),
+        ];
+        let loudness = integrated_loudness(&program(&segments), fs);
+        assert!((loudness + 23.0).abs() < 0.1, "{loudness}");
+    }
+
+    #[test]
+    fn test_ebu_tech_3342_loudness_range() {
+        let fs = signal::SAMPLE_RATE as f64;
+        let lra = |segments: &[(f64, f64)]| {
+            let weighted = k_weighted(&program(segments), fs);
+            range(&block_powers(&weighted, fs, SHORT_TERM_SECONDS, 30))
+        };
+
+        let cases: [(&[(f64, f64)], f64); 4] = [
+            (&[(-20.0, 20.0), (-30.0, 20.0)], 10.0),
+            (&[(-20.0, 20.0), (-15.0, 20.0)], 5.0),
+            (&[(-40.0, 20.0), (-20.0, 20.0)], 20.0),
+            (
+                &[
+                    (-50.0, 20.0),
+                    (-35.0, 20.0),
+                    (-20.0, 20.0),
+                    (-35.0, 20.0),
+                    (-50.0, 20.0),
+                ],
+                15.0,
+            ),
+        ];
+        for (segments, expected) in cases {
+            let value = lra(segments);
+            assert!((value - expected).abs() < 1.0, "{value} {expected}");
+        }
+        assert_eq!(lra(&[(-80.0, 5.0)]), 0.0);
+    }
+}
@@ -1,9 +1,36 @@
+mod analysis;
 mod args;
+mod biquad;
+mod cic;
+mod crossover;
+mod delay;
 mod dsp;
+mod dynamics;
+mod effects;
+mod eq;
+mod filtfilt;
+mod fir;
 mod generate;
+mod hilbert;
+mod iir;
+mod loudness;
+mod poly;
+mod remez;
+mod resample;
+mod reverb;
 mod signal;
+mod smooth;
+mod stretch;
+mod taps;
+#[cfg(test)]
+mod test_util;
+mod view;
+mod xcorr;
 
-use args::{Cli, Commands, GenCommands};
+use args::{
+    Cli, Commands, DesignCommands, DynCommands, EffectCommands, FilterCommands, GenCommands,
+    ReverbCommands, SmoothCommands,
+};
 use clap::Parser;
 
 fn main() {
@@ -97,47 +124,50 @@ fn main() {
         Commands::Add {
             signal1,
             signal2,
+            align,
+            length,
             out_signal,
         } => {
-            let mut sig1: Vec<f64> = Vec::new();
-            let mut sig2: Vec<f64> = Vec::new();
-
-            let _ = signal::read_wave(&mut sig1, &signal1);
-            let _ = signal::read_wave(&mut sig2, &signal2);
+            let Some((sig1, sig2, sample_rate)) = read_pair(&signal1, &signal2, &align, &length)
+            else {
+                return;
+            };
 
             let result = dsp::add_signal(&sig1, &sig2);
 
-            let _ = signal::save_wave(&result, &out_signal);
+            let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
         }
         Commands::Sub {
             signal1,
             signal2,
+            align,
+            length,
             out_signal,
         } => {
-            let mut sig1: Vec<f64> = Vec::new();
-            let mut sig2: Vec<f64> = Vec::new();
-
-            let _ = signal::read_wave(&mut sig1, &signal1);
-            let _ = signal::read_wave(&mut sig2, &signal2);
+            let Some((sig1, sig2, sample_rate)) = read_pair(&signal1, &signal2, &align, &length)
+            else {
+                return;
+            };
 
             let result = dsp::sub_signal(&sig1, &sig2);
 
-            let _ = signal::save_wave(&result, &out_signal);
+            let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
         }
         Commands::Mux {
             signal1,
             signal2,
+            align,
+            length,
             out_signal,
         } => {
-            let mut sig1: Vec<f64> = Vec::new();
-            let mut sig2: Vec<f64> = Vec::new();
-
-            let _ = signal::read_wave(&mut sig1, &signal1);
-            let _ = signal::read_wave(&mut sig2, &signal2);
+            let Some((sig1, sig2, sample_rate)) = read_pair(&signal1, &signal2, &align, &length)
+            else {
+                return;
+            };
 
             let result = dsp::mux_signal(&sig1, &sig2);
 
-            let _ = signal::save_wave(&result, &out_signal);
+            let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
         }
         Commands::Scale {
             signal,
@@ -152,6 +182,303 @@ fn main() {
 
             let _ = signal::save_wave(&result, &out_signal);
         }
+        Commands::Dyn(dynamics) => {
+            let (result, out_signal, sample_rate) = match dynamics.command {
+                Some(DynCommands::Compress {
+                    signal,
+                    sidechain,
+                    threshold,
+                    ratio,
+                    knee,
+                    attack,
+                    release,
+                    makeup,
+                    detector,
+                    out_signal,
+                }) => {
+                    let Some((sig, sidechain, sample_rate)) = read_dynamics(&signal, sidechain)
+                    else {
+                        return;
+                    };
+                    let detector = match dynamics::Detector::parse(&detector) {
+                        Ok(detector) => detector,
+                        Err(e) => {
+                            println!("{e}");
+                            return;
+                        }
+                    };
+                    let processor = dynamics::Dynamics {
+                        kind: dynamics::DynamicsKind::Compressor,
+                        threshold,
+                        ratio,
+                        knee,
+                        attack: attack / 1000.0,
+                        release: release / 1000.0,
+                        makeup,
+                        range: f64::NEG_INFINITY,
+                        detector,
+                    };
+                    (
+                        processor.process(&sig, sidechain.as_deref(), sample_rate as f64),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(DynCommands::Limit {
+                    signal,
+                    sidechain,
+                    threshold,
+                    lookahead,
+                    release,
+                    out_signal,
+                }) => {
+                    let Some((sig, sidechain, sample_rate)) = read_dynamics(&signal, sidechain)
+                    else {
+                        return;
+                    };
+                    (
+                        dynamics::limit(
+                            &sig,
+                            sidechain.as_deref(),
+                            sample_rate as f64,
+                            threshold,
+                            lookahead / 1000.0,
+                            release / 1000.0,
+                        ),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(DynCommands::Expand {
+                    signal,
+                    sidechain,
+                    threshold,
+                    ratio,
+                    knee,
+                    attack,
+                    release,
+                    range,
+                    detector,
+                    out_signal,
+                }) => {
+                    let Some((sig, sidechain, sample_rate)) = read_dynamics(&signal, sidechain)
+                    else {
+                        return;
+                    };
+                    let detector = match dynamics::Detector::parse(&detector) {
+                        Ok(detector) => detector,
+                        Err(e) => {
+                            println!("{e}");
+                            return;
+                        }
+                    };
+                    let processor = dynamics::Dynamics {
+                        kind: dynamics::DynamicsKind::Expander,
+                        threshold,
+                        ratio,
+                        knee,
+                        attack: attack / 1000.0,
+                        release: release / 1000.0,
+                        makeup: 0.0,
+                        range,
+                        detector,
+                    };
+                    (
+                        processor.process(&sig, sidechain.as_deref(), sample_rate as f64),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(DynCommands::Gate {
+                    signal,
+                    sidechain,
+                    threshold,
+                    attack,
+                    release,
+                    range,
+                    detector,
+                    out_signal,
+                }) => {
+                    let Some((sig, sidechain, sample_rate)) = read_dynamics(&signal, sidechain)
+                    else {
+                        return;
+                    };
+                    let detector = match dynamics::Detector::parse(&detector) {
+                        Ok(detector) => detector,
+                        Err(e) => {
+                            println!("{e}");
+                            return;
+                        }
+                    };
+                    let processor = dynamics::Dynamics {
+                        kind: dynamics::DynamicsKind::Gate,
+                        threshold,
+                        ratio: 1.0,
+                        knee: 0.0,
+                        attack: attack / 1000.0,
+                        release: release / 1000.0,
+                        makeup: 0.0,
+                        range,
+                        detector,
+                    };
+                    (
+                        processor.process(&sig, sidechain.as_deref(), sample_rate as f64),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(DynCommands::Agc {
+                    signal,
+                    target,
+                    max_gain,
+                    attack,
+                    decay,
+                    hold,
+                    block_size,
+                    out_signal,
+                }) => {
+                    let sample_rate = match signal::read_sample_rate(&signal) {
+                        Ok(rate) => rate,
+                        Err(e) => {
+                            println!("Fail to read {signal}: {e}");
+                            return;
+                        }
+                    };
+                    let mut agc = match dynamics::Agc::new(
+                        target,
+                        max_gain,
+                        attack / 1000.0,
+                        decay / 1000.0,
+                        hold / 1000.0,
+                        sample_rate as f64,
+                    ) {
+                        Ok(agc) => agc,
+                        Err(e) => {
+                            println!("Error in dynamics processing: {e}");
+                            return;
+                        }
+                    };
+                    match signal::process_wave_blocks(&signal, &out_signal, block_size, |block| {
+                        agc.process(block)
+                    }) {
+                        Ok(total) => {
+                            println!("Process {total} samples, final gain {:.1} dB", agc.gain())
+                        }
+                        Err(e) => println!("Fail to process {signal}: {e}"),
+                    }
+                    return;
+                }
+                None => {
+                    println!("Do nothing");
+                    return;
+                }
+            };
+            match result {
+                Ok((result, gain)) => {
+                    let lo = gain.iter().cloned().fold(f64::INFINITY, f64::min);
+                    let hi = gain.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                    println!("Gain from {lo:.1} dB to {hi:.1} dB");
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                }
+                Err(e) => println!("Error in dynamics processing: {e}"),
+            }
+        }
+        Commands::Normalize {
+            signal,
+            mode,
+            target,
+            out_signal,
+        } => {
+            let mode = match loudness::NormalizeMode::parse(&mode) {
+                Ok(mode) => mode,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+
+            match loudness::normalize(&mut sig, sample_rate as f64, mode, target) {
+                Ok((measured, gain)) => {
+                    let unit = mode.unit();
+                    println!(
+                        "Level {measured:.2} {unit}, gain {gain:+.2} dB to {target:.2} {unit}"
+                    );
+                    let _ = signal::save_wave_with_rate(&sig, &out_signal, sample_rate);
+                }
+                Err(e) => println!("Error in normalization: {e}"),
+            }
+        }
+        Commands::Loudness {
+            signal,
+            target,
+            tolerance,
+            max_true_peak,
+            out_csv,
+        } => {
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+
+            let report = match loudness::LoudnessReport::measure(&sig, sample_rate as f64) {
+                Ok(report) => report,
+                Err(e) => {
+                    println!("Error in loudness measurement: {e}");
+                    return;
+                }
+            };
+            println!("Integrated loudness: {:.1} LUFS", report.integrated);
+            println!("Loudness range: {:.1} LU", report.range);
+            println!("Max momentary loudness: {:.1} LUFS", report.max_momentary());
+            println!(
+                "Max short-term loudness: {:.1} LUFS",
+                report.max_short_term()
+            );
+            println!("True peak: {:.1} dBTP", report.true_peak);
+
+            let mut passed = true;
+            if let Some(target) = target {
+                let meets_target = (report.integrated - target).abs() <= tolerance;
+                let within_ceiling = report.true_peak <= max_true_peak;
+                passed = meets_target && within_ceiling;
+                let verdict = if meets_target { "meets" } else { "misses" };
+                println!(
+                    "Integrated loudness {:.1} LUFS {verdict} target {target:.1} ±{tolerance:.1} LU",
+                    report.integrated
+                );
+                let verdict = if within_ceiling {
+                    "is within"
+                } else {
+                    "exceeds"
+                };
+                println!(
+                    "True peak {:.1} dBTP {verdict} {max_true_peak:.1} dBTP",
+                    report.true_peak
+                );
+            }
+            if let Some(out_csv) = out_csv {
+                match report.save_csv(&out_csv) {
+                    Ok(()) => println!("Save loudness series to {out_csv}"),
+                    Err(e) => println!("Fail to save {out_csv}: {e}"),
+                }
+            }
+            // Ненулевой код возврата для проверки в скриптах и CI
+            if !passed {
+                std::process::exit(1);
+            }
+        }
         Commands::MovAverage {
             signal,
             kernel_length,
@@ -165,5 +492,1474 @@ fn main() {
 
             let _ = signal::save_wave(&result, &out_signal);
         }
+        Commands::Smooth(smooth) => {
+            let (result, out_signal, sample_rate) = match smooth.command {
+                Some(SmoothCommands::Median {
+                    signal,
+                    window,
+                    edge,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate, edge)) = read_smoothing(&signal, &edge) else {
+                        return;
+                    };
+                    (smooth::median(&sig, window, edge), out_signal, sample_rate)
+                }
+                Some(SmoothCommands::Savgol {
+                    signal,
+                    window,
+                    polyorder,
+                    deriv,
+                    edge,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate, edge)) = read_smoothing(&signal, &edge) else {
+                        return;
+                    };
+                    (
+                        smooth::savgol(&sig, window, polyorder, deriv, edge),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(SmoothCommands::Ema {
+                    signal,
+                    span,
+                    edge,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate, edge)) = read_smoothing(&signal, &edge) else {
+                        return;
+                    };
+                    (smooth::ema(&sig, span, edge), out_signal, sample_rate)
+                }
+                Some(SmoothCommands::Gaussian {
+                    signal,
+                    sigma,
+                    edge,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate, edge)) = read_smoothing(&signal, &edge) else {
+                        return;
+                    };
+                    (smooth::gaussian(&sig, sigma, edge), out_signal, sample_rate)
+                }
+                None => {
+                    println!("Do nothing");
+                    return;
+                }
+            };
+            match result {
+                Ok(result) => {
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                    println!("Save {} samples to {out_signal}", result.len());
+                }
+                Err(e) => println!("Error in smoothing: {e}"),
+            }
+        }
+        Commands::Eq {
+            signal,
+            preset,
+            out_signal,
+        } => {
+            let preset = match eq::Preset::read(&preset) {
+                Ok(preset) => preset,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+
+            match preset.apply(&sig, sample_rate as f64) {
+                Ok(result) => {
+                    let enabled = preset.bands.iter().filter(|band| band.enabled).count();
+                    println!("Apply {enabled} bands, preamp {:+.1} dB", preset.preamp);
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                    println!("Save {} samples to {out_signal}", result.len());
+                }
+                Err(e) => println!("Error in equalizer: {e}"),
+            }
+        }
+        Commands::Crossover {
+            signal,
+            freqs,
+            crossover_type,
+            order,
+            length,
+            out_prefix,
+        } => {
+            let crossover_type = match crossover::CrossoverType::parse(&crossover_type) {
+                Ok(crossover_type) => crossover_type,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+            let fs = sample_rate as f64;
+
+            let bands = match crossover_type {
+                crossover::CrossoverType::LinkwitzRiley => {
+                    crossover::linkwitz_riley(&sig, &freqs, order, fs)
+                }
+                crossover::CrossoverType::Fir => crossover::linear_phase(&sig, &freqs, length, fs),
+            };
+            match bands {
+                Ok(bands) => {
+                    // Границы полос от нуля до частоты Найквиста
+                    let mut edges = vec![0.0];
+                    edges.extend_from_slice(&freqs);
+                    edges.push(fs / 2.0);
+                    for (i, band) in bands.iter().enumerate() {
+                        let out_signal = format!("{out_prefix}_{}.wav", i + 1);
+                        let _ = signal::save_wave_with_rate(band, &out_signal, sample_rate);
+                        println!("Save band {}-{} Hz to {out_signal}", edges[i], edges[i + 1]);
+                    }
+                }
+                Err(e) => println!("Error in crossover: {e}"),
+            }
+        }
+        Commands::Filter(filter) => match filter.command {
+            Some(FilterCommands::Fir {
+                signal,
+                band_type,
+                cutoff,
+                length,
+                transition,
+                attenuation,
+                window,
+                taps_out,
+                out_signal,
+            }) => {
+                let mut sig: Vec<f64> = Vec::new();
+
+                let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                    Ok(rate) => rate,
+                    Err(e) => {
+                        println!("Fail to read {signal}: {e}");
+                        return;
+                    }
+                };
+
+                let taps =
+                    fir::BandType::parse(&band_type).and_then(|band| match (length, transition) {
+                        (Some(length), _) => {
+                            let window = fir::Window::parse(&window, attenuation)?;
+                            fir::firwin(length, &cutoff, band, window, sample_rate as f64)
+                        }
+                        (None, Some(transition)) => fir::firwin_kaiser(
+                            &cutoff,
+                            band,
+                            attenuation,
+                            transition,
+                            sample_rate as f64,
+                        ),
+                        (None, None) => Err("Set --length or --transition".to_string()),
+                    });
+
+                match taps {
+                    Ok(taps) => {
+                        if let Some(taps_out) = taps_out
+                            && let Err(e) = taps::save_taps(&taps, &taps_out)
+                        {
+                            println!("Fail to save taps to {taps_out}: {e}");
+                        }
+                        let result = fir::fir_filter(&sig, &taps);
+                        let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                        println!("Filter with {} taps", taps.len());
+                    }
+                    Err(e) => {
+                        println!("Error in filter design: {e}");
+                    }
+                }
+            }
+            Some(FilterCommands::Biquad {
+                signal,
+                kind,
+                freq,
+                q,
+                gain,
+                out_signal,
+            }) => {
+                let mut sig: Vec<f64> = Vec::new();
+
+                let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                    Ok(rate) => rate,
+                    Err(e) => {
+                        println!("Fail to read {signal}: {e}");
+                        return;
+                    }
+                };
+
+                let filter = biquad::BiquadType::parse(&kind).and_then(|kind| {
+                    biquad::Biquad::design(kind, freq, q, gain, sample_rate as f64)
+                });
+
+                match filter {
+                    Ok(mut filter) => {
+                        let (b, a) = filter.coefficients();
+                        let result = filter.process(&sig);
+                        let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                        println!("Filter with {kind} biquad: b = {b:?}, a = {a:?}");
+                    }
+                    Err(e) => {
+                        println!("Error in filter design: {e}");
+                    }
+                }
+            }
+            Some(FilterCommands::Iir {
+                signal,
+                family,
+                band_type,
+                cutoff,
+                order,
+                ripple,
+                attenuation,
+                sos_out,
+                out_signal,
+            }) => {
+                let mut sig: Vec<f64> = Vec::new();
+
+                let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                    Ok(rate) => rate,
+                    Err(e) => {
+                        println!("Fail to read {signal}: {e}");
+                        return;
+                    }
+                };
+
+                let sections = iir::IirFamily::parse(&family).and_then(|family| {
+                    let band = fir::BandType::parse(&band_type)?;
+                    iir::iirfilter(
+                        order,
+                        &cutoff,
+                        band,
+                        family,
+                        ripple,
+                        attenuation,
+                        sample_rate as f64,
+                    )
+                });
+
+                match sections {
+                    Ok(mut sections) => {
+                        if let Some(sos_out) = sos_out {
+                            let coeffs: Vec<_> =
+                                sections.iter().map(|s| s.coefficients()).collect();
+                            if let Err(e) = taps::save_sos(&coeffs, &sos_out) {
+                                println!("Fail to save sections to {sos_out}: {e}");
+                            }
+                        }
+                        let result = iir::sosfilt(&mut sections, &sig);
+                        let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                        println!("Filter with {} second-order sections", sections.len());
+                    }
+                    Err(e) => {
+                        println!("Error in filter design: {e}");
+                    }
+                }
+            }
+            Some(FilterCommands::Filtfilt {
+                signal,
+                taps,
+                numerator,
+                denominator,
+                sos,
+                out_signal,
+            }) => {
+                let mut sig: Vec<f64> = Vec::new();
+
+                let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                    Ok(rate) => rate,
+                    Err(e) => {
+                        println!("Fail to read {signal}: {e}");
+                        return;
+                    }
+                };
+
+                let result = match (taps, numerator, sos) {
+                    (Some(taps), _, _) => {
+                        taps::read_taps(&taps).and_then(|b| filtfilt::filtfilt(&b, &[1.0], &sig))
+                    }
+                    (_, Some(numerator), _) => taps::read_taps(&numerator).and_then(|b| {
+                        let a = match denominator {
+                            Some(denominator) => taps::read_taps(&denominator)?,
+                            None => vec![1.0],
+                        };
+                        filtfilt::filtfilt(&b, &a, &sig)
+                    }),
+                    (_, _, Some(sos)) => taps::read_sos(&sos).and_then(|coeffs| {
+                        let sections: Vec<biquad::Biquad> = coeffs
+                            .iter()
+                            .map(|(b, a)| biquad::Biquad::new(*b, *a))
+                            .collect();
+                        filtfilt::sosfiltfilt(&sections, &sig)
+                    }),
+                    _ => Err("Set --taps, --numerator or --sos".to_string()),
+                };
+
+                match result {
+                    Ok(result) => {
+                        let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                        println!("Filter forward and backward");
+                    }
+                    Err(e) => {
+                        println!("Error in filtering: {e}");
+                    }
+                }
+            }
+            _ => {
+                println!("Do nothing");
+            }
+        },
+        Commands::Design(design) => match design.command {
+            Some(DesignCommands::Remez {
+                length,
+                bands,
+                desired,
+                weight,
+                filter_type,
+                fs,
+                out_taps,
+            }) => {
+                let weight = if weight.is_empty() {
+                    vec![1.0; bands.len() / 2]
+                } else {
+                    weight
+                };
+                let taps = remez::RemezType::parse(&filter_type).and_then(|filter_type| {
+                    remez::remez(length, &bands, &desired, &weight, filter_type, fs)
+                });
+
+                match taps {
+                    Ok(taps) => match taps::save_taps(&taps, &out_taps) {
+                        Ok(()) => println!("Design filter with {} taps", taps.len()),
+                        Err(e) => println!("Fail to save taps to {out_taps}: {e}"),
+                    },
+                    Err(e) => {
+                        println!("Error in filter design: {e}");
+                    }
+                }
+            }
+            _ => {
+                println!("Do nothing");
+            }
+        },
+        Commands::View {
+            signal,
+            mode,
+            start,
+            end,
+            width,
+            height,
+            charset,
+        } => {
+            let mut sig: Vec<f64> = Vec::new();
+
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+            let charset = match view::Charset::parse(&charset) {
+                Ok(charset) => charset,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            if width == 0 || height == 0 {
+                println!("Width and height must be positive");
+                return;
+            }
+            let part = view::time_range(&sig, sample_rate, start, end);
+            if part.is_empty() {
+                println!("Empty time range");
+                return;
+            }
+
+            match mode.as_str() {
+                "wave" => print!("{}", view::render_waveform(part, width, height, charset)),
+                "spectrum" => print!(
+                    "{}",
+                    view::render_spectrum(part, sample_rate, width, height, charset)
+                ),
+                _ => println!("Unknown mode {mode}"),
+            }
+        }
+        Commands::Convolve {
+            signal,
+            kernel,
+            mode,
+            method,
+            out_signal,
+        } => {
+            let mut sig: Vec<f64> = Vec::new();
+
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+
+            let taps = if kernel.ends_with(".wav") {
+                let mut taps: Vec<f64> = Vec::new();
+                match signal::read_wave_with_rate(&mut taps, &kernel) {
+                    Ok(rate) if rate != sample_rate => Err(format!(
+                        "Sample rate of kernel {rate} Hz differs from signal {sample_rate} Hz"
+                    )),
+                    Ok(_) => Ok(taps),
+                    Err(e) => Err(format!("Fail to read {kernel}: {e}")),
+                }
+            } else {
+                taps::read_taps(&kernel)
+            };
+            let taps = match taps {
+                Ok(taps) => taps,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+
+            let params = dsp::ConvolveMode::parse(&mode)
+                .and_then(|mode| Ok((mode, dsp::ConvolveMethod::parse(&method)?)));
+            match params {
+                Ok((mode, method)) => {
+                    let method = method.resolve(sig.len(), taps.len());
+                    let result = dsp::convolve(&sig, &taps, mode, method);
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                    println!("Convolve by {method:?} method, {} samples", result.len());
+                }
+                Err(e) => {
+                    println!("{e}");
+                }
+            }
+        }
+        Commands::AnalyzeFilter {
+            taps,
+            numerator,
+            denominator,
+            sos,
+            mov_average,
+            points,
+            fs,
+            out_csv,
+            pz_out,
+            width,
+            height,
+            charset,
+        } => {
+            let charset = match view::Charset::parse(&charset) {
+                Ok(charset) => charset,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let filter = match analysis::load_filter(
+                taps.as_deref(),
+                numerator.as_deref(),
+                denominator.as_deref(),
+                sos.as_deref(),
+                mov_average,
+            ) {
+                Ok(filter) => filter,
+                Err(e) => {
+                    println!("Fail to load filter: {e}");
+                    return;
+                }
+            };
+
+            let (zeros, poles) = analysis::zeros_poles(&filter);
+            let radius = poles.iter().map(|p| p.norm()).fold(0.0, f64::max);
+            println!(
+                "Zeros: {}, poles: {}, max pole radius: {radius:.6}",
+                zeros.len(),
+                poles.len()
+            );
+            if analysis::is_stable(&poles) {
+                println!("Filter is stable");
+            } else {
+                println!("Filter is unstable");
+            }
+
+            let freqs = analysis::frequency_grid(points, fs);
+            let magnitude = analysis::magnitude_db(&analysis::freqz(&filter, &freqs, fs));
+            // Глубокие нули не дают рассмотреть полосу пропускания
+            let top = magnitude.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let magnitude: Vec<f64> = magnitude.iter().map(|m| m.max(top - 120.0)).collect();
+            let delay = analysis::group_delay(&filter, &freqs, fs);
+            println!(
+                "{}",
+                view::render_curve(&magnitude, "magnitude, dB", width, height, charset)
+            );
+            println!(
+                "{}",
+                view::render_curve(&delay, "group delay, samples", width, height, charset)
+            );
+
+            match analysis::save_response(&filter, &freqs, fs, &out_csv) {
+                Ok(()) => println!("Save response to {out_csv}"),
+                Err(e) => println!("Fail to save response to {out_csv}: {e}"),
+            }
+            if let Some(pz_out) = pz_out
+                && let Err(e) = analysis::save_zeros_poles(&zeros, &poles, &pz_out)
+            {
+                println!("Fail to save zeros and poles to {pz_out}: {e}");
+            }
+        }
+        Commands::Resample {
+            signal,
+            to,
+            quality,
+            method,
+            out_signal,
+        } => {
+            let params = resample::Quality::parse(&quality)
+                .and_then(|quality| Ok((quality, resample::ResampleMethod::parse(&method)?)));
+            let (quality, method) = match params {
+                Ok(params) => params,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+
+            match resample::resample(&sig, sample_rate, to, method, quality) {
+                Ok((result, method)) => {
+                    match method {
+                        resample::ResampleMethod::Arbitrary => println!(
+                            "Resample {sample_rate} Hz to {to} Hz with arbitrary ratio {:.6}",
+                            to as f64 / sample_rate as f64
+                        ),
+                        _ => {
+                            let (up, down) = resample::rational_factors(sample_rate, to);
+                            println!(
+                                "Resample {sample_rate} Hz to {to} Hz by polyphase filter, up {up}, down {down}"
+                            );
+                        }
+                    }
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, to);
+                }
+                Err(e) => {
+                    println!("Error in resampling: {e}");
+                }
+            }
+        }
+        Commands::Stretch {
+            signal,
+            factor,
+            method,
+            frame,
+            out_signal,
+        } => {
+            let Some((sig, sample_rate, method)) = read_stretch(&signal, &method) else {
+                return;
+            };
+            match stretch::stretch(&sig, factor, method, frame) {
+                Ok(result) => {
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                    println!("Save {} samples to {out_signal}", result.len());
+                }
+                Err(e) => println!("Error in stretching: {e}"),
+            }
+        }
+        Commands::Pitch {
+            signal,
+            semitones,
+            method,
+            frame,
+            out_signal,
+        } => {
+            let Some((sig, sample_rate, method)) = read_stretch(&signal, &method) else {
+                return;
+            };
+            match stretch::pitch_shift(&sig, semitones, method, frame) {
+                Ok(result) => {
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                    println!("Save {} samples to {out_signal}", result.len());
+                }
+                Err(e) => println!("Error in pitch shifting: {e}"),
+            }
+        }
+        Commands::Decimate {
+            signal,
+            factor,
+            filter,
+            zero_phase,
+            quality,
+            out_signal,
+        } => {
+            let params = resample::RateFilter::parse(&filter)
+                .and_then(|filter| Ok((filter, resample::Quality::parse(&quality)?)));
+            let (filter, quality) = match params {
+                Ok(params) => params,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            if let Err(e) = resample::check_factor(factor) {
+                println!("Error in resampling: {e}");
+                return;
+            }
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+            // Множитель 1 не меняет частоту, сигнал копируется без фильтрации
+            if factor == 1 {
+                let _ = signal::save_wave_with_rate(&sig, &out_signal, sample_rate);
+                println!("Factor 1 keeps {sample_rate} Hz, copy signal to {out_signal}");
+                return;
+            }
+            if !sample_rate.is_multiple_of(factor as u32) {
+                println!("Sample rate {sample_rate} Hz is not divisible by {factor}");
+                return;
+            }
+            let new_rate = sample_rate / factor as u32;
+
+            match resample::decimate(&sig, factor, filter, zero_phase, quality) {
+                Ok(result) => {
+                    let stages: Vec<String> = resample::stage_factors(factor)
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect();
+                    println!(
+                        "Decimate {sample_rate} Hz to {new_rate} Hz in stages {}",
+                        stages.join(", ")
+                    );
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, new_rate);
+                }
+                Err(e) => {
+                    println!("Error in resampling: {e}");
+                }
+            }
+        }
+        Commands::Interpolate {
+            signal,
+            factor,
+            filter,
+            zero_phase,
+            quality,
+            out_signal,
+        } => {
+            let params = resample::RateFilter::parse(&filter)
+                .and_then(|filter| Ok((filter, resample::Quality::parse(&quality)?)));
+            let (filter, quality) = match params {
+                Ok(params) => params,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            if let Err(e) = resample::check_factor(factor) {
+                println!("Error in resampling: {e}");
+                return;
+            }
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+            // Множитель 1 не меняет частоту, сигнал копируется без фильтрации
+            if factor == 1 {
+                let _ = signal::save_wave_with_rate(&sig, &out_signal, sample_rate);
+                println!("Factor 1 keeps {sample_rate} Hz, copy signal to {out_signal}");
+                return;
+            }
+            let Some(new_rate) = sample_rate.checked_mul(factor as u32) else {
+                println!("Sample rate {sample_rate} Hz multiplied by {factor} is too high");
+                return;
+            };
+
+            match resample::interpolate(&sig, factor, filter, zero_phase, quality) {
+                Ok(result) => {
+                    let stages: Vec<String> = resample::stage_factors(factor)
+                        .iter()
+                        .rev()
+                        .map(|s| s.to_string())
+                        .collect();
+                    println!(
+                        "Interpolate {sample_rate} Hz to {new_rate} Hz in stages {}",
+                        stages.join(", ")
+                    );
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, new_rate);
+                }
+                Err(e) => {
+                    println!("Error in resampling: {e}");
+                }
+            }
+        }
+        Commands::Cic {
+            signal,
+            mode,
+            rate,
+            stages,
+            delay,
+            bits,
+            compensation,
+            passband,
+            taps_out,
+            out_signal,
+        } => {
+            let cic = match cic::CicMode::parse(&mode)
+                .and_then(|mode| cic::Cic::new(stages, delay, rate, mode))
+            {
+                Ok(cic) => cic,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            if !(2..=32).contains(&bits) {
+                println!("Input must have 2..=32 bits, got {bits}");
+                return;
+            }
+            let width = cic.register_width(bits);
+            if width > 64 {
+                println!("Register width {width} bits exceeds 64 bits");
+                return;
+            }
+            let taps = match compensation.map(|length| cic.compensation_filter(length, passband)) {
+                Some(Ok(taps)) => Some(taps),
+                Some(Err(e)) => {
+                    println!("Error in filter design: {e}");
+                    return;
+                }
+                None => None,
+            };
+
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+            let new_rate = match cic.mode {
+                cic::CicMode::Decimator if sample_rate.is_multiple_of(rate as u32) => {
+                    sample_rate / rate as u32
+                }
+                cic::CicMode::Decimator => {
+                    println!("Sample rate {sample_rate} Hz is not divisible by {rate}");
+                    return;
+                }
+                cic::CicMode::Interpolator => match sample_rate.checked_mul(rate as u32) {
+                    Some(new_rate) => new_rate,
+                    None => {
+                        println!("Sample rate {sample_rate} Hz multiplied by {rate} is too high");
+                        return;
+                    }
+                },
+            };
+
+            // Интерполятор корректируется до CIC, дециматор после, оба на низкой частоте
+            if cic.mode == cic::CicMode::Interpolator
+                && let Some(taps) = &taps
+            {
+                sig = fir::fir_filter(&sig, taps);
+            }
+            let full_scale = ((1i64 << (bits - 1)) - 1) as f64;
+            let input: Vec<i64> = sig
+                .iter()
+                .map(|x| (x.clamp(-1.0, 1.0) * full_scale).round() as i64)
+                .collect();
+            let output = match cic.process(&input, width) {
+                Ok(output) => output,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let scale = cic.gain() * full_scale;
+            let mut result: Vec<f64> = output.iter().map(|&y| y as f64 / scale).collect();
+            if cic.mode == cic::CicMode::Decimator
+                && let Some(taps) = &taps
+            {
+                result = fir::fir_filter(&result, taps);
+            }
+
+            println!(
+                "CIC {}: {stages} stages, delay {delay}, rate {rate}, {sample_rate} Hz to {new_rate} Hz",
+                match cic.mode {
+                    cic::CicMode::Decimator => "decimator",
+                    cic::CicMode::Interpolator => "interpolator",
+                }
+            );
+            println!(
+                "Gain {}, bit growth {} bits, register width {width} bits for {bits}-bit input",
+                cic.gain(),
+                cic.bit_growth()
+            );
+            if let Some(taps) = &taps {
+                println!("Droop compensation with {} taps", taps.len());
+                if let Some(taps_out) = taps_out
+                    && let Err(e) = taps::save_taps(taps, &taps_out)
+                {
+                    println!("Fail to save taps to {taps_out}: {e}");
+                }
+            }
+            let _ = signal::save_wave_with_rate(&result, &out_signal, new_rate);
+        }
+        Commands::Envelope {
+            signal,
+            method,
+            fir_length,
+            out_signal,
+        } => {
+            let Some((analytic, sample_rate)) = read_analytic(&signal, &method, fir_length) else {
+                return;
+            };
+            let result = hilbert::envelope(&analytic);
+            let lo = result.iter().cloned().fold(f64::INFINITY, f64::min);
+            let hi = result.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            println!("Envelope from {lo:.6} to {hi:.6}");
+            let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+        }
+        Commands::InstPhase {
+            signal,
+            method,
+            fir_length,
+            out_signal,
+        } => {
+            let Some((analytic, sample_rate)) = read_analytic(&signal, &method, fir_length) else {
+                return;
+            };
+            let result = hilbert::inst_phase(&analytic);
+            if let (Some(first), Some(last)) = (result.first(), result.last()) {
+                println!("Phase from {first:.3} rad to {last:.3} rad");
+            }
+            let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+        }
+        Commands::InstFreq {
+            signal,
+            method,
+            fir_length,
+            out_signal,
+        } => {
+            let Some((analytic, sample_rate)) = read_analytic(&signal, &method, fir_length) else {
+                return;
+            };
+            let result = hilbert::inst_freq(&analytic, sample_rate as f64);
+            let lo = result.iter().cloned().fold(f64::INFINITY, f64::min);
+            let hi = result.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            println!("Instantaneous frequency from {lo:.1} Hz to {hi:.1} Hz");
+            let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+        }
+        Commands::Xcorr {
+            signal1,
+            signal2,
+            scale,
+            max_lag,
+            out_csv,
+        } => {
+            let scale = match xcorr::XcorrScale::parse(&scale) {
+                Ok(scale) => scale,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let mut sig1: Vec<f64> = Vec::new();
+            let rate1 = match signal::read_wave_with_rate(&mut sig1, &signal1) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal1}: {e}");
+                    return;
+                }
+            };
+            let sig2 = match signal2 {
+                Some(signal2) => {
+                    let mut sig2: Vec<f64> = Vec::new();
+                    match signal::read_wave_with_rate(&mut sig2, &signal2) {
+                        Ok(rate) if rate != rate1 => {
+                            println!(
+                                "Sample rate of {signal2} {rate} Hz differs from {signal1} {rate1} Hz"
+                            );
+                            return;
+                        }
+                        Ok(_) => sig2,
+                        Err(e) => {
+                            println!("Fail to read {signal2}: {e}");
+                            return;
+                        }
+                    }
+                }
+                None => sig1.clone(),
+            };
+
+            let (lags, values) = xcorr::xcorr(&sig1, &sig2, scale, max_lag);
+            if let Some((index, peak)) = values.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))
+            {
+                println!("Peak at lag {} samples: {peak:.6}", lags[index]);
+            }
+            match xcorr::save_xcorr(&lags, &values, &out_csv) {
+                Ok(()) => println!("Save correlation to {out_csv}"),
+                Err(e) => println!("Fail to save correlation to {out_csv}: {e}"),
+            }
+        }
+        Commands::Delay {
+            signal1,
+            signal2,
+            weighting,
+            max_lag,
+        } => {
+            let weighting = match xcorr::GccWeighting::parse(&weighting) {
+                Ok(weighting) => weighting,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let mut sig1: Vec<f64> = Vec::new();
+            let mut sig2: Vec<f64> = Vec::new();
+            let rate1 = match signal::read_wave_with_rate(&mut sig1, &signal1) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal1}: {e}");
+                    return;
+                }
+            };
+            let rate2 = match signal::read_wave_with_rate(&mut sig2, &signal2) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal2}: {e}");
+                    return;
+                }
+            };
+            if rate1 != rate2 {
+                println!("Sample rate of {signal2} {rate2} Hz differs from {signal1} {rate1} Hz");
+                return;
+            }
+
+            match xcorr::estimate_delay(&sig1, &sig2, weighting, max_lag) {
+                Some(estimate) => println!(
+                    "Delay of {signal2} relative to {signal1}: {:.3} samples, {:.3} ms",
+                    estimate.lag,
+                    1000.0 * estimate.lag / rate1 as f64
+                ),
+                None => println!("Signals are empty"),
+            }
+        }
+        Commands::Shift {
+            signal,
+            delay,
+            time,
+            method,
+            fill,
+            out_signal,
+        } => {
+            let params = dsp::ShiftMethod::parse(&method)
+                .and_then(|method| Ok((method, dsp::Fill::parse(&fill)?)));
+            let (method, fill) = match params {
+                Ok(params) => params,
+                Err(e) => {
+                    println!("{e}");
+                    return;
+                }
+            };
+            let mut sig: Vec<f64> = Vec::new();
+            let sample_rate = match signal::read_wave_with_rate(&mut sig, &signal) {
+                Ok(rate) => rate,
+                Err(e) => {
+                    println!("Fail to read {signal}: {e}");
+                    return;
+                }
+            };
+            // Задержка задана либо в отсчётах, либо в миллисекундах
+            let delay = delay.unwrap_or_else(|| time.unwrap_or(0.0) * sample_rate as f64 / 1000.0);
+            if !delay.is_finite() {
+                println!("Delay must be finite, got {delay}");
+                return;
+            }
+            let result = dsp::shift(&sig, delay, method, fill);
+            println!(
+                "Shift by {delay:.3} samples ({:.3} ms)",
+                1000.0 * delay / sample_rate as f64
+            );
+            let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+            println!("Save {} samples to {out_signal}", result.len());
+        }
+        Commands::Effect(effect) => {
+            let (result, out_signal, sample_rate) = match effect.command {
+                Some(EffectCommands::Comb {
+                    signal,
+                    delay,
+                    gain,
+                    comb_type,
+                    out_signal,
+                }) => {
+                    let comb_type = match effects::CombType::parse(&comb_type) {
+                        Ok(comb_type) => comb_type,
+                        Err(e) => {
+                            println!("{e}");
+                            return;
+                        }
+                    };
+                    let Some((sig, sample_rate)) = read_effect(&signal) else {
+                        return;
+                    };
+                    let delay = (delay / 1000.0 * sample_rate as f64).round() as usize;
+                    (
+                        effects::comb(&sig, delay, gain, comb_type),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(EffectCommands::Echo {
+                    signal,
+                    delay,
+                    feedback,
+                    mix,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate)) = read_effect(&signal) else {
+                        return;
+                    };
+                    let delay = (delay / 1000.0 * sample_rate as f64).round() as usize;
+                    (
+                        effects::echo(&sig, delay, feedback, mix),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(EffectCommands::Chorus {
+                    signal,
+                    delay,
+                    depth,
+                    rate,
+                    voices,
+                    mix,
+                    interpolation,
+                    waveform,
+                    out_signal,
+                }) => {
+                    let interpolation = match delay::Interpolation::parse(&interpolation) {
+                        Ok(interpolation) => interpolation,
+                        Err(e) => {
+                            println!("{e}");
+                            return;
+                        }
+                    };
+                    let Some((sig, sample_rate, lfo)) = read_modulated(&signal, &waveform, rate)
+                    else {
+                        return;
+                    };
+                    let chorus = effects::Chorus {
+                        delay: delay / 1000.0,
+                        depth: depth / 1000.0,
+                        lfo,
+                        voices,
+                        mix,
+                    };
+                    (
+                        chorus.process(&sig, sample_rate as f64, interpolation),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(EffectCommands::Flanger {
+                    signal,
+                    delay,
+                    depth,
+                    rate,
+                    feedback,
+                    mix,
+                    interpolation,
+                    waveform,
+                    out_signal,
+                }) => {
+                    let interpolation = match delay::Interpolation::parse(&interpolation) {
+                        Ok(interpolation) => interpolation,
+                        Err(e) => {
+                            println!("{e}");
+                            return;
+                        }
+                    };
+                    let Some((sig, sample_rate, lfo)) = read_modulated(&signal, &waveform, rate)
+                    else {
+                        return;
+                    };
+                    let flanger = effects::Flanger {
+                        delay: delay / 1000.0,
+                        depth: depth / 1000.0,
+                        lfo,
+                        feedback,
+                        mix,
+                    };
+                    (
+                        flanger.process(&sig, sample_rate as f64, interpolation),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(EffectCommands::Phaser {
+                    signal,
+                    stages,
+                    min_freq,
+                    max_freq,
+                    rate,
+                    feedback,
+                    mix,
+                    waveform,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate, lfo)) = read_modulated(&signal, &waveform, rate)
+                    else {
+                        return;
+                    };
+                    let phaser = effects::Phaser {
+                        stages,
+                        min_freq,
+                        max_freq,
+                        lfo,
+                        feedback,
+                        mix,
+                    };
+                    (
+                        phaser.process(&sig, sample_rate as f64),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                None => {
+                    println!("Do nothing");
+                    return;
+                }
+            };
+            match result {
+                Ok(result) => {
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                    println!("Save {} samples to {out_signal}", result.len());
+                }
+                Err(e) => println!("Error in effect: {e}"),
+            }
+        }
+        Commands::Reverb(reverb) => {
+            let (result, out_signal, sample_rate) = match reverb.command {
+                Some(ReverbCommands::Freeverb {
+                    signal,
+                    rt60,
+                    damping,
+                    pre_delay,
+                    mix,
+                    tail,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate)) = read_reverb(&signal, tail) else {
+                        return;
+                    };
+                    let params = reverb::Reverb {
+                        rt60,
+                        pre_delay: pre_delay / 1000.0,
+                        damping,
+                        mix,
+                    };
+                    (
+                        params.freeverb(&sig, sample_rate as f64),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(ReverbCommands::Fdn {
+                    signal,
+                    rt60,
+                    damping,
+                    lines,
+                    pre_delay,
+                    mix,
+                    tail,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate)) = read_reverb(&signal, tail) else {
+                        return;
+                    };
+                    let params = reverb::Reverb {
+                        rt60,
+                        pre_delay: pre_delay / 1000.0,
+                        damping,
+                        mix,
+                    };
+                    (
+                        params.fdn(&sig, sample_rate as f64, lines),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                Some(ReverbCommands::Ir {
+                    signal,
+                    ir,
+                    pre_delay,
+                    mix,
+                    tail,
+                    out_signal,
+                }) => {
+                    let Some((sig, sample_rate)) = read_reverb(&signal, tail) else {
+                        return;
+                    };
+                    let mut response: Vec<f64> = Vec::new();
+                    match signal::read_wave_with_rate(&mut response, &ir) {
+                        Ok(rate) if rate != sample_rate => {
+                            println!(
+                                "Sample rate of {ir} {rate} Hz differs from {signal} {sample_rate} Hz"
+                            );
+                            return;
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            println!("Fail to read {ir}: {e}");
+                            return;
+                        }
+                    }
+                    // RT60 и демпфирование задаются самой импульсной характеристикой
+                    let params = reverb::Reverb {
+                        rt60: 1.0,
+                        pre_delay: pre_delay / 1000.0,
+                        damping: 0.0,
+                        mix,
+                    };
+                    (
+                        params.convolve_ir(&sig, &response, sample_rate as f64),
+                        out_signal,
+                        sample_rate,
+                    )
+                }
+                None => {
+                    println!("Do nothing");
+                    return;
+                }
+            };
+            match result {
+                Ok(result) => {
+                    let _ = signal::save_wave_with_rate(&result, &out_signal, sample_rate);
+                    println!("Save {} samples to {out_signal}", result.len());
+                }
+                Err(e) => println!("Error in reverb: {e}"),
+            }
+        }
+    }
+}
+
+// Чтение двух сигналов с одной частотой дискретизации, выравнивание второго
+// по первому и приведение к общей длине
+fn read_pair(
+    signal1: &str,
+    signal2: &str,
+    align: &str,
+    length: &str,
+) -> Option<(Vec<f64>, Vec<f64>, u32)> {
+    let params = xcorr::AlignMode::parse(align)
+        .and_then(|align| Ok((align, dsp::LengthPolicy::parse(length)?)));
+    let (align, length) = match params {
+        Ok(params) => params,
+        Err(e) => {
+            println!("{e}");
+            return None;
+        }
+    };
+
+    let mut sig1: Vec<f64> = Vec::new();
+    let mut sig2: Vec<f64> = Vec::new();
+    let rate1 = match signal::read_wave_with_rate(&mut sig1, signal1) {
+        Ok(rate) => rate,
+        Err(e) => {
+            println!("Fail to read {signal1}: {e}");
+            return None;
+        }
+    };
+    let rate2 = match signal::read_wave_with_rate(&mut sig2, signal2) {
+        Ok(rate) => rate,
+        Err(e) => {
+            println!("Fail to read {signal2}: {e}");
+            return None;
+        }
+    };
+    if rate1 != rate2 {
+        println!("Sample rate of {signal2} {rate2} Hz differs from {signal1} {rate1} Hz");
+        return None;
+    }
+
+    if align != xcorr::AlignMode::None {
+        let (aligned, lag) = xcorr::align(&sig1, &sig2, align);
+        println!("Align {signal2} by {lag:.3} samples");
+        sig2 = aligned;
     }
+
+    let (sig1, sig2) = dsp::match_length(&sig1, &sig2, length);
+    Some((sig1, sig2, rate1))
+}
+
+// Чтение сигнала и расчёт аналитического сигнала выбранным методом
+fn read_analytic(
+    signal: &str,
+    method: &str,
+    fir_length: usize,
+) -> Option<(Vec<rustfft::num_complex::Complex<f64>>, u32)> {
+    let method = match hilbert::HilbertMethod::parse(method) {
+        Ok(method) => method,
+        Err(e) => {
+            println!("{e}");
+            return None;
+        }
+    };
+    let mut sig: Vec<f64> = Vec::new();
+    let sample_rate = match signal::read_wave_with_rate(&mut sig, signal) {
+        Ok(rate) => rate,
+        Err(e) => {
+            println!("Fail to read {signal}: {e}");
+            return None;
+        }
+    };
+    match hilbert::analytic(&sig, method, fir_length) {
+        Ok(analytic) => Some((analytic, sample_rate)),
+        Err(e) => {
+            println!("Error in filter design: {e}");
+            None
+        }
+    }
+}
+
+// Чтение сигнала и режима краёв для сглаживания
+fn read_smoothing(signal: &str, edge: &str) -> Option<(Vec<f64>, u32, smooth::EdgeMode)> {
+    let edge = match smooth::EdgeMode::parse(edge) {
+        Ok(edge) => edge,
+        Err(e) => {
+            println!("{e}");
+            return None;
+        }
+    };
+    let mut sig: Vec<f64> = Vec::new();
+    match signal::read_wave_with_rate(&mut sig, signal) {
+        Ok(rate) => Some((sig, rate, edge)),
+        Err(e) => {
+            println!("Fail to read {signal}: {e}");
+            None
+        }
+    }
+}
+
+// Чтение сигнала и управляющего сигнала с той же частотой дискретизации
+fn read_dynamics(
+    signal: &str,
+    sidechain: Option<String>,
+) -> Option<(Vec<f64>, Option<Vec<f64>>, u32)> {
+    let mut sig: Vec<f64> = Vec::new();
+    let sample_rate = match signal::read_wave_with_rate(&mut sig, signal) {
+        Ok(rate) => rate,
+        Err(e) => {
+            println!("Fail to read {signal}: {e}");
+            return None;
+        }
+    };
+    let Some(sidechain) = sidechain else {
+        return Some((sig, None, sample_rate));
+    };
+    let mut sc: Vec<f64> = Vec::new();
+    match signal::read_wave_with_rate(&mut sc, &sidechain) {
+        Ok(rate) if rate == sample_rate => Some((sig, Some(sc), sample_rate)),
+        Ok(rate) => {
+            println!("Sample rate of {sidechain} {rate} Hz differs from {signal} {sample_rate} Hz");
+            None
+        }
+        Err(e) => {
+            println!("Fail to read {sidechain}: {e}");
+            None
+        }
+    }
+}
+
+fn read_effect(signal: &str) -> Option<(Vec<f64>, u32)> {
+    let mut sig: Vec<f64> = Vec::new();
+    match signal::read_wave_with_rate(&mut sig, signal) {
+        Ok(rate) => Some((sig, rate)),
+        Err(e) => {
+            println!("Fail to read {signal}: {e}");
+            None
+        }
+    }
+}
+
+// Чтение сигнала и LFO для эффектов с модуляцией задержки
+fn read_modulated(
+    signal: &str,
+    waveform: &str,
+    rate: f64,
+) -> Option<(Vec<f64>, u32, effects::Lfo)> {
+    let waveform = match effects::Waveform::parse(waveform) {
+        Ok(waveform) => waveform,
+        Err(e) => {
+            println!("{e}");
+            return None;
+        }
+    };
+    let (sig, sample_rate) = read_effect(signal)?;
+    let lfo = effects::Lfo {
+        rate,
+        waveform,
+        phase: 0.0,
+    };
+    Some((sig, sample_rate, lfo))
+}
+
+// Чтение сигнала с добавлением тишины под хвост реверберации
+fn read_reverb(signal: &str, tail: f64) -> Option<(Vec<f64>, u32)> {
+    let (mut sig, sample_rate) = read_effect(signal)?;
+    if tail < 0.0 {
+        println!("Tail must not be negative, got {tail}");
+        return None;
+    }
+    sig.resize(
+        sig.len() + (tail * sample_rate as f64).round() as usize,
+        0.0,
+    );
+    Some((sig, sample_rate))
+}
+
+fn read_stretch(signal: &str, method: &str) -> Option<(Vec<f64>, u32, stretch::StretchMethod)> {
+    let method = match stretch::StretchMethod::parse(method) {
+        Ok(method) => method,
+        Err(e) => {
+            println!("{e}");
+            return None;
+        }
+    };
+    let (sig, sample_rate) = read_effect(signal)?;
+    Some((sig, sample_rate, method))
 }
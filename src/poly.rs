@@ -0,0 +1,138 @@
+use rustfft::num_complex::Complex;
+
+const MAX_ITERATIONS: usize = 500;
+
+/// Roots of the polynomial with real coefficients given from the highest
+/// power down (as in numpy.roots), found by Aberth-Ehrlich iterations.
+pub fn roots(coeffs: &[f64]) -> Vec<Complex<f64>> {
+    // Отбрасываем нулевые старшие коэффициенты
+    let first = coeffs
+        .iter()
+        .position(|&c| c != 0.0)
+        .unwrap_or(coeffs.len());
+    let coeffs = &coeffs[first..];
+    if coeffs.len() < 2 {
+        return Vec::new();
+    }
+    // Нулевые младшие коэффициенты дают корни в нуле
+    let last = coeffs.iter().rposition(|&c| c != 0.0).unwrap_or(0);
+    let zero_roots = coeffs.len() - 1 - last;
+    let monic: Vec<f64> = coeffs[..=last].iter().map(|c| c / coeffs[0]).collect();
+    let degree = monic.len() - 1;
+
+    let mut result = vec![Complex::new(0.0, 0.0); zero_roots];
+    if degree == 0 {
+        return result;
+    }
+
+    // Начальные приближения на окружности радиуса оценки корней
+    let radius = monic[1..]
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c.abs().powf(1.0 / (i + 1) as f64))
+        .fold(0.0, f64::max)
+        .max(1e-3);
+    let mut z: Vec<Complex<f64>> = (0..degree)
+        .map(|k| {
+            Complex::from_polar(
+                radius,
+                2.0 * std::f64::consts::PI * k as f64 / degree as f64 + 0.4,
+            )
+        })
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut max_step: f64 = 0.0;
+        for i in 0..degree {
+            let (value, derivative) = eval_with_derivative(&monic, z[i]);
+            if value.norm() == 0.0 {
+                continue;
+            }
+            let ratio = value / derivative;
+            let repulsion: Complex<f64> = (0..degree)
+                .filter(|&j| j != i)
+                .map(|j| (z[i] - z[j]).inv())
+                .sum();
+            let step = ratio / (Complex::new(1.0, 0.0) - ratio * repulsion);
+            z[i] -= step;
+            max_step = max_step.max(step.norm() / z[i].norm().max(1.0));
+        }
+        if max_step < 1e-15 {
+            break;
+        }
+    }
+
+    // Корни вещественного полинома: почти вещественные делаем вещественными
+    for root in z.iter_mut() {
+        if root.im.abs() < 1e-12 * root.norm().max(1.0) {
+            root.im = 0.0;
+        }
+    }
+
+    result.extend(z);
+    result
+}
+
+fn eval_with_derivative(coeffs: &[f64], x: Complex<f64>) -> (Complex<f64>, Complex<f64>) {
+    let mut value = Complex::new(0.0, 0.0);
+    let mut derivative = Complex::new(0.0, 0.0);
+    for &c in coeffs {
+        derivative = derivative * x + value;
+        value = value * x + c;
+    }
+    (value, derivative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut r: Vec<Complex<f64>>) -> Vec<Complex<f64>> {
+        r.sort_by(|a, b| (a.re, a.im).partial_cmp(&(b.re, b.im)).unwrap());
+        r
+    }
+
+    #[test]
+    fn test_roots_real() {
+        // (x - 1)(x - 2)(x + 3) = x^3 - 7x + 6
+        let r = sorted(roots(&[1.0, 0.0, -7.0, 6.0]));
+        let expected = [-3.0, 1.0, 2.0];
+        for (root, e) in r.iter().zip(expected.iter()) {
+            assert!((root - Complex::new(*e, 0.0)).norm() < 1e-10, "{root}");
+        }
+    }
+
+    #[test]
+    fn test_roots_complex() {
+        // x^2 + 2x + 5 -> -1 ± 2j
+        let r = sorted(roots(&[2.0, 4.0, 10.0]));
+        assert!((r[0] - Complex::new(-1.0, -2.0)).norm() < 1e-10);
+        assert!((r[1] - Complex::new(-1.0, 2.0)).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_roots_zero_and_leading_zeros() {
+        // 0 x^3 + x^2 - x = x (x - 1)
+        let r = sorted(roots(&[0.0, 1.0, -1.0, 0.0]));
+        assert_eq!(r.len(), 2);
+        assert!(r[0].norm() < 1e-12);
+        assert!((r[1] - Complex::new(1.0, 0.0)).norm() < 1e-12);
+
+        assert!(roots(&[3.0]).is_empty());
+        assert!(roots(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_roots_unit_circle() {
+        // x^8 - 1
+        let mut coeffs = vec![0.0; 9];
+        coeffs[0] = 1.0;
+        coeffs[8] = -1.0;
+        let r = roots(&coeffs);
+        assert_eq!(r.len(), 8);
+        for root in r {
+            assert!((root.norm() - 1.0).abs() < 1e-10);
+            assert!((root.powu(8) - Complex::new(1.0, 0.0)).norm() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,540 @@
+use std::f64::consts::PI;
+
+const GRID_DENSITY: usize = 16;
+const MAX_ITERATIONS: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemezType {
+    Bandpass,
+    Differentiator,
+    Hilbert,
+}
+
+impl RemezType {
+    pub fn parse(name: &str) -> Result<RemezType, String> {
+        match name {
+            "bandpass" => Ok(RemezType::Bandpass),
+            "differentiator" => Ok(RemezType::Differentiator),
+            "hilbert" => Ok(RemezType::Hilbert),
+            _ => Err(format!(
+                "Unknown filter type {name}, expected bandpass, differentiator or hilbert"
+            )),
+        }
+    }
+
+    // Дифференциатор и преобразователь Гильберта антисимметричны
+    fn is_symmetric(&self) -> bool {
+        matches!(self, RemezType::Bandpass)
+    }
+}
+
+// Равномерная сетка частот (в долях fs) с желаемой характеристикой и весами
+struct DenseGrid {
+    grid: Vec<f64>,
+    desired: Vec<f64>,
+    weight: Vec<f64>,
+}
+
+fn create_dense_grid(
+    r: usize,
+    numtaps: usize,
+    bands: &[f64],
+    desired: &[f64],
+    weight: &[f64],
+    symmetric: bool,
+) -> DenseGrid {
+    let delf = 0.5 / (GRID_DENSITY * r) as f64;
+    let mut dense = DenseGrid {
+        grid: Vec::new(),
+        desired: Vec::new(),
+        weight: Vec::new(),
+    };
+    let per_edge = desired.len() == bands.len();
+
+    for band in 0..bands.len() / 2 {
+        let (edge_lo, edge_hi) = (bands[2 * band], bands[2 * band + 1]);
+        let mut lowf = edge_lo;
+        // У антисимметричных фильтров нуль на нулевой частоте
+        if !symmetric && band == 0 && lowf < delf {
+            lowf = delf;
+        }
+        let k = (((edge_hi - lowf) / delf + 0.5) as usize).max(1);
+        let (d0, d1) = if per_edge {
+            (desired[2 * band], desired[2 * band + 1])
+        } else {
+            (desired[band], desired[band])
+        };
+
+        for i in 0..k {
+            let f = if i == k - 1 {
+                edge_hi
+            } else {
+                lowf + i as f64 * delf
+            };
+            // Желаемая характеристика линейна по частоте между краями полосы
+            let slope = if edge_hi > edge_lo {
+                (f - edge_lo) / (edge_hi - edge_lo)
+            } else {
+                0.0
+            };
+            dense.desired.push(d0 + slope * (d1 - d0));
+            dense.weight.push(weight[band]);
+            dense.grid.push(f);
+        }
+    }
+
+    // У антисимметричного фильтра нечётной длины нуль и на fs/2
+    if let Some(last) = dense.grid.last_mut()
+        && !symmetric
+        && numtaps % 2 == 1
+        && *last > 0.5 - delf
+    {
+        *last = 0.5 - delf;
+    }
+
+    dense
+}
+
+// Барицентрическая форма интерполяции Лагранжа (Оппенгейм и Шафер, 7.131-7.133)
+struct Interpolation {
+    x: Vec<f64>,
+    y: Vec<f64>,
+    ad: Vec<f64>,
+}
+
+fn calc_parms(ext: &[usize], dense: &DenseGrid) -> Interpolation {
+    let r = ext.len() - 1;
+    let x: Vec<f64> = ext
+        .iter()
+        .map(|&e| (2.0 * PI * dense.grid[e]).cos())
+        .collect();
+
+    // Перемножаем с шагом, чтобы избежать переполнения и потери точности
+    let ld = (r - 1) / 15 + 1;
+    let ad: Vec<f64> = (0..=r)
+        .map(|i| {
+            let mut denom = 1.0;
+            for j in 0..ld {
+                for k in (j..=r).step_by(ld) {
+                    if k != i {
+                        denom *= 2.0 * (x[i] - x[k]);
+                    }
+                }
+            }
+            if denom.abs() < 1e-5 {
+                denom = 1e-5;
+            }
+            1.0 / denom
+        })
+        .collect();
+
+    let mut numer = 0.0;
+    let mut denom = 0.0;
+    let mut sign = 1.0;
+    for i in 0..=r {
+        numer += ad[i] * dense.desired[ext[i]];
+        denom += sign * ad[i] / dense.weight[ext[i]];
+        sign = -sign;
+    }
+    let delta = numer / denom;
+
+    let mut sign = 1.0;
+    let y: Vec<f64> = (0..=r)
+        .map(|i| {
+            let value = dense.desired[ext[i]] - sign * delta / dense.weight[ext[i]];
+            sign = -sign;
+            value
+        })
+        .collect();
+
+    Interpolation { x, y, ad }
+}
+
+fn compute_a(freq: f64, interp: &Interpolation) -> f64 {
+    let xc = (2.0 * PI * freq).cos();
+    let mut numer = 0.0;
+    let mut denom = 0.0;
+
+    for i in 0..interp.x.len() {
+        let c = xc - interp.x[i];
+        if c.abs() < 1e-7 {
+            return interp.y[i];
+        }
+        let c = interp.ad[i] / c;
+        denom += c;
+        numer += c * interp.y[i];
+    }
+
+    numer / denom
+}
+
+// Поиск экстремумов ошибки, лишние удаляются с сохранением чередования
+fn search(r: usize, error: &[f64]) -> Result<Vec<usize>, String> {
+    let n = error.len();
+    let mut found: Vec<usize> = Vec::new();
+
+    if (error[0] > 0.0 && error[0] > error[1]) || (error[0] < 0.0 && error[0] < error[1]) {
+        found.push(0);
+    }
+    for i in 1..n - 1 {
+        if (error[i] >= error[i - 1] && error[i] > error[i + 1] && error[i] > 0.0)
+            || (error[i] <= error[i - 1] && error[i] < error[i + 1] && error[i] < 0.0)
+        {
+            found.push(i);
+        }
+    }
+    let j = n - 1;
+    if (error[j] > 0.0 && error[j] > error[j - 1]) || (error[j] < 0.0 && error[j] < error[j - 1]) {
+        found.push(j);
+    }
+
+    if found.len() < r + 1 {
+        return Err(format!(
+            "Remez exchange found only {} extrema, {} needed",
+            found.len(),
+            r + 1
+        ));
+    }
+
+    while found.len() > r + 1 {
+        let extra = found.len() - (r + 1);
+        let mut up = error[found[0]] > 0.0;
+        let mut smallest = 0;
+        let mut alternating = true;
+
+        for j in 1..found.len() {
+            if error[found[j]].abs() < error[found[smallest]].abs() {
+                smallest = j;
+            }
+            if up && error[found[j]] < 0.0 {
+                up = false;
+            } else if !up && error[found[j]] > 0.0 {
+                up = true;
+            } else {
+                alternating = false;
+                break;
+            }
+        }
+
+        if alternating && extra == 1 {
+            let last = found.len() - 1;
+            smallest = if error[found[last]].abs() < error[found[0]].abs() {
+                last
+            } else {
+                0
+            };
+        }
+
+        found.remove(smallest);
+    }
+
+    Ok(found)
+}
+
+fn is_done(ext: &[usize], error: &[f64]) -> bool {
+    let (min, max) = ext.iter().fold((f64::INFINITY, 0.0_f64), |(min, max), &e| {
+        (min.min(error[e].abs()), max.max(error[e].abs()))
+    });
+    (max - min) / max < 1e-4
+}
+
+// Частотная выборка: восстановление импульсной характеристики по A(f)
+fn freq_sample(amplitudes: &[f64], numtaps: usize, symmetric: bool) -> Vec<f64> {
+    let n = numtaps as f64;
+    let m = (n - 1.0) / 2.0;
+    let last = if numtaps % 2 == 1 {
+        (numtaps - 1) / 2
+    } else {
+        numtaps / 2 - 1
+    };
+
+    (0..numtaps)
+        .map(|i| {
+            let x = 2.0 * PI * (i as f64 - m) / n;
+            let mut val = if symmetric {
+                amplitudes[0]
+            } else if numtaps.is_multiple_of(2) {
+                amplitudes[numtaps / 2] * (PI * (i as f64 - m)).sin()
+            } else {
+                0.0
+            };
+            for (k, a) in amplitudes.iter().enumerate().take(last + 1).skip(1) {
+                val += if symmetric {
+                    2.0 * a * (x * k as f64).cos()
+                } else {
+                    2.0 * a * (x * k as f64).sin()
+                };
+            }
+            val / n
+        })
+        .collect()
+}
+
+/// Parks-McClellan equiripple FIR design.
+///
+/// `bands` are band edges in Hz (two per band), `desired` is the gain in each
+/// band (one per band) or at each band edge (linear in between), `weight` is
+/// the relative weight of each band. For a differentiator the desired gain is
+/// usually given per edge so that it grows with frequency.
+pub fn remez(
+    numtaps: usize,
+    bands: &[f64],
+    desired: &[f64],
+    weight: &[f64],
+    filter_type: RemezType,
+    fs: f64,
+) -> Result<Vec<f64>, String> {
+    if numtaps < 3 {
+        return Err("Filter length must be at least 3".to_string());
+    }
+    if bands.is_empty() || !bands.len().is_multiple_of(2) {
+        return Err("Band edges must be given in pairs".to_string());
+    }
+    let band_count = bands.len() / 2;
+    if desired.len() != band_count && desired.len() != bands.len() {
+        return Err(format!(
+            "Desired gain must be given for each band ({band_count}) or band edge ({})",
+            bands.len()
+        ));
+    }
+    if weight.len() != band_count {
+        return Err(format!("Weight must be given for each band ({band_count})"));
+    }
+    if weight.iter().any(|&w| w <= 0.0) {
+        return Err("Weights must be positive".to_string());
+    }
+    if bands.windows(2).any(|pair| pair[0] > pair[1])
+        || bands[0] < 0.0
+        || bands[bands.len() - 1] > fs / 2.0
+    {
+        return Err(format!(
+            "Band edges must be increasing and lie in [0, {}] Hz",
+            fs / 2.0
+        ));
+    }
+
+    let symmetric = filter_type.is_symmetric();
+    let norm_bands: Vec<f64> = bands.iter().map(|b| b / fs).collect();
+
+    let mut r = numtaps / 2;
+    if numtaps % 2 == 1 && symmetric {
+        r += 1;
+    }
+
+    let mut dense = create_dense_grid(r, numtaps, &norm_bands, desired, weight, symmetric);
+    let gridsize = dense.grid.len();
+    if gridsize <= r + 1 {
+        return Err("Bands are too narrow for the filter length".to_string());
+    }
+
+    if filter_type == RemezType::Differentiator {
+        for i in 0..gridsize {
+            if dense.desired[i] > 1e-4 {
+                dense.weight[i] /= dense.grid[i];
+            }
+        }
+    }
+
+    // Приводим задачу к аппроксимации косинусным полиномом
+    for i in 0..gridsize {
+        let f = dense.grid[i];
+        let c = match (symmetric, numtaps % 2 == 1) {
+            (true, true) => 1.0,
+            (true, false) => (PI * f).cos(),
+            (false, true) => (2.0 * PI * f).sin(),
+            (false, false) => (PI * f).sin(),
+        };
+        dense.desired[i] /= c;
+        dense.weight[i] *= c;
+    }
+
+    let mut ext: Vec<usize> = (0..=r).map(|i| i * (gridsize - 1) / r).collect();
+    let mut converged = false;
+
+    for _ in 0..MAX_ITERATIONS {
+        let interp = calc_parms(&ext, &dense);
+        let error: Vec<f64> = (0..gridsize)
+            .map(|i| dense.weight[i] * (dense.desired[i] - compute_a(dense.grid[i], &interp)))
+            .collect();
+        ext = search(r, &error)?;
+        if is_done(&ext, &error) {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(format!(
+            "Remez exchange did not converge in {MAX_ITERATIONS} iterations"
+        ));
+    }
+
+    let interp = calc_parms(&ext, &dense);
+    let amplitudes: Vec<f64> = (0..=numtaps / 2)
+        .map(|i| {
+            let f = i as f64 / numtaps as f64;
+            let c = match (symmetric, numtaps % 2 == 1) {
+                (true, true) => 1.0,
+                (true, false) => (PI * f).cos(),
+                (false, true) => (2.0 * PI * f).sin(),
+                (false, false) => (PI * f).sin(),
+            };
+            compute_a(f, &interp) * c
+        })
+        .collect();
+
+    Ok(freq_sample(&amplitudes, numtaps, symmetric))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FS: f64 = 10000.0;
+
+    // Комплексная частотная характеристика КИХ-фильтра: (|H|, мнимая часть после
+    // компенсации линейной фазы)
+    fn response(taps: &[f64], freq: f64) -> (f64, f64) {
+        let w = 2.0 * PI * freq / FS;
+        let m = (taps.len() - 1) as f64 / 2.0;
+        let (re, im) = taps
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(re, im), (n, h)| {
+                let phase = w * (n as f64 - m);
+                (re + h * phase.cos(), im - h * phase.sin())
+            });
+        ((re * re + im * im).sqrt(), im)
+    }
+
+    #[test]
+    fn test_remez_type_parse() {
+        assert_eq!(RemezType::parse("bandpass"), Ok(RemezType::Bandpass));
+        assert_eq!(RemezType::parse("hilbert"), Ok(RemezType::Hilbert));
+        assert!(RemezType::parse("lowpass").is_err());
+    }
+
+    #[test]
+    fn test_remez_lowpass_equiripple() {
+        let taps = remez(
+            51,
+            &[0.0, 1000.0, 1500.0, 5000.0],
+            &[1.0, 0.0],
+            &[1.0, 1.0],
+            RemezType::Bandpass,
+            FS,
+        )
+        .unwrap();
+
+        assert_eq!(taps.len(), 51);
+        for i in 0..51 {
+            assert!((taps[i] - taps[50 - i]).abs() < 1e-12);
+        }
+
+        let pass_ripple = (0..=100)
+            .map(|i| (response(&taps, 10.0 * i as f64).0 - 1.0).abs())
+            .fold(0.0, f64::max);
+        let stop_ripple = (0..=350)
+            .map(|i| response(&taps, 1500.0 + 10.0 * i as f64).0)
+            .fold(0.0, f64::max);
+
+        // При равных весах пульсации в полосах одинаковы
+        assert!(pass_ripple < 0.01, "passband ripple {pass_ripple}");
+        assert!(
+            (pass_ripple - stop_ripple).abs() < 0.1 * pass_ripple,
+            "ripples differ: {pass_ripple} vs {stop_ripple}"
+        );
+    }
+
+    #[test]
+    fn test_remez_weight_shifts_ripple() {
+        let taps = remez(
+            51,
+            &[0.0, 1000.0, 1500.0, 5000.0],
+            &[1.0, 0.0],
+            &[1.0, 10.0],
+            RemezType::Bandpass,
+            FS,
+        )
+        .unwrap();
+
+        let pass_ripple = (0..=100)
+            .map(|i| (response(&taps, 10.0 * i as f64).0 - 1.0).abs())
+            .fold(0.0, f64::max);
+        let stop_ripple = (0..=350)
+            .map(|i| response(&taps, 1500.0 + 10.0 * i as f64).0)
+            .fold(0.0, f64::max);
+
+        let ratio = pass_ripple / stop_ripple;
+        assert!((ratio - 10.0).abs() < 1.0, "ripple ratio {ratio}");
+    }
+
+    #[test]
+    fn test_remez_bandpass_even_length() {
+        let taps = remez(
+            40,
+            &[0.0, 800.0, 1200.0, 2500.0, 2900.0, 5000.0],
+            &[0.0, 1.0, 0.0],
+            &[1.0, 1.0, 1.0],
+            RemezType::Bandpass,
+            FS,
+        )
+        .unwrap();
+
+        assert_eq!(taps.len(), 40);
+        assert!((response(&taps, 1800.0).0 - 1.0).abs() < 0.05);
+        assert!(response(&taps, 300.0).0 < 0.05);
+        assert!(response(&taps, 4000.0).0 < 0.05);
+    }
+
+    #[test]
+    fn test_remez_hilbert() {
+        let taps = remez(31, &[500.0, 4500.0], &[1.0], &[1.0], RemezType::Hilbert, FS).unwrap();
+
+        for i in 0..31 {
+            assert!((taps[i] + taps[30 - i]).abs() < 1e-12, "not antisymmetric");
+        }
+        // Полоса симметрична относительно fs/4, поэтому отсчёты на чётном
+        // расстоянии от центра нулевые
+        for i in (1..31).step_by(2) {
+            assert!(taps[i].abs() < 1e-9, "tap {i} is {}", taps[i]);
+        }
+        for freq in [1000.0, 2500.0, 4000.0] {
+            assert!((response(&taps, freq).0 - 1.0).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_remez_differentiator() {
+        let edge = 2.0 * PI * 4000.0 / FS;
+        let taps = remez(
+            30,
+            &[0.0, 4000.0],
+            &[0.0, edge],
+            &[1.0],
+            RemezType::Differentiator,
+            FS,
+        )
+        .unwrap();
+
+        for freq in [500.0, 1500.0, 3000.0] {
+            let expected = 2.0 * PI * freq / FS;
+            let (gain, _) = response(&taps, freq);
+            assert!(
+                (gain - expected).abs() < 0.01 * expected,
+                "gain {gain} at {freq}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remez_invalid_arguments() {
+        let bp = RemezType::Bandpass;
+        assert!(remez(2, &[0.0, 1000.0], &[1.0], &[1.0], bp, FS).is_err());
+        assert!(remez(11, &[0.0, 1000.0, 2000.0], &[1.0], &[1.0], bp, FS).is_err());
+        assert!(remez(11, &[0.0, 1000.0], &[1.0, 0.0, 1.0], &[1.0], bp, FS).is_err());
+        assert!(remez(11, &[0.0, 1000.0], &[1.0], &[1.0, 2.0], bp, FS).is_err());
+        assert!(remez(11, &[0.0, 6000.0], &[1.0], &[1.0], bp, FS).is_err());
+        assert!(remez(11, &[1000.0, 0.0], &[1.0], &[1.0], bp, FS).is_err());
+        assert!(remez(11, &[0.0, 1000.0], &[1.0], &[0.0], bp, FS).is_err());
+    }
+}
@@ -0,0 +1,514 @@
+use crate::filtfilt;
+use crate::fir::{self, BandType, Window};
+use crate::iir::{self, IirFamily};
+
+// Наибольший коэффициент интерполяции для полифазного метода,
+// при больших рациональных множителях банк фильтров слишком велик
+const MAX_POLYPHASE_FACTOR: usize = 1024;
+// Число фаз в таблице ядра для произвольного коэффициента
+const TABLE_PHASES: usize = 256;
+// Наибольший множитель одной ступени при многоступенчатом разложении
+const MAX_STAGE_FACTOR: usize = 10;
+// Фильтр Чебышёва I для БИХ-варианта, как в scipy.signal.decimate
+const IIR_ORDER: usize = 8;
+const IIR_RIPPLE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quality {
+    Low,
+    Medium,
+    High,
+}
+
+impl Quality {
+    pub fn parse(name: &str) -> Result<Quality, String> {
+        match name {
+            "low" => Ok(Quality::Low),
+            "medium" => Ok(Quality::Medium),
+            "high" => Ok(Quality::High),
+            _ => Err(format!(
+                "Unknown quality {name}, expected low, medium or high"
+            )),
+        }
+    }
+
+    /// Stopband attenuation in dB and transition width as a fraction
+    /// of the lower Nyquist frequency
+    pub fn parameters(&self) -> (f64, f64) {
+        match self {
+            Quality::Low => (50.0, 0.2),
+            Quality::Medium => (80.0, 0.1),
+            Quality::High => (120.0, 0.05),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResampleMethod {
+    Auto,
+    Polyphase,
+    Arbitrary,
+}
+
+impl ResampleMethod {
+    pub fn parse(name: &str) -> Result<ResampleMethod, String> {
+        match name {
+            "auto" => Ok(ResampleMethod::Auto),
+            "polyphase" => Ok(ResampleMethod::Polyphase),
+            "arbitrary" => Ok(ResampleMethod::Arbitrary),
+            _ => Err(format!(
+                "Unknown resample method {name}, expected auto, polyphase or arbitrary"
+            )),
+        }
+    }
+
+    /// Polyphase filter is used while the reduced interpolation factor is small enough
+    pub fn resolve(self, from: u32, to: u32) -> ResampleMethod {
+        if self != ResampleMethod::Auto {
+            return self;
+        }
+        let (up, _) = rational_factors(from, to);
+        if up <= MAX_POLYPHASE_FACTOR {
+            ResampleMethod::Polyphase
+        } else {
+            ResampleMethod::Arbitrary
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Interpolation and decimation factors up/down = to/from in lowest terms
+pub fn rational_factors(from: u32, to: u32) -> (usize, usize) {
+    let divisor = gcd(from as usize, to as usize).max(1);
+    (to as usize / divisor, from as usize / divisor)
+}
+
+/// Kaiser-window anti-aliasing lowpass for the upsampled rate `fs`,
+/// the stopband starts at `nyquist`
+pub fn antialias_filter(nyquist: f64, fs: f64, quality: Quality) -> Result<Vec<f64>, String> {
+    let (attenuation, transition) = quality.parameters();
+    let width = transition * nyquist;
+    let (mut length, beta) = fir::kaiser_order(attenuation, width, fs)?;
+    if length.is_multiple_of(2) {
+        length += 1;
+    }
+    fir::firwin(
+        length,
+        &[nyquist - width / 2.0],
+        BandType::Lowpass,
+        Window::Kaiser(beta),
+        fs,
+    )
+}
+
+/// Rational resampling by `up / down` with a polyphase FIR filter,
+/// the filter delay is compensated and the duration is preserved
+pub fn resample_poly(
+    signal: &[f64],
+    up: usize,
+    down: usize,
+    quality: Quality,
+) -> Result<Vec<f64>, String> {
+    if up == 0 || down == 0 {
+        return Err("Resampling factors must be positive".to_string());
+    }
+    if up == down {
+        return Ok(signal.to_vec());
+    }
+    // Частоты в долях входной частоты дискретизации
+    let nyquist = 0.5 / up.max(down) as f64;
+    let taps = antialias_filter(nyquist * up as f64, up as f64, quality)?;
+    let delay = (taps.len() - 1) / 2;
+    let length = (signal.len() * up).div_ceil(down);
+
+    let mut sig_new: Vec<f64> = Vec::with_capacity(length);
+    for m in 0..length {
+        // Отсчёт m*down в сетке, повышенной в up раз, со сдвигом на задержку фильтра
+        let t = m * down + delay;
+        let (base, phase) = (t / up, t % up);
+        let mut acc = 0.0;
+        for (j, h) in taps.iter().skip(phase).step_by(up).enumerate() {
+            if j <= base && base - j < signal.len() {
+                acc += h * signal[base - j];
+            }
+        }
+        sig_new.push(acc * up as f64);
+    }
+
+    Ok(sig_new)
+}
+
+/// Resampling by an arbitrary ratio `to / from` with windowed-sinc
+/// interpolation from a finely tabulated kernel
+pub fn resample_arbitrary(
+    signal: &[f64],
+    ratio: f64,
+    quality: Quality,
+) -> Result<Vec<f64>, String> {
+    if ratio <= 0.0 || !ratio.is_finite() {
+        return Err(format!("Resampling ratio must be positive, got {ratio}"));
+    }
+    // Время в отсчётах входа, таблица ядра с шагом 1 / TABLE_PHASES
+    let phases = TABLE_PHASES as f64;
+    let nyquist = 0.5 * ratio.min(1.0);
+    let mut table = antialias_filter(nyquist, phases, quality)?;
+    table.iter_mut().for_each(|h| *h *= phases);
+    let center = (table.len() - 1) as f64 / 2.0;
+    let half = center / phases;
+
+    let kernel = |tau: f64| {
+        let pos = tau * phases + center;
+        let i = pos.floor();
+        if i < 0.0 || i as usize + 1 >= table.len() {
+            return 0.0;
+        }
+        let frac = pos - i;
+        let i = i as usize;
+        table[i] * (1.0 - frac) + table[i + 1] * frac
+    };
+
+    // Допуск на округление, чтобы 44100 * (47999 / 44100) дало 47999
+    let length = (signal.len() as f64 * ratio - 1e-9).ceil() as usize;
+    let last = signal.len() as isize - 1;
+    let mut sig_new: Vec<f64> = Vec::with_capacity(length);
+    for m in 0..length {
+        let t = m as f64 / ratio;
+        let first = ((t - half).ceil() as isize).max(0);
+        let end = ((t + half).floor() as isize).min(last);
+        let acc: f64 = (first..=end)
+            .map(|k| signal[k as usize] * kernel(k as f64 - t))
+            .sum();
+        sig_new.push(acc);
+    }
+
+    Ok(sig_new)
+}
+
+/// Converts the sample rate from `from` to `to` Hz, returns the signal
+/// and the method actually used
+pub fn resample(
+    signal: &[f64],
+    from: u32,
+    to: u32,
+    method: ResampleMethod,
+    quality: Quality,
+) -> Result<(Vec<f64>, ResampleMethod), String> {
+    if from == 0 || to == 0 {
+        return Err("Sample rates must be positive".to_string());
+    }
+    let method = method.resolve(from, to);
+    let sig_new = match method {
+        ResampleMethod::Arbitrary => resample_arbitrary(signal, to as f64 / from as f64, quality)?,
+        _ => {
+            let (up, down) = rational_factors(from, to);
+            resample_poly(signal, up, down, quality)?
+        }
+    };
+    Ok((sig_new, method))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateFilter {
+    Fir,
+    Iir,
+}
+
+impl RateFilter {
+    pub fn parse(name: &str) -> Result<RateFilter, String> {
+        match name {
+            "fir" => Ok(RateFilter::Fir),
+            "iir" => Ok(RateFilter::Iir),
+            _ => Err(format!("Unknown filter {name}, expected fir or iir")),
+        }
+    }
+}
+
+/// Splits a large factor into stages of at most `MAX_STAGE_FACTOR`
+/// (or a larger prime), the largest stage goes first
+pub fn stage_factors(factor: usize) -> Vec<usize> {
+    let mut primes: Vec<usize> = Vec::new();
+    let mut rest = factor;
+    let mut p = 2;
+    while p * p <= rest {
+        while rest.is_multiple_of(p) {
+            primes.push(p);
+            rest /= p;
+        }
+        p += 1;
+    }
+    if rest > 1 {
+        primes.push(rest);
+    }
+
+    // Простые множители от больших к меньшим раскладываем по ступеням
+    let mut stages: Vec<usize> = Vec::new();
+    for p in primes.into_iter().rev() {
+        match stages.iter_mut().find(|s| **s * p <= MAX_STAGE_FACTOR) {
+            Some(stage) => *stage *= p,
+            None => stages.push(p),
+        }
+    }
+    stages.sort_unstable_by(|a, b| b.cmp(a));
+    stages
+}
+
+// ФНЧ Чебышёва I, частота среза в долях частоты дискретизации
+fn lowpass_iir(signal: &[f64], cutoff: f64, zero_phase: bool) -> Result<Vec<f64>, String> {
+    let mut sections = iir::iirfilter(
+        IIR_ORDER,
+        &[cutoff],
+        BandType::Lowpass,
+        IirFamily::Chebyshev1,
+        IIR_RIPPLE,
+        0.0,
+        1.0,
+    )?;
+    if zero_phase {
+        filtfilt::sosfiltfilt(&sections, signal)
+    } else {
+        Ok(iir::sosfilt(&mut sections, signal))
+    }
+}
+
+fn decimate_stage(
+    signal: &[f64],
+    factor: usize,
+    filter: RateFilter,
+    zero_phase: bool,
+    quality: Quality,
+) -> Result<Vec<f64>, String> {
+    match filter {
+        // Полифазный фильтр уже компенсирует задержку
+        RateFilter::Fir if zero_phase => resample_poly(signal, 1, factor, quality),
+        RateFilter::Fir => {
+            let taps = antialias_filter(0.5 / factor as f64, 1.0, quality)?;
+            Ok(fir::fir_filter(signal, &taps)
+                .into_iter()
+                .step_by(factor)
+                .collect())
+        }
+        RateFilter::Iir => {
+            let filtered = lowpass_iir(signal, 0.4 / factor as f64, zero_phase)?;
+            Ok(filtered.into_iter().step_by(factor).collect())
+        }
+    }
+}
+
+fn interpolate_stage(
+    signal: &[f64],
+    factor: usize,
+    filter: RateFilter,
+    zero_phase: bool,
+    quality: Quality,
+) -> Result<Vec<f64>, String> {
+    if filter == RateFilter::Fir && zero_phase {
+        return resample_poly(signal, factor, 1, quality);
+    }
+    // Вставка нулей, усиление factor восстанавливает амплитуду после фильтра
+    let mut stuffed = vec![0.0; signal.len() * factor];
+    for (i, x) in signal.iter().enumerate() {
+        stuffed[i * factor] = x * factor as f64;
+    }
+    match filter {
+        RateFilter::Fir => {
+            let taps = antialias_filter(0.5 / factor as f64, 1.0, quality)?;
+            Ok(fir::fir_filter(&stuffed, &taps))
+        }
+        RateFilter::Iir => lowpass_iir(&stuffed, 0.4 / factor as f64, zero_phase),
+    }
+}
+
+/// Checks that the integer factor of rate change is at least 1
+pub fn check_factor(factor: usize) -> Result<(), String> {
+    if factor == 0 {
+        return Err("Factor must be positive".to_string());
+    }
+    Ok(())
+}
+
+/// Lowers the sample rate by an integer `factor` in stages, each stage
+/// filters out everything above its new Nyquist frequency
+pub fn decimate(
+    signal: &[f64],
+    factor: usize,
+    filter: RateFilter,
+    zero_phase: bool,
+    quality: Quality,
+) -> Result<Vec<f64>, String> {
+    check_factor(factor)?;
+    let mut sig_new = signal.to_vec();
+    for stage in stage_factors(factor) {
+        sig_new = decimate_stage(&sig_new, stage, filter, zero_phase, quality)?;
+    }
+    Ok(sig_new)
+}
+
+/// Raises the sample rate by an integer `factor` in stages, each stage
+/// removes images above the old Nyquist frequency
+pub fn interpolate(
+    signal: &[f64],
+    factor: usize,
+    filter: RateFilter,
+    zero_phase: bool,
+    quality: Quality,
+) -> Result<Vec<f64>, String> {
+    check_factor(factor)?;
+    let mut sig_new = signal.to_vec();
+    // Самая узкая по переходу ступень работает на низкой частоте
+    for stage in stage_factors(factor).into_iter().rev() {
+        sig_new = interpolate_stage(&sig_new, stage, filter, zero_phase, quality)?;
+    }
+    Ok(sig_new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{rms, tone};
+
+    fn max_error(result: &[f64], expected: &[f64], margin: usize) -> f64 {
+        result[margin..result.len() - margin]
+            .iter()
+            .zip(expected[margin..].iter())
+            .map(|(r, e)| (r - e).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_rational_factors() {
+        assert_eq!(rational_factors(44100, 48000), (160, 147));
+        assert_eq!(rational_factors(10000, 48000), (24, 5));
+        assert_eq!(rational_factors(48000, 16000), (1, 3));
+    }
+
+    #[test]
+    fn test_resolve_and_parse() {
+        assert_eq!(
+            ResampleMethod::Auto.resolve(44100, 48000),
+            ResampleMethod::Polyphase
+        );
+        assert_eq!(
+            ResampleMethod::Auto.resolve(44100, 47999),
+            ResampleMethod::Arbitrary
+        );
+        assert_eq!(
+            ResampleMethod::Arbitrary.resolve(44100, 48000),
+            ResampleMethod::Arbitrary
+        );
+        assert!(ResampleMethod::parse("linear").is_err());
+        assert_eq!(Quality::parse("high"), Ok(Quality::High));
+        assert!(Quality::parse("best").is_err());
+    }
+
+    #[test]
+    fn test_resample_poly_upsampling() {
+        let sig = tone(440.0, 10000.0, 2000);
+        let result = resample_poly(&sig, 24, 5, Quality::Medium).unwrap();
+        assert_eq!(result.len(), 9600);
+        let expected = tone(440.0, 48000.0, 9600);
+        assert!(max_error(&result, &expected, 500) < 1e-3);
+    }
+
+    #[test]
+    fn test_resample_poly_removes_aliases() {
+        let low = tone(1000.0, 48000.0, 9600);
+        let high = tone(7000.0, 48000.0, 9600);
+        let mixed: Vec<f64> = low.iter().zip(high.iter()).map(|(a, b)| a + b).collect();
+
+        let result = resample_poly(&mixed, 5, 24, Quality::Medium).unwrap();
+        assert_eq!(result.len(), 2000);
+        // 7 кГц выше новой частоты Найквиста 5 кГц и должен быть подавлен
+        let expected = tone(1000.0, 10000.0, 2000);
+        assert!(max_error(&result, &expected, 100) < 1e-3);
+    }
+
+    #[test]
+    fn test_resample_arbitrary() {
+        let sig = tone(300.0, 10000.0, 3000);
+        let ratio = 1.37;
+        let result = resample_arbitrary(&sig, ratio, Quality::Medium).unwrap();
+        assert_eq!(result.len(), 4110);
+        let expected = tone(300.0, 10000.0 * ratio, 4110);
+        assert!(max_error(&result, &expected, 300) < 1e-3);
+
+        // При понижении частоты ядро расширяется и подавляет тон выше Найквиста
+        let high = tone(4000.0, 10000.0, 3000);
+        let result = resample_arbitrary(&high, 0.61, Quality::Medium).unwrap();
+        assert!(rms(&result[100..1700]) < 1e-3);
+
+        assert!(resample_arbitrary(&sig, 0.0, Quality::Low).is_err());
+    }
+
+    #[test]
+    fn test_resample_preserves_duration() {
+        let sig = tone(100.0, 44100.0, 44100);
+        let (result, method) =
+            resample(&sig, 44100, 48000, ResampleMethod::Auto, Quality::Low).unwrap();
+        assert_eq!(method, ResampleMethod::Polyphase);
+        assert_eq!(result.len(), 48000);
+
+        let (result, method) =
+            resample(&sig, 44100, 47999, ResampleMethod::Auto, Quality::Low).unwrap();
+        assert_eq!(method, ResampleMethod::Arbitrary);
+        assert_eq!(result.len(), 47999);
+    }
+
+    #[test]
+    fn test_stage_factors() {
+        assert_eq!(stage_factors(1), Vec::<usize>::new());
+        assert_eq!(stage_factors(8), vec![8]);
+        assert_eq!(stage_factors(12), vec![6, 2]);
+        assert_eq!(stage_factors(100), vec![10, 10]);
+        assert_eq!(stage_factors(64), vec![8, 8]);
+        assert_eq!(stage_factors(26), vec![13, 2]);
+        assert!(RateFilter::parse("cic").is_err());
+    }
+
+    #[test]
+    fn test_decimate_removes_aliases() {
+        // 3 кГц выше новой частоты Найквиста 1 кГц и должен быть подавлен
+        let low = tone(100.0, 40000.0, 40000);
+        let high = tone(3000.0, 40000.0, 40000);
+        let mixed: Vec<f64> = low.iter().zip(high.iter()).map(|(a, b)| a + b).collect();
+        let expected = tone(100.0, 2000.0, 2000);
+
+        let result = decimate(&mixed, 20, RateFilter::Fir, true, Quality::Medium).unwrap();
+        assert_eq!(result.len(), 2000);
+        assert!(max_error(&result, &expected, 100) < 1e-3);
+
+        let result = decimate(&mixed, 20, RateFilter::Iir, true, Quality::Medium).unwrap();
+        assert_eq!(result.len(), 2000);
+        // Неравномерность 0.05 дБ на каждый из четырёх проходов двух ступеней
+        assert!(max_error(&result, &expected, 100) < 2.5e-2);
+
+        // Без компенсации фазы тон сохраняет амплитуду, но запаздывает
+        for filter in [RateFilter::Fir, RateFilter::Iir] {
+            let result = decimate(&mixed, 20, filter, false, Quality::Medium).unwrap();
+            assert!((rms(&result[200..1800]) - rms(&expected[200..1800])).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_interpolate_removes_images() {
+        let sig = tone(300.0, 2000.0, 1000);
+        let expected = tone(300.0, 24000.0, 12000);
+
+        let result = interpolate(&sig, 12, RateFilter::Fir, true, Quality::Medium).unwrap();
+        assert_eq!(result.len(), 12000);
+        assert!(max_error(&result, &expected, 1000) < 1e-3);
+
+        let result = interpolate(&sig, 12, RateFilter::Iir, true, Quality::Medium).unwrap();
+        assert!(max_error(&result, &expected, 1000) < 1e-2);
+
+        for filter in [RateFilter::Fir, RateFilter::Iir] {
+            let result = interpolate(&sig, 12, filter, false, Quality::Medium).unwrap();
+            assert_eq!(result.len(), 12000);
+            assert!((rms(&result[2000..10000]) - rms(&expected[2000..10000])).abs() < 1e-2);
+        }
+
+        assert!(interpolate(&sig, 0, RateFilter::Fir, true, Quality::Medium).is_err());
+    }
+}
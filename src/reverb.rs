@@ -0,0 +1,289 @@
+use crate::delay::{DelayLine, Interpolation};
+use crate::dsp;
+
+// Задержки гребёнок и всепропускающих звеньев Freeverb для 44.1 кГц
+const FREEVERB_RATE: f64 = 44100.0;
+const FREEVERB_COMBS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const FREEVERB_ALLPASSES: [usize; 4] = [556, 441, 341, 225];
+const FREEVERB_ALLPASS_GAIN: f64 = 0.5;
+
+// Взаимно простые длины линий FDN для 44.1 кГц
+const FDN_LINES: [usize; 8] = [1031, 1327, 1523, 1801, 2053, 2311, 2633, 2917];
+
+/// Reverb parameters: `rt60` and `pre_delay` in seconds, `damping` is the
+/// coefficient of one-pole lowpass in feedback loops, `mix` is the share of wet signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reverb {
+    pub rt60: f64,
+    pub pre_delay: f64,
+    pub damping: f64,
+    pub mix: f64,
+}
+
+// Длина задержки в отсчётах при частоте дискретизации fs, не меньше одного отсчёта
+fn scaled(length: usize, fs: f64) -> usize {
+    ((length as f64 * fs / FREEVERB_RATE).round() as usize).max(1)
+}
+
+impl Reverb {
+    fn check(&self) -> Result<(), String> {
+        if self.rt60 <= 0.0 {
+            return Err(format!("RT60 must be positive, got {}", self.rt60));
+        }
+        if self.pre_delay < 0.0 {
+            return Err(format!(
+                "Pre-delay must not be negative, got {}",
+                self.pre_delay
+            ));
+        }
+        if !(0.0..1.0).contains(&self.damping) {
+            return Err(format!("Damping must be in [0, 1), got {}", self.damping));
+        }
+        if !(0.0..=1.0).contains(&self.mix) {
+            return Err(format!("Mix must be in [0, 1], got {}", self.mix));
+        }
+        Ok(())
+    }
+
+    // Усиление петли с задержкой length, при котором затухание 60 дБ за rt60
+    fn loop_gain(&self, length: usize, fs: f64) -> f64 {
+        10f64.powf(-3.0 * length as f64 / (self.rt60 * fs))
+    }
+
+    fn pre_delayed(&self, signal: &[f64], fs: f64) -> Vec<f64> {
+        let delay = (self.pre_delay * fs).round() as usize;
+        let mut line = DelayLine::new(delay, Interpolation::Linear);
+        signal
+            .iter()
+            .map(|&x| {
+                line.push(x);
+                line.tap(delay)
+            })
+            .collect()
+    }
+
+    fn mixed(&self, signal: &[f64], wet: &[f64]) -> Vec<f64> {
+        signal
+            .iter()
+            .zip(wet.iter())
+            .map(|(x, w)| (1.0 - self.mix) * x + self.mix * w)
+            .collect()
+    }
+
+    /// Freeverb: eight parallel lowpass-feedback combs followed by four
+    /// series allpasses, delays are scaled from 44.1 kHz to `fs`
+    pub fn freeverb(&self, signal: &[f64], fs: f64) -> Result<Vec<f64>, String> {
+        self.check()?;
+        let input = self.pre_delayed(signal, fs);
+        let mut wet = vec![0.0; signal.len()];
+
+        for length in FREEVERB_COMBS.map(|length| scaled(length, fs)) {
+            let gain = self.loop_gain(length, fs);
+            let mut line = DelayLine::new(length, Interpolation::Linear);
+            let mut store = 0.0;
+            for (y, &x) in wet.iter_mut().zip(input.iter()) {
+                let out = line.tap(length - 1);
+                store = out * (1.0 - self.damping) + store * self.damping;
+                line.push(x + gain * store);
+                *y += out / FREEVERB_COMBS.len() as f64;
+            }
+        }
+
+        for length in FREEVERB_ALLPASSES.map(|length| scaled(length, fs)) {
+            let mut line = DelayLine::new(length, Interpolation::Linear);
+            for y in wet.iter_mut() {
+                let delayed = line.tap(length - 1);
+                line.push(*y + FREEVERB_ALLPASS_GAIN * delayed);
+                *y = delayed - *y;
+            }
+        }
+        Ok(self.mixed(signal, &wet))
+    }
+
+    /// Feedback delay network of `lines` delay lines with Householder
+    /// feedback matrix and per-line attenuation for the given RT60
+    pub fn fdn(&self, signal: &[f64], fs: f64, lines: usize) -> Result<Vec<f64>, String> {
+        self.check()?;
+        if lines == 0 || lines > FDN_LINES.len() {
+            return Err(format!(
+                "Number of lines must be from 1 to {}, got {lines}",
+                FDN_LINES.len()
+            ));
+        }
+        let lengths: Vec<usize> = FDN_LINES[..lines]
+            .iter()
+            .map(|&length| scaled(length, fs))
+            .collect();
+        let gains: Vec<f64> = lengths
+            .iter()
+            .map(|&length| self.loop_gain(length, fs))
+            .collect();
+        let mut delay_lines: Vec<DelayLine> = lengths
+            .iter()
+            .map(|&length| DelayLine::new(length, Interpolation::Linear))
+            .collect();
+        let mut stores = vec![0.0; lines];
+
+        let input = self.pre_delayed(signal, fs);
+        let wet: Vec<f64> = input
+            .iter()
+            .map(|&x| {
+                let outputs: Vec<f64> = delay_lines
+                    .iter()
+                    .zip(lengths.iter())
+                    .map(|(line, &length)| line.tap(length - 1))
+                    .collect();
+                for ((store, out), gain) in stores.iter_mut().zip(outputs.iter()).zip(gains.iter())
+                {
+                    *store = gain * out * (1.0 - self.damping) + *store * self.damping;
+                }
+                // Матрица Хаусхолдера I - 2/N * 11^T сохраняет энергию
+                let sum: f64 = stores.iter().sum();
+                for (line, store) in delay_lines.iter_mut().zip(stores.iter()) {
+                    line.push(x + store - 2.0 * sum / lines as f64);
+                }
+                outputs.iter().sum::<f64>() / lines as f64
+            })
+            .collect();
+        Ok(self.mixed(signal, &wet))
+    }
+
+    /// Convolution with measured impulse response `ir`, the output keeps
+    /// the length of the signal; `rt60` and `damping` are not used
+    pub fn convolve_ir(&self, signal: &[f64], ir: &[f64], fs: f64) -> Result<Vec<f64>, String> {
+        self.check()?;
+        if ir.is_empty() {
+            return Err("Impulse response is empty".to_string());
+        }
+        let mut kernel = vec![0.0; (self.pre_delay * fs).round() as usize];
+        kernel.extend_from_slice(ir);
+        let mut wet = dsp::convolve(
+            signal,
+            &kernel,
+            dsp::ConvolveMode::Full,
+            dsp::ConvolveMethod::Auto,
+        );
+        wet.truncate(signal.len());
+        Ok(self.mixed(signal, &wet))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::impulse;
+
+    const FS: f64 = 10000.0;
+
+    // Время затухания на 60 дБ по наклону кривой Шрёдера между -5 и -35 дБ
+    fn decay_time(response: &[f64]) -> f64 {
+        let mut energy: Vec<f64> = response
+            .iter()
+            .rev()
+            .scan(0.0, |sum, x| {
+                *sum += x * x;
+                Some(*sum)
+            })
+            .collect();
+        energy.reverse();
+        let edc: Vec<f64> = energy
+            .iter()
+            .map(|e| 10.0 * (e / energy[0]).log10())
+            .collect();
+        let start = edc.iter().position(|&l| l < -5.0).unwrap();
+        let end = edc.iter().position(|&l| l < -35.0).unwrap();
+        2.0 * (end - start) as f64 / FS
+    }
+
+    fn reverb(rt60: f64) -> Reverb {
+        Reverb {
+            rt60,
+            pre_delay: 0.0,
+            damping: 0.0,
+            mix: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_decay_time() {
+        for rt60 in [0.5, 1.2] {
+            let response = reverb(rt60).fdn(&impulse(40000), FS, 8).unwrap();
+            let measured = decay_time(&response);
+            assert!(
+                (measured - rt60).abs() < 0.1 * rt60,
+                "fdn {rt60} {measured}"
+            );
+
+            let response = reverb(rt60).freeverb(&impulse(40000), FS).unwrap();
+            let measured = decay_time(&response);
+            assert!(
+                (measured - rt60).abs() < 0.05 * rt60,
+                "freeverb {rt60} {measured}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_damping_and_pre_delay() {
+        // Демпфирование ускоряет затухание
+        let damped = Reverb {
+            damping: 0.5,
+            ..reverb(1.0)
+        };
+        let response = damped.fdn(&impulse(40000), FS, 4).unwrap();
+        assert!(decay_time(&response) < 0.9);
+
+        // Мокрый сигнал не появляется раньше предзадержки плюс кратчайшая линия
+        let delayed = Reverb {
+            pre_delay: 0.01,
+            mix: 0.5,
+            ..reverb(1.0)
+        };
+        for response in [
+            delayed.fdn(&impulse(1000), FS, 4).unwrap(),
+            delayed.freeverb(&impulse(1000), FS).unwrap(),
+        ] {
+            assert_eq!(response[0], 0.5);
+            assert!(response[1..100 + scaled(225, FS)].iter().all(|&x| x == 0.0));
+            assert!(response[100..].iter().any(|&x| x != 0.0));
+        }
+
+        assert!(
+            Reverb {
+                rt60: 0.0,
+                ..delayed
+            }
+            .fdn(&impulse(10), FS, 4)
+            .is_err()
+        );
+        assert!(
+            Reverb {
+                damping: 1.0,
+                ..delayed
+            }
+            .freeverb(&impulse(10), FS)
+            .is_err()
+        );
+        assert!(delayed.fdn(&impulse(10), FS, 9).is_err());
+    }
+
+    #[test]
+    fn test_convolve_ir() {
+        let signal = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let params = Reverb {
+            pre_delay: 0.0001,
+            mix: 1.0,
+            ..reverb(1.0)
+        };
+        let result = params.convolve_ir(&signal, &[0.0, 0.5], FS).unwrap();
+        let expected = [0.0, 0.0, 0.5, 1.0, 1.5];
+        for (y, e) in result.iter().zip(expected.iter()) {
+            assert!((y - e).abs() < 1e-12);
+        }
+
+        let params = Reverb { mix: 0.5, ..params };
+        let result = params.convolve_ir(&signal, &[0.0, 0.5], FS).unwrap();
+        assert!((result[4] - 3.25).abs() < 1e-12);
+        assert!(params.convolve_ir(&signal, &[], FS).is_err());
+    }
+}
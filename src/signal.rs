@@ -2,10 +2,19 @@ pub const SAMPLE_RATE: u32 = 10000;
 
 use std::f32;
 
-pub fn save_wave(signal: &Vec<f64>, filename: &str) -> Result<(), hound::Error> {
+pub fn save_wave(signal: &[f64], filename: &str) -> Result<(), hound::Error> {
+    save_wave_with_rate(signal, filename, SAMPLE_RATE)
+}
+
+/// Same as `save_wave`, but with the given sample rate
+pub fn save_wave_with_rate(
+    signal: &[f64],
+    filename: &str,
+    sample_rate: u32,
+) -> Result<(), hound::Error> {
     let spec = hound::WavSpec {
         channels: 1,
-        sample_rate: SAMPLE_RATE,
+        sample_rate,
         bits_per_sample: 32,
         sample_format: hound::SampleFormat::Float,
     };
@@ -18,13 +27,63 @@ pub fn save_wave(signal: &Vec<f64>, filename: &str) -> Result<(), hound::Error>
 }
 
 pub fn read_wave(signal: &mut Vec<f64>, filename: &str) -> Result<(), hound::Error> {
+    read_wave_with_rate(signal, filename).map(|_| ())
+}
+
+/// Same as `read_wave`, but also returns the sample rate stored in the file
+pub fn read_wave_with_rate(signal: &mut Vec<f64>, filename: &str) -> Result<u32, hound::Error> {
     let mut reader = hound::WavReader::open(filename)?;
+    let sample_rate = reader.spec().sample_rate;
 
     for sample in reader.samples::<f32>() {
         signal.push(sample? as f64);
     }
 
-    Ok(())
+    Ok(sample_rate)
+}
+
+pub fn read_sample_rate(filename: &str) -> Result<u32, hound::Error> {
+    Ok(hound::WavReader::open(filename)?.spec().sample_rate)
+}
+
+/// Process a wave file by blocks of `block_size` samples without loading it whole,
+/// the output keeps the sample rate. Returns the number of processed samples.
+pub fn process_wave_blocks<F>(
+    input: &str,
+    output: &str,
+    block_size: usize,
+    mut process: F,
+) -> Result<usize, hound::Error>
+where
+    F: FnMut(&[f64]) -> Vec<f64>,
+{
+    let mut reader = hound::WavReader::open(input)?;
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: reader.spec().sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(output, spec)?;
+
+    let mut samples = reader.samples::<f32>();
+    let mut block: Vec<f64> = Vec::with_capacity(block_size);
+    let mut total = 0;
+    loop {
+        block.clear();
+        for sample in samples.by_ref().take(block_size.max(1)) {
+            block.push(sample? as f64);
+        }
+        if block.is_empty() {
+            break;
+        }
+        for value in process(&block) {
+            writer.write_sample(value as f32)?;
+        }
+        total += block.len();
+    }
+    writer.finalize()?;
+    Ok(total)
 }
 
 #[cfg(test)]
@@ -56,4 +115,61 @@ mod tests {
 
         fs::remove_file(fname).ok();
     }
+
+    #[test]
+    fn test_read_wave_with_rate() {
+        let signal = generate::create_sine(50., 0., 0.01, 1.0);
+        let fname = "test_rate.wav";
+
+        save_wave(&signal, fname).expect("failed to save wav");
+
+        let mut readed_signal: Vec<f64> = Vec::new();
+        let rate = read_wave_with_rate(&mut readed_signal, fname).expect("failed to open wav");
+
+        assert_eq!(rate, SAMPLE_RATE);
+        assert_eq!(readed_signal.len(), signal.len());
+
+        fs::remove_file(fname).ok();
+    }
+    #[test]
+    fn test_save_wave_with_rate() {
+        let signal = generate::create_sine(50., 0., 0.01, 1.0);
+        let fname = "test_rate_48k.wav";
+
+        save_wave_with_rate(&signal, fname, 48000).expect("failed to save wav");
+
+        let mut readed_signal: Vec<f64> = Vec::new();
+        let rate = read_wave_with_rate(&mut readed_signal, fname).expect("failed to open wav");
+
+        assert_eq!(rate, 48000);
+        assert_eq!(readed_signal.len(), signal.len());
+
+        fs::remove_file(fname).ok();
+    }
+
+    #[test]
+    fn test_process_wave_blocks() {
+        let signal = generate::create_sine(50., 0., 0.1, 0.5);
+        let (input, output) = ("test_blocks_in.wav", "test_blocks_out.wav");
+        save_wave_with_rate(&signal, input, 8000).expect("failed to save wav");
+
+        let mut sizes = Vec::new();
+        let total = process_wave_blocks(input, output, 300, |block| {
+            sizes.push(block.len());
+            block.iter().map(|x| 2.0 * x).collect()
+        })
+        .expect("failed to process wav");
+        assert_eq!(total, 1000);
+        assert_eq!(sizes, vec![300, 300, 300, 100]);
+        assert_eq!(read_sample_rate(output).unwrap(), 8000);
+
+        let mut processed: Vec<f64> = Vec::new();
+        read_wave(&mut processed, output).expect("failed to open wav");
+        for (y, x) in processed.iter().zip(signal.iter()) {
+            assert!((y - 2.0 * x).abs() < 1e-6);
+        }
+
+        fs::remove_file(input).ok();
+        fs::remove_file(output).ok();
+    }
 }
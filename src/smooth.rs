@@ -0,0 +1,401 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use crate::dsp::{self, ConvolveMethod, ConvolveMode};
+
+// Ядро Гаусса обрезается на 4 сигмах, как в scipy.ndimage
+const GAUSSIAN_TRUNCATE: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeMode {
+    Valid,
+    Reflect,
+    Constant,
+    Nearest,
+}
+
+impl EdgeMode {
+    pub fn parse(name: &str) -> Result<EdgeMode, String> {
+        match name {
+            "valid" => Ok(EdgeMode::Valid),
+            "reflect" => Ok(EdgeMode::Reflect),
+            "constant" => Ok(EdgeMode::Constant),
+            "nearest" => Ok(EdgeMode::Nearest),
+            _ => Err(format!(
+                "Unknown edge mode {name}, expected valid, reflect, constant or nearest"
+            )),
+        }
+    }
+}
+
+/// Pad the signal for "same" output: reflect mirrors about the edge
+/// (d c b a | a b c d | d c b a), constant pads with zeros,
+/// nearest repeats the edge sample. Valid leaves the signal as is.
+pub fn pad(signal: &[f64], before: usize, after: usize, edge: EdgeMode) -> Vec<f64> {
+    let n = signal.len() as isize;
+    if edge == EdgeMode::Valid || n == 0 {
+        return signal.to_vec();
+    }
+    (-(before as isize)..n + after as isize)
+        .map(|i| match edge {
+            _ if (0..n).contains(&i) => signal[i as usize],
+            EdgeMode::Constant => 0.0,
+            EdgeMode::Nearest => signal[i.clamp(0, n - 1) as usize],
+            _ => {
+                // Отражение периодично с периодом 2n, поэтому работает и для окна длиннее сигнала
+                let m = i.rem_euclid(2 * n);
+                signal[(if m < n { m } else { 2 * n - 1 - m }) as usize]
+            }
+        })
+        .collect()
+}
+
+// Дополнение под центрированное окно длины window
+fn extend(signal: &[f64], window: usize, edge: EdgeMode) -> Vec<f64> {
+    let half = (window - 1) / 2;
+    pad(signal, half, window - 1 - half, edge)
+}
+
+fn check_window(window: usize) -> Result<(), String> {
+    if window.is_multiple_of(2) {
+        return Err(format!("Window length must be odd, got {window}"));
+    }
+    Ok(())
+}
+
+// Корреляция с ядром, отсчёт kernel[i] умножается на x[n + i - half]
+fn correlate(signal: &[f64], kernel: &[f64], edge: EdgeMode) -> Vec<f64> {
+    let padded = extend(signal, kernel.len(), edge);
+    if padded.len() < kernel.len() {
+        return Vec::new();
+    }
+    let reversed: Vec<f64> = kernel.iter().rev().cloned().collect();
+    dsp::convolve(
+        &padded,
+        &reversed,
+        ConvolveMode::Valid,
+        ConvolveMethod::Auto,
+    )
+}
+
+// Отсчёт с полным порядком для BTreeSet, индекс делает ключи уникальными
+#[derive(Debug, Clone, Copy)]
+struct Sample(f64, usize);
+
+impl PartialEq for Sample {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Sample {}
+
+impl PartialOrd for Sample {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Sample {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// Running median over an odd window, O(log window) per sample:
+/// the lower half of the window and the upper half are kept in two sorted sets
+pub fn median(signal: &[f64], window: usize, edge: EdgeMode) -> Result<Vec<f64>, String> {
+    check_window(window)?;
+    let padded = extend(signal, window, edge);
+    if padded.len() < window {
+        return Ok(Vec::new());
+    }
+
+    let mut low: BTreeSet<Sample> = BTreeSet::new();
+    let mut high: BTreeSet<Sample> = BTreeSet::new();
+    let mut result = Vec::with_capacity(padded.len() - window + 1);
+    for (i, &x) in padded.iter().enumerate() {
+        let sample = Sample(x, i);
+        if low.last().is_some_and(|top| sample < *top) {
+            low.insert(sample);
+        } else {
+            high.insert(sample);
+        }
+        if i >= window {
+            let old = Sample(padded[i - window], i - window);
+            if !low.remove(&old) {
+                high.remove(&old);
+            }
+        }
+        // В нижней половине на один отсчёт больше, её максимум и есть медиана
+        while low.len() > high.len() + 1 {
+            let top = low.pop_last().unwrap();
+            high.insert(top);
+        }
+        while low.len() < high.len() {
+            let bottom = high.pop_first().unwrap();
+            low.insert(bottom);
+        }
+        if i + 1 >= window {
+            result.push(low.last().unwrap().0);
+        }
+    }
+    Ok(result)
+}
+
+// Решение системы линейных уравнений методом Гаусса с выбором ведущего элемента
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Result<Vec<f64>, String> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        if a[pivot][col].abs() < 1e-12 {
+            return Err("Singular system in Savitzky-Golay design".to_string());
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (x, p) in a[row][col..].iter_mut().zip(pivot_row[col..].iter()) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Ok(x)
+}
+
+/// Savitzky-Golay coefficients of the least-squares polynomial of `polyorder`
+/// over an odd window, evaluated at the center; `deriv` gives the derivative
+/// per sample. Coefficient i multiplies the sample at offset i - window / 2.
+pub fn savgol_coeffs(window: usize, polyorder: usize, deriv: usize) -> Result<Vec<f64>, String> {
+    check_window(window)?;
+    if polyorder >= window {
+        return Err(format!(
+            "Polynomial order {polyorder} must be less than window length {window}"
+        ));
+    }
+    if deriv > polyorder {
+        return Ok(vec![0.0; window]);
+    }
+    // Смещения нормируются на половину окна, иначе степени плохо обусловлены
+    let center = (window / 2) as f64;
+    let half = center.max(1.0);
+    let offsets: Vec<f64> = (0..window).map(|i| (i as f64 - center) / half).collect();
+
+    // Нормальные уравнения (A^T A) c = e_deriv * deriv! / half^deriv, где A[i][k] = u_i^k
+    let order = polyorder + 1;
+    let gram: Vec<Vec<f64>> = (0..order)
+        .map(|j| {
+            (0..order)
+                .map(|k| offsets.iter().map(|x| x.powi((j + k) as i32)).sum())
+                .collect()
+        })
+        .collect();
+    let mut rhs = vec![0.0; order];
+    rhs[deriv] = (1..=deriv).product::<usize>() as f64 / half.powi(deriv as i32);
+    let c = solve(gram, rhs)?;
+
+    Ok(offsets
+        .iter()
+        .map(|x| (0..order).map(|k| c[k] * x.powi(k as i32)).sum())
+        .collect())
+}
+
+pub fn savgol(
+    signal: &[f64],
+    window: usize,
+    polyorder: usize,
+    deriv: usize,
+    edge: EdgeMode,
+) -> Result<Vec<f64>, String> {
+    let coeffs = savgol_coeffs(window, polyorder, deriv)?;
+    Ok(correlate(signal, &coeffs, edge))
+}
+
+/// Exponential moving average with alpha = 2 / (span + 1).
+/// The recursion starts from span - 1 padded samples before the signal,
+/// valid output drops the first span - 1 samples instead.
+pub fn ema(signal: &[f64], span: usize, edge: EdgeMode) -> Result<Vec<f64>, String> {
+    if span == 0 {
+        return Err("Span must be positive".to_string());
+    }
+    let alpha = 2.0 / (span as f64 + 1.0);
+    let padded = pad(signal, span - 1, 0, edge);
+    let Some(&first) = padded.first() else {
+        return Ok(Vec::new());
+    };
+
+    let mut state = first;
+    let smoothed: Vec<f64> = padded
+        .iter()
+        .map(|&x| {
+            state += alpha * (x - state);
+            state
+        })
+        .collect();
+    Ok(smoothed.into_iter().skip(span - 1).collect())
+}
+
+/// Normalized Gaussian kernel truncated at 4 sigma
+pub fn gaussian_kernel(sigma: f64) -> Result<Vec<f64>, String> {
+    if sigma <= 0.0 {
+        return Err(format!("Sigma must be positive, got {sigma}"));
+    }
+    let radius = (GAUSSIAN_TRUNCATE * sigma).round() as isize;
+    let kernel: Vec<f64> = (-radius..=radius)
+        .map(|i| (-0.5 * (i as f64 / sigma).powi(2)).exp())
+        .collect();
+    let sum: f64 = kernel.iter().sum();
+    Ok(kernel.iter().map(|k| k / sum).collect())
+}
+
+pub fn gaussian(signal: &[f64], sigma: f64, edge: EdgeMode) -> Result<Vec<f64>, String> {
+    let kernel = gaussian_kernel(sigma)?;
+    Ok(correlate(signal, &kernel, edge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Прямой расчёт медианы сортировкой окна
+    fn naive_median(signal: &[f64], window: usize) -> Vec<f64> {
+        signal
+            .windows(window)
+            .map(|w| {
+                let mut sorted = w.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                sorted[window / 2]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pad_modes() {
+        let sig = [1.0, 2.0, 3.0];
+        assert_eq!(
+            pad(&sig, 2, 2, EdgeMode::Reflect),
+            vec![2.0, 1.0, 1.0, 2.0, 3.0, 3.0, 2.0]
+        );
+        assert_eq!(
+            pad(&sig, 2, 1, EdgeMode::Constant),
+            vec![0.0, 0.0, 1.0, 2.0, 3.0, 0.0]
+        );
+        assert_eq!(
+            pad(&sig, 1, 2, EdgeMode::Nearest),
+            vec![1.0, 1.0, 2.0, 3.0, 3.0, 3.0]
+        );
+        assert_eq!(pad(&sig, 2, 2, EdgeMode::Valid), sig.to_vec());
+        // Окно длиннее сигнала
+        assert_eq!(
+            pad(&[1.0, 2.0], 3, 0, EdgeMode::Reflect),
+            vec![2.0, 2.0, 1.0, 1.0, 2.0]
+        );
+        assert_eq!(EdgeMode::parse("nearest"), Ok(EdgeMode::Nearest));
+        assert!(EdgeMode::parse("wrap").is_err());
+    }
+
+    #[test]
+    fn test_median_matches_sorting() {
+        let sig: Vec<f64> = (0..500).map(|i| ((i * 7919) % 113) as f64 - 56.0).collect();
+        for window in [1, 3, 11, 51] {
+            let result = median(&sig, window, EdgeMode::Valid).unwrap();
+            assert_eq!(result, naive_median(&sig, window));
+        }
+
+        // Одиночные выбросы полностью убираются
+        let mut spikes = vec![1.0; 20];
+        spikes[5] = 100.0;
+        spikes[12] = -100.0;
+        let result = median(&spikes, 3, EdgeMode::Nearest).unwrap();
+        assert_eq!(result, vec![1.0; 20]);
+
+        assert!(median(&sig, 4, EdgeMode::Valid).is_err());
+        assert!(median(&sig[..2], 5, EdgeMode::Valid).unwrap().is_empty());
+        assert_eq!(median(&sig[..2], 5, EdgeMode::Reflect).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_savgol_coeffs() {
+        // scipy.signal.savgol_coeffs(5, 2, use='dot')
+        let expected = [-3.0, 12.0, 17.0, 12.0, -3.0].map(|c| c / 35.0);
+        for (c, e) in savgol_coeffs(5, 2, 0).unwrap().iter().zip(expected.iter()) {
+            assert!((c - e).abs() < 1e-12);
+        }
+        // Первая производная, use='dot'
+        let expected = [-2.0, -1.0, 0.0, 1.0, 2.0].map(|c| c / 10.0);
+        for (c, e) in savgol_coeffs(5, 2, 1).unwrap().iter().zip(expected.iter()) {
+            assert!((c - e).abs() < 1e-12);
+        }
+        assert_eq!(savgol_coeffs(5, 2, 3).unwrap(), vec![0.0; 5]);
+        assert_eq!(savgol_coeffs(1, 0, 0).unwrap(), vec![1.0]);
+        assert!(savgol_coeffs(5, 5, 0).is_err());
+        assert!(savgol_coeffs(6, 2, 0).is_err());
+    }
+
+    #[test]
+    fn test_savgol_preserves_polynomial() {
+        let sig: Vec<f64> = (0..100)
+            .map(|n| {
+                let x = n as f64 / 10.0;
+                0.5 * x.powi(3) - x + 2.0
+            })
+            .collect();
+        let smooth = savgol(&sig, 11, 3, 0, EdgeMode::Valid).unwrap();
+        assert_eq!(smooth.len(), 90);
+        for (y, x) in smooth.iter().zip(sig[5..95].iter()) {
+            assert!((y - x).abs() < 1e-9);
+        }
+
+        let derivative = savgol(&sig, 11, 3, 1, EdgeMode::Reflect).unwrap();
+        assert_eq!(derivative.len(), 100);
+        for (n, d) in derivative.iter().enumerate().take(95).skip(5) {
+            let x = n as f64 / 10.0;
+            let expected = (1.5 * x.powi(2) - 1.0) / 10.0;
+            assert!((d - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_ema() {
+        let step = vec![1.0; 10];
+        // С нулевым дополнением рекурсия стартует с нуля
+        let result = ema(&step, 3, EdgeMode::Constant).unwrap();
+        let expected = [0.5, 0.75, 0.875];
+        for (y, e) in result.iter().zip(expected.iter()) {
+            assert!((y - e).abs() < 1e-12);
+        }
+        assert_eq!(ema(&step, 3, EdgeMode::Nearest).unwrap(), vec![1.0; 10]);
+        assert_eq!(ema(&step, 3, EdgeMode::Valid).unwrap().len(), 8);
+        assert_eq!(ema(&step, 1, EdgeMode::Valid).unwrap(), step);
+        assert!(ema(&step, 0, EdgeMode::Valid).is_err());
+    }
+
+    #[test]
+    fn test_gaussian() {
+        let kernel = gaussian_kernel(2.0).unwrap();
+        assert_eq!(kernel.len(), 17);
+        assert!((kernel.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        // Постоянная и линейная функции сохраняются симметричным ядром
+        let ramp: Vec<f64> = (0..50).map(|n| n as f64).collect();
+        let result = gaussian(&ramp, 2.0, EdgeMode::Valid).unwrap();
+        assert_eq!(result.len(), 34);
+        for (y, x) in result.iter().zip(ramp[8..42].iter()) {
+            assert!((y - x).abs() < 1e-9);
+        }
+        let result = gaussian(&[3.0; 20], 1.5, EdgeMode::Reflect).unwrap();
+        for y in &result {
+            assert!((y - 3.0).abs() < 1e-12);
+        }
+        assert!(gaussian_kernel(0.0).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+use std::f64::consts::PI;
+
+use rustfft::{FftPlanner, num_complex::Complex};
+
+use crate::resample::{self, Quality};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StretchMethod {
+    Vocoder,
+    Wsola,
+}
+
+impl StretchMethod {
+    pub fn parse(name: &str) -> Result<StretchMethod, String> {
+        match name {
+            "vocoder" => Ok(StretchMethod::Vocoder),
+            "wsola" => Ok(StretchMethod::Wsola),
+            _ => Err(format!(
+                "Unknown stretch method {name}, expected vocoder or wsola"
+            )),
+        }
+    }
+}
+
+fn check(factor: f64, frame: usize) -> Result<(), String> {
+    if factor <= 0.0 || !factor.is_finite() {
+        return Err(format!("Stretch factor must be positive, got {factor}"));
+    }
+    if frame < 16 || !frame.is_multiple_of(4) {
+        return Err(format!(
+            "Frame length must be a multiple of 4 not less than 16, got {frame}"
+        ));
+    }
+    Ok(())
+}
+
+// Периодическое окно Ханна: при шаге frame/2 сумма окон постоянна
+fn hann(frame: usize) -> Vec<f64> {
+    (0..frame)
+        .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f64 / frame as f64).cos())
+        .collect()
+}
+
+fn wrap(phase: f64) -> f64 {
+    phase - 2.0 * PI * (phase / (2.0 * PI)).round()
+}
+
+// Сигнал с нулями по краям: полкадра спереди и кадр сзади
+fn padded(signal: &[f64], frame: usize) -> Vec<f64> {
+    let mut padded = vec![0.0; frame / 2];
+    padded.extend_from_slice(signal);
+    padded.resize(padded.len() + frame, 0.0);
+    padded
+}
+
+// Индекс ближайшего пика спектра для каждого бина, границы областей посередине
+fn nearest_peaks(magnitude: &[f64]) -> Vec<usize> {
+    let bins = magnitude.len();
+    let peaks: Vec<usize> = (0..bins)
+        .filter(|&k| {
+            let lo = k.saturating_sub(2);
+            let hi = (k + 2).min(bins - 1);
+            magnitude[lo..=hi].iter().all(|&m| m <= magnitude[k]) && magnitude[k] > 0.0
+        })
+        .collect();
+    if peaks.is_empty() {
+        return (0..bins).collect();
+    }
+    let mut owner = Vec::with_capacity(bins);
+    let mut current = 0;
+    for k in 0..bins {
+        while current + 1 < peaks.len() && peaks[current + 1] + peaks[current] < 2 * k {
+            current += 1;
+        }
+        owner.push(peaks[current]);
+    }
+    owner
+}
+
+/// Phase vocoder with identity phase locking: the duration is multiplied by
+/// `factor`, the pitch is kept. Frames of `frame` samples overlap by 3/4.
+pub fn phase_vocoder(signal: &[f64], factor: f64, frame: usize) -> Result<Vec<f64>, String> {
+    check(factor, frame)?;
+    let synthesis_hop = frame / 4;
+    let analysis_hop = synthesis_hop as f64 / factor;
+    let bins = frame / 2 + 1;
+    let window = hann(frame);
+    let input = padded(signal, frame);
+
+    let mut planner = FftPlanner::new();
+    let forward = planner.plan_fft_forward(frame);
+    let inverse = planner.plan_fft_inverse(frame);
+
+    let frames = (signal.len() as f64 / analysis_hop).floor() as usize + 1;
+    let mut output = vec![0.0; frames * synthesis_hop + frame];
+    let mut norm = vec![0.0; output.len()];
+    let mut previous_phase = vec![0.0; bins];
+    let mut synthesis_phase = vec![0.0; bins];
+    let mut previous_start = 0;
+
+    for m in 0..frames {
+        let start = (m as f64 * analysis_hop).round() as usize;
+        let mut buffer: Vec<Complex<f64>> = input[start..start + frame]
+            .iter()
+            .zip(window.iter())
+            .map(|(x, w)| Complex::new(x * w, 0.0))
+            .collect();
+        forward.process(&mut buffer);
+        let magnitude: Vec<f64> = buffer[..bins].iter().map(|c| c.norm()).collect();
+        let phase: Vec<f64> = buffer[..bins].iter().map(|c| c.arg()).collect();
+
+        if m == 0 {
+            synthesis_phase.copy_from_slice(&phase);
+        } else {
+            // Фактический шаг анализа после округления позиций кадров
+            let hop = (start - previous_start) as f64;
+            let owner = nearest_peaks(&magnitude);
+            let mut advanced = synthesis_phase.clone();
+            for (k, &peak) in owner.iter().enumerate() {
+                if k != peak {
+                    continue;
+                }
+                let omega = 2.0 * PI * k as f64 / frame as f64;
+                let freq = if hop > 0.0 {
+                    omega + wrap(phase[k] - previous_phase[k] - omega * hop) / hop
+                } else {
+                    omega
+                };
+                advanced[k] = synthesis_phase[k] + synthesis_hop as f64 * freq;
+            }
+            // Бины вокруг пика сохраняют разность фаз с ним
+            for (k, &peak) in owner.iter().enumerate() {
+                synthesis_phase[k] = advanced[peak] + phase[k] - phase[peak];
+            }
+        }
+        previous_phase = phase;
+        previous_start = start;
+
+        for (c, (m, p)) in buffer
+            .iter_mut()
+            .zip(magnitude.iter().zip(synthesis_phase.iter()))
+        {
+            *c = Complex::from_polar(*m, *p);
+        }
+        // Эрмитова симметрия для вещественного выхода
+        for k in bins..frame {
+            buffer[k] = buffer[frame - k].conj();
+        }
+        inverse.process(&mut buffer);
+
+        let offset = m * synthesis_hop;
+        for (n, (c, w)) in buffer.iter().zip(window.iter()).enumerate() {
+            output[offset + n] += c.re / frame as f64 * w;
+            norm[offset + n] += w * w;
+        }
+    }
+
+    let length = (signal.len() as f64 * factor).round() as usize;
+    Ok(output
+        .iter()
+        .zip(norm.iter())
+        .skip(frame / 2)
+        .take(length)
+        .map(|(y, n)| if *n > 1e-3 { y / n } else { 0.0 })
+        .collect())
+}
+
+/// Waveform similarity overlap-add: frames are taken near their nominal
+/// positions with the offset that best continues the previous frame
+pub fn wsola(signal: &[f64], factor: f64, frame: usize) -> Result<Vec<f64>, String> {
+    check(factor, frame)?;
+    let synthesis_hop = frame / 2;
+    let analysis_hop = synthesis_hop as f64 / factor;
+    let tolerance = frame / 4;
+    let window = hann(frame);
+    // Запас на поиск смещения по обе стороны
+    let mut input = vec![0.0; tolerance];
+    input.extend(padded(signal, frame));
+    input.resize(input.len() + tolerance + synthesis_hop, 0.0);
+
+    let frames = (signal.len() as f64 / analysis_hop).floor() as usize + 1;
+    let mut output = vec![0.0; frames * synthesis_hop + frame];
+    let mut previous = tolerance;
+
+    for m in 0..frames {
+        let nominal = tolerance + (m as f64 * analysis_hop).round() as usize;
+        let start = if m == 0 {
+            nominal
+        } else {
+            // Естественное продолжение предыдущего кадра
+            let natural = &input[previous + synthesis_hop..previous + synthesis_hop + frame];
+            (nominal - tolerance..=nominal + tolerance)
+                .map(|s| {
+                    let score: f64 = input[s..s + frame]
+                        .iter()
+                        .zip(natural.iter())
+                        .map(|(x, y)| x * y)
+                        .sum();
+                    (score, s)
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map_or(nominal, |(_, s)| s)
+        };
+        previous = start;
+
+        let offset = m * synthesis_hop;
+        for (n, (x, w)) in input[start..start + frame]
+            .iter()
+            .zip(window.iter())
+            .enumerate()
+        {
+            output[offset + n] += x * w;
+        }
+    }
+
+    let length = (signal.len() as f64 * factor).round() as usize;
+    Ok(output.into_iter().skip(frame / 2).take(length).collect())
+}
+
+pub fn stretch(
+    signal: &[f64],
+    factor: f64,
+    method: StretchMethod,
+    frame: usize,
+) -> Result<Vec<f64>, String> {
+    match method {
+        StretchMethod::Vocoder => phase_vocoder(signal, factor, frame),
+        StretchMethod::Wsola => wsola(signal, factor, frame),
+    }
+}
+
+/// Pitch shift by `semitones` keeping the duration: stretch and resample back
+pub fn pitch_shift(
+    signal: &[f64],
+    semitones: f64,
+    method: StretchMethod,
+    frame: usize,
+) -> Result<Vec<f64>, String> {
+    let ratio = 2f64.powf(semitones / 12.0);
+    let stretched = stretch(signal, ratio, method, frame)?;
+    let mut shifted = resample::resample_arbitrary(&stretched, 1.0 / ratio, Quality::Medium)?;
+    shifted.resize(signal.len(), 0.0);
+    Ok(shifted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{peak, tone};
+
+    const FS: f64 = 10000.0;
+
+    // Частота по числу переходов через ноль снизу вверх
+    fn frequency(signal: &[f64]) -> f64 {
+        let crossings: Vec<usize> = signal
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0] < 0.0 && pair[1] >= 0.0)
+            .map(|(n, _)| n)
+            .collect();
+        let periods = (crossings.len() - 1) as f64;
+        periods * FS / (crossings[crossings.len() - 1] - crossings[0]) as f64
+    }
+
+    #[test]
+    fn test_vocoder_identity() {
+        let signal: Vec<f64> = tone(300.0, FS, 4000)
+            .iter()
+            .zip(tone(1234.0, FS, 4000).iter())
+            .map(|(a, b)| a + b)
+            .collect();
+        let result = phase_vocoder(&signal, 1.0, 512).unwrap();
+        assert_eq!(result.len(), signal.len());
+        for (y, x) in result.iter().zip(signal.iter()) {
+            assert!((y - x).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_stretch_keeps_pitch() {
+        let signal = tone(440.0, FS, 10000);
+        for method in [StretchMethod::Vocoder, StretchMethod::Wsola] {
+            for factor in [0.7, 1.5] {
+                let result = stretch(&signal, factor, method, 1024).unwrap();
+                let length = (10000.0 * factor) as usize;
+                assert_eq!(result.len(), length);
+                let middle = &result[1000..length - 1000];
+                let freq = frequency(middle);
+                assert!((freq - 440.0).abs() < 2.0, "{method:?} {factor} {freq}");
+                assert!((peak(middle) - 1.0).abs() < 0.1, "{method:?} {factor}");
+            }
+        }
+        assert!(stretch(&signal, 0.0, StretchMethod::Vocoder, 1024).is_err());
+        assert!(stretch(&signal, 2.0, StretchMethod::Wsola, 10).is_err());
+        assert_eq!(StretchMethod::parse("wsola"), Ok(StretchMethod::Wsola));
+        assert!(StretchMethod::parse("psola").is_err());
+    }
+
+    #[test]
+    fn test_pitch_shift() {
+        let signal = tone(300.0, FS, 10000);
+        for method in [StretchMethod::Vocoder, StretchMethod::Wsola] {
+            for (semitones, expected) in [(12.0, 600.0), (-7.0, 300.0 * 2f64.powf(-7.0 / 12.0))] {
+                let result = pitch_shift(&signal, semitones, method, 1024).unwrap();
+                assert_eq!(result.len(), signal.len());
+                let freq = frequency(&result[1500..8500]);
+                assert!(
+                    (freq - expected).abs() < 0.01 * expected,
+                    "{method:?} {semitones} {freq}"
+                );
+            }
+        }
+    }
+}
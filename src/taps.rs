@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TapsFormat {
+    Csv,
+    Json,
+    CHeader,
+}
+
+impl TapsFormat {
+    /// Picks format by extension: `.json`, `.h`, anything else is CSV
+    pub fn from_filename(filename: &str) -> TapsFormat {
+        match Path::new(filename).extension().and_then(|e| e.to_str()) {
+            Some("json") => TapsFormat::Json,
+            Some("h") => TapsFormat::CHeader,
+            _ => TapsFormat::Csv,
+        }
+    }
+}
+
+// Имя массива в C-заголовке строится из имени файла
+fn c_identifier(filename: &str) -> String {
+    let stem = Path::new(filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("taps");
+    let mut name: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Saves filter taps, format is selected by the file extension
+pub fn save_taps(taps: &[f64], filename: &str) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(filename)?);
+
+    match TapsFormat::from_filename(filename) {
+        TapsFormat::Csv => {
+            for tap in taps {
+                writeln!(writer, "{tap:e}")?;
+            }
+        }
+        TapsFormat::Json => {
+            let values: Vec<String> = taps.iter().map(|t| format!("{t:e}")).collect();
+            writeln!(writer, "{{\"taps\": [{}]}}", values.join(", "))?;
+        }
+        TapsFormat::CHeader => {
+            let name = c_identifier(filename);
+            let guard = name.to_uppercase();
+            writeln!(writer, "#ifndef {guard}_H")?;
+            writeln!(writer, "#define {guard}_H")?;
+            writeln!(writer)?;
+            writeln!(writer, "#define {guard}_LENGTH {}", taps.len())?;
+            writeln!(writer)?;
+            writeln!(writer, "static const double {name}[{guard}_LENGTH] = {{")?;
+            for tap in taps {
+                writeln!(writer, "    {tap:e},")?;
+            }
+            writeln!(writer, "}};")?;
+            writeln!(writer)?;
+            writeln!(writer, "#endif")?;
+        }
+    }
+
+    writer.flush()
+}
+
+/// Second-order section as numerator and denominator
+pub type Section = ([f64; 3], [f64; 3]);
+
+/// Saves second-order sections as CSV, one row `b0,b1,b2,a0,a1,a2` per section
+pub fn save_sos(sections: &[Section], filename: &str) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(filename)?);
+
+    for (b, a) in sections {
+        let row: Vec<String> = b.iter().chain(a.iter()).map(|c| format!("{c:e}")).collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    writer.flush()
+}
+
+fn parse_values(text: &str, filename: &str) -> Result<Vec<f64>, String> {
+    text.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<f64>()
+                .map_err(|e| format!("Bad value {v} in {filename}: {e}"))
+        })
+        .collect()
+}
+
+/// JSON file of taps as written by `save_taps`: `{"taps": [...]}`
+#[derive(Deserialize)]
+struct TapsFile {
+    taps: Vec<f64>,
+}
+
+/// Reads coefficients saved by `save_taps`: CSV or JSON (`.h` is not supported)
+pub fn read_taps(filename: &str) -> Result<Vec<f64>, String> {
+    let text =
+        std::fs::read_to_string(filename).map_err(|e| format!("Fail to read {filename}: {e}"))?;
+
+    let values = match TapsFormat::from_filename(filename) {
+        TapsFormat::Csv => parse_values(&text, filename)?,
+        TapsFormat::Json => {
+            let file: TapsFile = serde_json::from_str(&text)
+                .map_err(|e| format!("Malformed taps file {filename}: {e}"))?;
+            file.taps
+        }
+        TapsFormat::CHeader => {
+            return Err(format!("Reading of C headers is not supported: {filename}"));
+        }
+    };
+
+    if values.is_empty() {
+        return Err(format!("No coefficients in {filename}"));
+    }
+    Ok(values)
+}
+
+/// Reads sections saved by `save_sos`
+pub fn read_sos(filename: &str) -> Result<Vec<Section>, String> {
+    let text =
+        std::fs::read_to_string(filename).map_err(|e| format!("Fail to read {filename}: {e}"))?;
+
+    let mut sections = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let row = parse_values(line, filename)?;
+        if row.len() != 6 {
+            return Err(format!(
+                "Section must have 6 coefficients, got {} in {filename}",
+                row.len()
+            ));
+        }
+        sections.push(([row[0], row[1], row[2]], [row[3], row[4], row[5]]));
+    }
+
+    if sections.is_empty() {
+        return Err(format!("No sections in {filename}"));
+    }
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_format_from_filename() {
+        assert_eq!(TapsFormat::from_filename("taps.csv"), TapsFormat::Csv);
+        assert_eq!(TapsFormat::from_filename("taps.txt"), TapsFormat::Csv);
+        assert_eq!(TapsFormat::from_filename("taps"), TapsFormat::Csv);
+        assert_eq!(TapsFormat::from_filename("dir/taps.json"), TapsFormat::Json);
+        assert_eq!(TapsFormat::from_filename("lowpass.h"), TapsFormat::CHeader);
+    }
+
+    #[test]
+    fn test_c_identifier() {
+        assert_eq!(c_identifier("dir/low-pass.h"), "low_pass");
+        assert_eq!(c_identifier("1k.h"), "_1k");
+    }
+
+    #[test]
+    fn test_save_taps() {
+        let fname = "test_taps.csv";
+        save_taps(&[0.25, -0.5, 1.0 / 3.0], fname).expect("failed to save taps");
+
+        let text = fs::read_to_string(fname).unwrap();
+        let values: Vec<f64> = text.lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(values, vec![0.25, -0.5, 1.0 / 3.0]);
+
+        fs::remove_file(fname).ok();
+    }
+
+    #[test]
+    fn test_save_taps_json() {
+        let fname = "test_taps.json";
+        save_taps(&[0.25, -0.5], fname).expect("failed to save taps");
+
+        let text = fs::read_to_string(fname).unwrap();
+        assert_eq!(text, "{\"taps\": [2.5e-1, -5e-1]}\n");
+
+        fs::remove_file(fname).ok();
+    }
+
+    #[test]
+    fn test_save_taps_c_header() {
+        let fname = "test_taps.h";
+        save_taps(&[0.25, -0.5], fname).expect("failed to save taps");
+
+        let text = fs::read_to_string(fname).unwrap();
+        assert_eq!(
+            text,
+            "#ifndef TEST_TAPS_H
+#define TEST_TAPS_H
+
+#define TEST_TAPS_LENGTH 2
+
+static const double test_taps[TEST_TAPS_LENGTH] = {
+    2.5e-1,
+    -5e-1,
+};
+
+#endif
+"
+        );
+
+        fs::remove_file(fname).ok();
+    }
+
+    #[test]
+    fn test_save_sos() {
+        let fname = "test_sos.csv";
+        let sections = [([0.5, 1.0, 0.5], [1.0, -0.25, 0.125])];
+        save_sos(&sections, fname).expect("failed to save sections");
+
+        let text = fs::read_to_string(fname).unwrap();
+        assert_eq!(text, "5e-1,1e0,5e-1,1e0,-2.5e-1,1.25e-1\n");
+
+        fs::remove_file(fname).ok();
+    }
+
+    #[test]
+    fn test_read_taps() {
+        let taps = [0.25, -0.5, 1.0 / 3.0];
+        for fname in ["test_read_taps.csv", "test_read_taps.json"] {
+            save_taps(&taps, fname).expect("failed to save taps");
+            assert_eq!(read_taps(fname), Ok(taps.to_vec()));
+            fs::remove_file(fname).ok();
+        }
+
+        // JSON без ключа taps или с мусором не принимается
+        for text in [
+            "[1, 2, 3]",
+            "{\"b\": [1, 2]}",
+            "garbage [1, 2] garbage",
+            "{\"taps\": [1, \"x\"]}",
+        ] {
+            fs::write("test_read_taps.json", text).unwrap();
+            assert!(
+                read_taps("test_read_taps.json")
+                    .unwrap_err()
+                    .starts_with("Malformed taps file test_read_taps.json"),
+                "{text}"
+            );
+        }
+        fs::remove_file("test_read_taps.json").ok();
+
+        fs::write("test_read_taps.txt", "1, 2; 3\n\n4\n").unwrap();
+        assert_eq!(
+            read_taps("test_read_taps.txt"),
+            Ok(vec![1.0, 2.0, 3.0, 4.0])
+        );
+        fs::write("test_read_taps.txt", "1, x").unwrap();
+        assert!(read_taps("test_read_taps.txt").is_err());
+        fs::remove_file("test_read_taps.txt").ok();
+
+        assert!(read_taps("missing_taps.csv").is_err());
+    }
+
+    #[test]
+    fn test_read_sos() {
+        let fname = "test_read_sos.csv";
+        let sections = [
+            ([0.5, 1.0, 0.5], [1.0, -0.25, 0.125]),
+            ([1.0, -2.0, 1.0], [1.0, 0.5, 0.0]),
+        ];
+        save_sos(&sections, fname).expect("failed to save sections");
+        assert_eq!(read_sos(fname), Ok(sections.to_vec()));
+
+        fs::write(fname, "1,2,3\n").unwrap();
+        assert!(read_sos(fname).is_err());
+
+        fs::remove_file(fname).ok();
+    }
+}
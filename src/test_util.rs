@@ -0,0 +1,36 @@
+use std::f64::consts::PI;
+
+// Общие сигналы и измерения для модульных тестов
+
+pub fn impulse(length: usize) -> Vec<f64> {
+    let mut signal = vec![0.0; length];
+    signal[0] = 1.0;
+    signal
+}
+
+/// Sine of unit amplitude and frequency `freq` at sample rate `fs`
+pub fn tone(freq: f64, fs: f64, length: usize) -> Vec<f64> {
+    (0..length)
+        .map(|n| (2.0 * PI * freq * n as f64 / fs).sin())
+        .collect()
+}
+
+/// Magnitude of the frequency response of impulse response `response` at `freq`
+pub fn magnitude(response: &[f64], freq: f64, fs: f64) -> f64 {
+    let w = 2.0 * PI * freq / fs;
+    let (re, im) = response
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(re, im), (n, h)| {
+            (re + h * (w * n as f64).cos(), im - h * (w * n as f64).sin())
+        });
+    (re * re + im * im).sqrt()
+}
+
+pub fn peak(signal: &[f64]) -> f64 {
+    signal.iter().fold(0.0, |m, x| m.max(x.abs()))
+}
+
+pub fn rms(signal: &[f64]) -> f64 {
+    (signal.iter().map(|x| x * x).sum::<f64>() / signal.len() as f64).sqrt()
+}
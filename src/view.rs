@@ -0,0 +1,432 @@
+use rustfft::{FftPlanner, num_complex::Complex};
+
+// Нижняя граница спектра относительно максимума, дБ
+const SPECTRUM_RANGE_DB: f64 = 80.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Charset {
+    Braille,
+    Block,
+    Ascii,
+}
+
+impl Charset {
+    pub fn parse(name: &str) -> Result<Charset, String> {
+        match name {
+            "braille" => Ok(Charset::Braille),
+            "block" => Ok(Charset::Block),
+            "ascii" => Ok(Charset::Ascii),
+            _ => Err(format!(
+                "Unknown charset {name}, expected braille, block or ascii"
+            )),
+        }
+    }
+
+    // Сколько точек помещается в одну ячейку терминала (по x, по y)
+    fn cell_size(&self) -> (usize, usize) {
+        match self {
+            Charset::Braille => (2, 4),
+            Charset::Block => (1, 2),
+            Charset::Ascii => (1, 1),
+        }
+    }
+}
+
+/// Dot grid that is rendered into terminal characters
+pub struct Canvas {
+    charset: Charset,
+    width: usize,
+    height: usize,
+    dots: Vec<bool>,
+}
+
+impl Canvas {
+    /// `width` and `height` are given in terminal cells
+    pub fn new(width: usize, height: usize, charset: Charset) -> Canvas {
+        let (cw, ch) = charset.cell_size();
+        Canvas {
+            charset,
+            width: width * cw,
+            height: height * ch,
+            dots: vec![false; width * cw * height * ch],
+        }
+    }
+
+    pub fn dot_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn dot_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set(&mut self, x: usize, y: usize) {
+        if x < self.width && y < self.height {
+            self.dots[y * self.width + x] = true;
+        }
+    }
+
+    pub fn is_set(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.dots[y * self.width + x]
+    }
+
+    /// Vertical segment in column `x` between rows `y0` and `y1` inclusive
+    pub fn vline(&mut self, x: usize, y0: usize, y1: usize) {
+        let (lo, hi) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+        for y in lo..=hi {
+            self.set(x, y);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let (cw, ch) = self.charset.cell_size();
+        let mut out = String::new();
+
+        for row in 0..self.height / ch {
+            for col in 0..self.width / cw {
+                let (x, y) = (col * cw, row * ch);
+                let symbol = match self.charset {
+                    Charset::Braille => self.braille_cell(x, y),
+                    Charset::Block => match (self.is_set(x, y), self.is_set(x, y + 1)) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    },
+                    Charset::Ascii => {
+                        if self.is_set(x, y) {
+                            '*'
+                        } else {
+                            ' '
+                        }
+                    }
+                };
+                out.push(symbol);
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    fn braille_cell(&self, x: usize, y: usize) -> char {
+        // Нумерация точек символа Брайля (U+2800):
+        // 0 3
+        // 1 4
+        // 2 5
+        // 6 7
+        const BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        let mut code = 0x2800;
+        for (dy, row) in BITS.iter().enumerate() {
+            for (dx, bit) in row.iter().enumerate() {
+                if self.is_set(x + dx, y + dy) {
+                    code |= bit;
+                }
+            }
+        }
+        char::from_u32(code).unwrap_or(' ')
+    }
+}
+
+/// Splits the signal into `columns` buckets and keeps min and max of each one
+pub fn decimate_min_max(signal: &[f64], columns: usize) -> Vec<(f64, f64)> {
+    let mut out = Vec::with_capacity(columns);
+
+    if signal.is_empty() || columns == 0 {
+        return out;
+    }
+
+    for col in 0..columns {
+        let start = col * signal.len() / columns;
+        let end = ((col + 1) * signal.len() / columns).max(start + 1);
+        let bucket = &signal[start..end.min(signal.len())];
+
+        let min = bucket.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = bucket.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        out.push((min, max));
+    }
+
+    out
+}
+
+// Переводит значение в номер строки холста (0 — верх)
+fn value_to_row(value: f64, lo: f64, hi: f64, rows: usize) -> usize {
+    if hi <= lo {
+        return rows / 2;
+    }
+    let norm = ((value - lo) / (hi - lo)).clamp(0.0, 1.0);
+    ((1.0 - norm) * rows.saturating_sub(1) as f64).round() as usize
+}
+
+fn plot_min_max(canvas: &mut Canvas, columns: &[(f64, f64)], lo: f64, hi: f64) {
+    let rows = canvas.dot_height();
+    let mut prev: Option<(f64, f64)> = None;
+
+    for (x, &(min, max)) in columns.iter().enumerate() {
+        // Соединяем соседние столбцы, чтобы линия не рвалась
+        let (min, max) = match prev {
+            Some((pmin, pmax)) => (min.min(pmax), max.max(pmin)),
+            None => (min, max),
+        };
+        canvas.vline(
+            x,
+            value_to_row(min, lo, hi, rows),
+            value_to_row(max, lo, hi, rows),
+        );
+        prev = Some(columns[x]);
+    }
+}
+
+pub fn render_waveform(signal: &[f64], width: usize, height: usize, charset: Charset) -> String {
+    render_curve(signal, "amplitude", width, height, charset)
+}
+
+/// Draws values as a line, the first line of output is `label: min .. max`
+pub fn render_curve(
+    values: &[f64],
+    label: &str,
+    width: usize,
+    height: usize,
+    charset: Charset,
+) -> String {
+    let mut canvas = Canvas::new(width, height, charset);
+    let columns = decimate_min_max(values, canvas.dot_width());
+
+    let mut lo = columns.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+    let mut hi = columns
+        .iter()
+        .map(|c| c.1)
+        .fold(f64::NEG_INFINITY, f64::max);
+    // Почти постоянную кривую рисуем ровной линией, а не шумом округления
+    if hi - lo < 1e-9 * hi.abs().max(lo.abs()).max(1.0) {
+        lo = (lo + hi) / 2.0;
+        hi = lo;
+    }
+
+    plot_min_max(&mut canvas, &columns, lo, hi);
+
+    format!("{label}: {lo:.3} .. {hi:.3}\n{}", canvas.render())
+}
+
+/// Magnitude spectrum in dB of the Hann-windowed signal, bins from 0 to fs/2
+pub fn magnitude_spectrum_db(signal: &[f64]) -> Vec<f64> {
+    let n = signal.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut buffer: Vec<Complex<f64>> = signal
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let w = if n > 1 {
+                0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / (n - 1) as f64).cos()
+            } else {
+                1.0
+            };
+            Complex::new(x * w, 0.0)
+        })
+        .collect();
+
+    let mut planner = FftPlanner::new();
+    planner.plan_fft_forward(n).process(&mut buffer);
+
+    buffer[..n / 2 + 1]
+        .iter()
+        .map(|c| 20.0 * (c.norm() / n as f64).max(1e-12).log10())
+        .collect()
+}
+
+pub fn render_spectrum(
+    signal: &[f64],
+    sample_rate: u32,
+    width: usize,
+    height: usize,
+    charset: Charset,
+) -> String {
+    let mut canvas = Canvas::new(width, height, charset);
+    let spectrum = magnitude_spectrum_db(signal);
+    // Для спектра достаточно максимума в каждом столбце
+    let columns: Vec<(f64, f64)> = decimate_min_max(&spectrum, canvas.dot_width())
+        .into_iter()
+        .map(|(_, max)| (max, max))
+        .collect();
+
+    let hi = spectrum.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let lo = hi - SPECTRUM_RANGE_DB;
+
+    // Заливаем область под кривой
+    let rows = canvas.dot_height();
+    for (x, &(_, max)) in columns.iter().enumerate() {
+        canvas.vline(x, value_to_row(max, lo, hi, rows), rows.saturating_sub(1));
+    }
+
+    format!(
+        "frequency: 0 .. {} Hz, level: {lo:.1} .. {hi:.1} dB\n{}",
+        sample_rate / 2,
+        canvas.render()
+    )
+}
+
+/// Cuts the `[start, end)` range given in seconds out of the signal
+pub fn time_range(signal: &[f64], sample_rate: u32, start: f64, end: Option<f64>) -> &[f64] {
+    let to_index = |t: f64| ((t.max(0.0) * sample_rate as f64) as usize).min(signal.len());
+    let first = to_index(start);
+    let last = end.map(to_index).unwrap_or(signal.len()).max(first);
+
+    &signal[first..last]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+    use crate::signal;
+
+    #[test]
+    fn test_decimate_min_max_basic() {
+        let sig = vec![1.0, -1.0, 2.0, -2.0, 3.0, -3.0];
+        let result = decimate_min_max(&sig, 3);
+        assert_eq!(result, vec![(-1.0, 1.0), (-2.0, 2.0), (-3.0, 3.0)]);
+    }
+
+    #[test]
+    fn test_decimate_min_max_more_columns_than_samples() {
+        let sig = vec![1.0, 2.0];
+        let result = decimate_min_max(&sig, 4);
+        assert_eq!(result, vec![(1.0, 1.0), (1.0, 1.0), (2.0, 2.0), (2.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_decimate_min_max_empty() {
+        assert!(decimate_min_max(&[], 10).is_empty());
+        assert!(decimate_min_max(&[1.0], 0).is_empty());
+    }
+
+    #[test]
+    fn test_braille_full_cell() {
+        let mut canvas = Canvas::new(1, 1, Charset::Braille);
+        for x in 0..2 {
+            canvas.vline(x, 0, 3);
+        }
+        assert_eq!(canvas.render(), "⣿\n");
+    }
+
+    #[test]
+    fn test_braille_single_dots() {
+        let mut canvas = Canvas::new(1, 1, Charset::Braille);
+        canvas.set(0, 0);
+        assert_eq!(canvas.render(), "⠁\n");
+
+        let mut canvas = Canvas::new(1, 1, Charset::Braille);
+        canvas.set(1, 3);
+        assert_eq!(canvas.render(), "⢀\n");
+    }
+
+    #[test]
+    fn test_block_and_ascii_cells() {
+        let mut canvas = Canvas::new(2, 1, Charset::Block);
+        canvas.set(0, 0);
+        canvas.set(1, 1);
+        assert_eq!(canvas.render(), "▀▄\n");
+
+        let mut canvas = Canvas::new(2, 2, Charset::Ascii);
+        canvas.set(1, 1);
+        assert_eq!(canvas.render(), "  \n *\n");
+    }
+
+    #[test]
+    fn test_canvas_ignores_out_of_range() {
+        let mut canvas = Canvas::new(1, 1, Charset::Ascii);
+        canvas.set(5, 5);
+        assert_eq!(canvas.render(), " \n");
+    }
+
+    #[test]
+    fn test_charset_parse() {
+        assert_eq!(Charset::parse("braille"), Ok(Charset::Braille));
+        assert_eq!(Charset::parse("block"), Ok(Charset::Block));
+        assert_eq!(Charset::parse("ascii"), Ok(Charset::Ascii));
+        assert!(Charset::parse("unicode").is_err());
+    }
+
+    #[test]
+    fn test_render_waveform_size() {
+        let sig = generate::create_sine(50.0, 0.0, 1.0, 1.0);
+        let picture = render_waveform(&sig, 40, 10, Charset::Braille);
+        let lines: Vec<&str> = picture.lines().collect();
+
+        assert_eq!(lines.len(), 11);
+        assert!(lines[0].starts_with("amplitude:"));
+        assert!(lines[1..].iter().all(|l| l.chars().count() == 40));
+    }
+
+    #[test]
+    fn test_render_curve_rounding_noise_is_flat() {
+        let values: Vec<f64> = (0..100)
+            .map(|i| 2.0 + if i % 7 == 0 { 1e-14 } else { 0.0 })
+            .collect();
+        let picture = render_curve(&values, "delay", 10, 3, Charset::Ascii);
+        let lines: Vec<&str> = picture.lines().collect();
+
+        assert_eq!(lines[0], "delay: 2.000 .. 2.000");
+        assert_eq!(lines[2], "**********");
+        assert_eq!(lines[1].trim(), "");
+    }
+
+    #[test]
+    fn test_render_waveform_constant_signal() {
+        let sig = vec![0.5; 100];
+        let picture = render_waveform(&sig, 10, 3, Charset::Ascii);
+        let lines: Vec<&str> = picture.lines().collect();
+
+        // Постоянный сигнал рисуется горизонтальной линией посередине
+        assert_eq!(lines[2], "**********");
+        assert_eq!(lines[1].trim(), "");
+        assert_eq!(lines[3].trim(), "");
+    }
+
+    #[test]
+    fn test_magnitude_spectrum_peak() {
+        let freq = 1000.0;
+        let sig = generate::create_sine(freq, 0.0, 0.1, 1.0);
+        let spectrum = magnitude_spectrum_db(&sig);
+
+        let peak = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|(i, _)| i)
+            .unwrap();
+        let peak_freq = peak as f64 * signal::SAMPLE_RATE as f64 / sig.len() as f64;
+
+        assert!((peak_freq - freq).abs() < 20.0, "Peak at {peak_freq} Hz");
+    }
+
+    #[test]
+    fn test_render_spectrum_header() {
+        let sig = generate::create_sine(1000.0, 0.0, 0.1, 1.0);
+        let picture = render_spectrum(&sig, signal::SAMPLE_RATE, 20, 5, Charset::Block);
+        assert!(picture.starts_with("frequency: 0 .. 5000 Hz"));
+        assert_eq!(picture.lines().count(), 6);
+    }
+
+    #[test]
+    fn test_render_zero_height() {
+        let sig = generate::create_sine(1000.0, 0.0, 0.1, 1.0);
+        let picture = render_spectrum(&sig, signal::SAMPLE_RATE, 20, 0, Charset::Ascii);
+        assert_eq!(picture.lines().count(), 1);
+        let picture = render_waveform(&sig, 20, 0, Charset::Braille);
+        assert_eq!(picture.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_time_range() {
+        let sig: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        assert_eq!(time_range(&sig, 10, 1.0, Some(2.0)).len(), 10);
+        assert_eq!(time_range(&sig, 10, 1.0, Some(2.0))[0], 10.0);
+        assert_eq!(time_range(&sig, 10, 5.0, None).len(), 50);
+        assert!(time_range(&sig, 10, 20.0, None).is_empty());
+        assert!(time_range(&sig, 10, 3.0, Some(1.0)).is_empty());
+    }
+}
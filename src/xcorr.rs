@@ -0,0 +1,406 @@
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::dsp::{self, ConvolveMethod, ConvolveMode};
+
+// Полуширина сглаживания автоспектров для весов SCOT, в бинах
+const SCOT_SMOOTHING: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum XcorrScale {
+    None,
+    Biased,
+    Unbiased,
+    Normalized,
+}
+
+impl XcorrScale {
+    pub fn parse(name: &str) -> Result<XcorrScale, String> {
+        match name {
+            "none" => Ok(XcorrScale::None),
+            "biased" => Ok(XcorrScale::Biased),
+            "unbiased" => Ok(XcorrScale::Unbiased),
+            "normalized" => Ok(XcorrScale::Normalized),
+            _ => Err(format!(
+                "Unknown scale {name}, expected none, biased, unbiased or normalized"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GccWeighting {
+    None,
+    Phat,
+    Scot,
+}
+
+impl GccWeighting {
+    pub fn parse(name: &str) -> Result<GccWeighting, String> {
+        match name {
+            "none" => Ok(GccWeighting::None),
+            "phat" => Ok(GccWeighting::Phat),
+            "scot" => Ok(GccWeighting::Scot),
+            _ => Err(format!(
+                "Unknown weighting {name}, expected none, phat or scot"
+            )),
+        }
+    }
+}
+
+// Диапазон задержек -(len(y)-1)..=len(x)-1, ограниченный max_lag
+fn lag_range(x_length: usize, y_length: usize, max_lag: Option<usize>) -> (isize, isize) {
+    let mut lo = 1 - y_length as isize;
+    let mut hi = x_length as isize - 1;
+    if let Some(max_lag) = max_lag {
+        lo = lo.max(-(max_lag as isize));
+        hi = hi.min(max_lag as isize);
+    }
+    (lo, hi)
+}
+
+/// Cross-correlation r[k] = sum_n x[n + k] * y[n] for lags
+/// -(len(y) - 1)..=len(x) - 1, limited to |k| <= max_lag
+pub fn xcorr(
+    x: &[f64],
+    y: &[f64],
+    scale: XcorrScale,
+    max_lag: Option<usize>,
+) -> (Vec<isize>, Vec<f64>) {
+    if x.is_empty() || y.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    // Корреляция это свёртка с развёрнутым вторым сигналом
+    let reversed: Vec<f64> = y.iter().rev().cloned().collect();
+    let full = dsp::convolve(x, &reversed, ConvolveMode::Full, ConvolveMethod::Auto);
+
+    let (nx, ny) = (x.len() as isize, y.len() as isize);
+    let (lo, hi) = lag_range(x.len(), y.len(), max_lag);
+    let lags: Vec<isize> = (lo..=hi).collect();
+    let mut values: Vec<f64> = lags.iter().map(|k| full[(k + ny - 1) as usize]).collect();
+
+    match scale {
+        XcorrScale::None => {}
+        XcorrScale::Biased => {
+            let length = nx.max(ny) as f64;
+            values.iter_mut().for_each(|v| *v /= length);
+        }
+        XcorrScale::Unbiased => {
+            for (v, k) in values.iter_mut().zip(lags.iter()) {
+                // Число пар отсчётов, перекрывающихся при сдвиге k
+                let overlap = ny.min(nx - k) - (-k).max(0);
+                *v /= overlap as f64;
+            }
+        }
+        XcorrScale::Normalized => {
+            let energy_x: f64 = x.iter().map(|v| v * v).sum();
+            let energy_y: f64 = y.iter().map(|v| v * v).sum();
+            let norm = (energy_x * energy_y).sqrt();
+            if norm > 0.0 {
+                values.iter_mut().for_each(|v| *v /= norm);
+            }
+        }
+    }
+
+    (lags, values)
+}
+
+// Скользящее среднее по кругу частот
+fn smooth_spectrum(power: &[f64], half_width: usize) -> Vec<f64> {
+    let n = power.len();
+    let width = (2 * half_width + 1) as f64;
+    (0..n)
+        .map(|i| {
+            (0..=2 * half_width)
+                .map(|j| power[(i + n + j - half_width) % n])
+                .sum::<f64>()
+                / width
+        })
+        .collect()
+}
+
+/// Generalized cross-correlation with weighting of the cross spectrum,
+/// lags as in `xcorr`
+pub fn gcc(
+    x: &[f64],
+    y: &[f64],
+    weighting: GccWeighting,
+    max_lag: Option<usize>,
+) -> (Vec<isize>, Vec<f64>) {
+    if x.is_empty() || y.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let n = (x.len() + y.len() - 1).next_power_of_two();
+    let mut planner = FftPlanner::new();
+    let forward = planner.plan_fft_forward(n);
+    let inverse = planner.plan_fft_inverse(n);
+
+    let spectrum = |sig: &[f64]| {
+        let mut buffer = vec![Complex::new(0.0, 0.0); n];
+        for (c, v) in buffer.iter_mut().zip(sig.iter()) {
+            c.re = *v;
+        }
+        forward.process(&mut buffer);
+        buffer
+    };
+    let spec_x = spectrum(x);
+    let spec_y = spectrum(y);
+    let mut cross: Vec<Complex<f64>> = spec_x
+        .iter()
+        .zip(spec_y.iter())
+        .map(|(a, b)| a * b.conj())
+        .collect();
+
+    match weighting {
+        GccWeighting::None => {}
+        GccWeighting::Phat => {
+            // Бины без энергии не несут фазы, их обнуляем
+            let top = cross.iter().map(|c| c.norm()).fold(0.0, f64::max);
+            for c in cross.iter_mut() {
+                let magnitude = c.norm();
+                *c = if magnitude > top * 1e-12 {
+                    *c / magnitude
+                } else {
+                    Complex::new(0.0, 0.0)
+                };
+            }
+        }
+        GccWeighting::Scot => {
+            let power_x: Vec<f64> = spec_x.iter().map(|c| c.norm_sqr()).collect();
+            let power_y: Vec<f64> = spec_y.iter().map(|c| c.norm_sqr()).collect();
+            let power_x = smooth_spectrum(&power_x, SCOT_SMOOTHING);
+            let power_y = smooth_spectrum(&power_y, SCOT_SMOOTHING);
+            let weights: Vec<f64> = power_x
+                .iter()
+                .zip(power_y.iter())
+                .map(|(px, py)| (px * py).sqrt())
+                .collect();
+            let top = weights.iter().cloned().fold(0.0, f64::max);
+            for (c, w) in cross.iter_mut().zip(weights.iter()) {
+                *c = if *w > top * 1e-12 {
+                    *c / *w
+                } else {
+                    Complex::new(0.0, 0.0)
+                };
+            }
+        }
+    }
+    inverse.process(&mut cross);
+
+    let (lo, hi) = lag_range(x.len(), y.len(), max_lag);
+    let lags: Vec<isize> = (lo..=hi).collect();
+    // Отрицательные задержки лежат в конце буфера
+    let values = lags
+        .iter()
+        .map(|k| cross[(k + n as isize) as usize % n].re / n as f64)
+        .collect();
+
+    (lags, values)
+}
+
+/// Position of the maximum refined by a parabola through three points,
+/// returns the fractional index and the interpolated value
+pub fn parabolic_peak(values: &[f64]) -> Option<(f64, f64)> {
+    let (i, &peak) = values
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    if i == 0 || i == values.len() - 1 {
+        return Some((i as f64, peak));
+    }
+    let (left, right) = (values[i - 1], values[i + 1]);
+    let curvature = left - 2.0 * peak + right;
+    if curvature >= 0.0 {
+        return Some((i as f64, peak));
+    }
+    let delta = 0.5 * (left - right) / curvature;
+    Some((i as f64 + delta, peak - 0.25 * (left - right) * delta))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DelayEstimate {
+    pub lag: f64,
+    pub peak: f64,
+}
+
+/// Delay of `y` relative to `x` in samples, positive when `y` lags behind `x`
+pub fn estimate_delay(
+    x: &[f64],
+    y: &[f64],
+    weighting: GccWeighting,
+    max_lag: Option<usize>,
+) -> Option<DelayEstimate> {
+    let (lags, values) = gcc(y, x, weighting, max_lag);
+    let (index, peak) = parabolic_peak(&values)?;
+    Some(DelayEstimate {
+        lag: lags[0] as f64 + index,
+        peak,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlignMode {
+    None,
+    Integer,
+    Fractional,
+}
+
+impl AlignMode {
+    pub fn parse(name: &str) -> Result<AlignMode, String> {
+        match name {
+            "none" => Ok(AlignMode::None),
+            "integer" => Ok(AlignMode::Integer),
+            "fractional" => Ok(AlignMode::Fractional),
+            _ => Err(format!(
+                "Unknown align mode {name}, expected none, integer or fractional"
+            )),
+        }
+    }
+}
+
+/// Shifts `signal` to best match `reference` by GCC-PHAT delay estimate,
+/// returns the shifted signal and the removed delay in samples
+pub fn align(reference: &[f64], signal: &[f64], mode: AlignMode) -> (Vec<f64>, f64) {
+    let lag = match estimate_delay(reference, signal, GccWeighting::Phat, None) {
+        Some(estimate) if mode != AlignMode::None => estimate.lag,
+        _ => return (signal.to_vec(), 0.0),
+    };
+    let lag = if mode == AlignMode::Integer {
+        lag.round()
+    } else {
+        lag
+    };
+    (dsp::shift_signal(signal, -lag), lag)
+}
+
+/// Saves correlation as CSV with header `lag,value`
+pub fn save_xcorr(lags: &[isize], values: &[f64], filename: &str) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(writer, "lag,value")?;
+    for (lag, value) in lags.iter().zip(values.iter()) {
+        writeln!(writer, "{lag},{value}")?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    fn delayed(signal: &[f64], delay: usize) -> Vec<f64> {
+        let mut sig_new = vec![0.0; delay];
+        sig_new.extend_from_slice(&signal[..signal.len() - delay]);
+        sig_new
+    }
+
+    #[test]
+    fn test_xcorr_scales() {
+        // numpy.correlate(x, y, "full") = [2, 5, 8, 3]
+        let x = [1.0, 2.0, 3.0];
+        let y = [1.0, 2.0];
+        let (lags, values) = xcorr(&x, &y, XcorrScale::None, None);
+        assert_eq!(lags, vec![-1, 0, 1, 2]);
+        let expected = [2.0, 5.0, 8.0, 3.0];
+        for (v, e) in values.iter().zip(expected.iter()) {
+            assert!((v - e).abs() < 1e-12, "{v} {e}");
+        }
+
+        let (_, biased) = xcorr(&x, &y, XcorrScale::Biased, None);
+        assert!((biased[1] - 5.0 / 3.0).abs() < 1e-12);
+
+        let (_, unbiased) = xcorr(&x, &y, XcorrScale::Unbiased, None);
+        let expected = [2.0, 2.5, 4.0, 3.0];
+        for (v, e) in unbiased.iter().zip(expected.iter()) {
+            assert!((v - e).abs() < 1e-12, "{v} {e}");
+        }
+
+        let (_, normalized) = xcorr(&x, &x, XcorrScale::Normalized, None);
+        assert!((normalized[2] - 1.0).abs() < 1e-12);
+
+        let (lags, values) = xcorr(&x, &y, XcorrScale::None, Some(0));
+        assert_eq!(lags, vec![0]);
+        assert!((values[0] - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_xcorr_parse() {
+        assert_eq!(XcorrScale::parse("unbiased"), Ok(XcorrScale::Unbiased));
+        assert!(XcorrScale::parse("coeff").is_err());
+        assert_eq!(GccWeighting::parse("scot"), Ok(GccWeighting::Scot));
+        assert!(GccWeighting::parse("roth").is_err());
+    }
+
+    #[test]
+    fn test_gcc_without_weighting_matches_xcorr() {
+        let x = generate::create_sine(440.0, 0.3, 0.05, 1.0);
+        let y = generate::create_sine(300.0, 1.0, 0.03, 0.5);
+        let (lags, direct) = xcorr(&x, &y, XcorrScale::None, Some(100));
+        let (gcc_lags, values) = gcc(&x, &y, GccWeighting::None, Some(100));
+        assert_eq!(lags, gcc_lags);
+        for (a, b) in direct.iter().zip(values.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_parabolic_peak() {
+        // Парабола -(x - 2.3)^2 восстанавливается точно
+        let values: Vec<f64> = (0..5).map(|i| -(i as f64 - 2.3).powi(2)).collect();
+        let (index, peak) = parabolic_peak(&values).unwrap();
+        assert!((index - 2.3).abs() < 1e-12);
+        assert!(peak.abs() < 1e-12);
+
+        assert_eq!(parabolic_peak(&[3.0, 1.0]), Some((0.0, 3.0)));
+        assert_eq!(parabolic_peak(&[]), None);
+    }
+
+    #[test]
+    fn test_estimate_delay_of_noise() {
+        let noise = generate::create_noise(0.5, 1.0, 0.0).unwrap();
+        let late = delayed(&noise, 37);
+        for weighting in [GccWeighting::None, GccWeighting::Phat, GccWeighting::Scot] {
+            let estimate = estimate_delay(&noise, &late, weighting, None).unwrap();
+            assert!(
+                (estimate.lag - 37.0).abs() < 0.1,
+                "{weighting:?} {estimate:?}"
+            );
+
+            let estimate = estimate_delay(&late, &noise, weighting, Some(100)).unwrap();
+            assert!(
+                (estimate.lag + 37.0).abs() < 0.1,
+                "{weighting:?} {estimate:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_align() {
+        let noise = generate::create_noise(0.2, 1.0, 0.0).unwrap();
+        let late = delayed(&noise, 25);
+
+        let (aligned, lag) = align(&noise, &late, AlignMode::Integer);
+        assert_eq!(lag, 25.0);
+        assert_eq!(aligned.len(), late.len());
+        assert_eq!(&aligned[..noise.len() - 25], &noise[..noise.len() - 25]);
+
+        let (aligned, lag) = align(&noise, &late, AlignMode::None);
+        assert_eq!(lag, 0.0);
+        assert_eq!(aligned, late);
+
+        assert!(AlignMode::parse("sample").is_err());
+    }
+
+    #[test]
+    fn test_estimate_fractional_delay() {
+        let pulse = |shift: f64| -> Vec<f64> {
+            (0..400)
+                .map(|n| (-((n as f64 - 200.0 - shift) / 20.0).powi(2)).exp())
+                .collect()
+        };
+        let estimate = estimate_delay(&pulse(0.0), &pulse(7.3), GccWeighting::None, None).unwrap();
+        assert!((estimate.lag - 7.3).abs() < 0.05, "{estimate:?}");
+    }
+}
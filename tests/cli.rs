@@ -12,6 +12,26 @@ fn serial_test_guard() -> std::sync::MutexGuard<'static, ()> {
         .unwrap()
 }
 
+// Тон freq Гц длительностью 1 с с частотой дискретизации rate
+fn write_tone(fname: &str, freq: f64, rate: u32) -> Result<(), hound::Error> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(fname, spec)?;
+    for n in 0..rate {
+        let phase = 2.0 * std::f64::consts::PI * freq * n as f64 / rate as f64;
+        writer.write_sample(phase.sin() as f32)?;
+    }
+    writer.finalize()
+}
+
+fn wave_rate(fname: &str) -> Result<u32, hound::Error> {
+    Ok(hound::WavReader::open(fname)?.spec().sample_rate)
+}
+
 #[test]
 fn test_general_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
@@ -22,13 +42,38 @@ fn test_general_help() -> Result<(), Box<dyn std::error::Error>> {
 Usage: minidsp <COMMAND>
 
 Commands:
-  gen          Generare signal
-  add          Sum of two signals
-  sub          Substraction of two signals
-  mux          Multiplex of two signals
-  scale        Scaling of signal
-  mov-average  Moving average
-  help         Print this message or the help of the given subcommand(s)
+  gen             Generare signal
+  add             Sum of two signals
+  sub             Substraction of two signals
+  mux             Multiplex of two signals
+  scale           Scaling of signal
+  dyn             Dynamic range processing: compressor, limiter, expander, gate, AGC
+  normalize       Normalize signal to peak, true peak, RMS or integrated loudness
+  loudness        Loudness meter of EBU R128 and ITU-R BS.1770
+  mov-average     Moving average
+  smooth          Smooth signal by running median, Savitzky-Golay, exponential or Gaussian filter
+  filter          Filter signal
+  eq              Parametric equalizer from TOML, JSON or REW/AutoEQ preset
+  crossover       Split signal into bands by Linkwitz-Riley or linear-phase FIR crossover
+  design          Design filter and export its taps
+  view            Draw waveform or spectrum in terminal
+  convolve        Convolution of signal with kernel, e.g. room impulse response
+  analyze-filter  Frequency response, delays, zeros and poles of filter
+  resample        Sample rate conversion
+  stretch         Change duration keeping pitch by phase vocoder or WSOLA
+  pitch           Shift pitch keeping duration by phase vocoder or WSOLA
+  decimate        Lower sample rate by integer factor
+  interpolate     Raise sample rate by integer factor
+  cic             Fixed-point CIC decimator or interpolator with droop compensation
+  envelope        Envelope of signal by Hilbert transform
+  inst-phase      Unwrapped instantaneous phase in radians
+  inst-freq       Instantaneous frequency in Hz
+  xcorr           Cross-correlation of two signals or autocorrelation of one
+  delay           Delay of second signal relative to first
+  shift           Shift signal by fractional delay with zero or circular fill
+  effect          Delay-line effects: comb, echo, chorus, flanger, phaser
+  reverb          Reverb: Freeverb, feedback delay network or measured impulse response
+  help            Print this message or the help of the given subcommand(s)
 
 Options:
   -h, --help     Print help
@@ -101,6 +146,8 @@ Usage: minidsp add [OPTIONS] --signal1 <SIGNAL1> --signal2 <SIGNAL2>
 Options:
   -1, --signal1 <SIGNAL1>        first signal
   -2, --signal2 <SIGNAL2>        second signal
+  -a, --align <ALIGN>            align second signal to first: none, integer, fractional [default: none]
+  -l, --length <LENGTH>          length of output: shortest, longest, first [default: shortest]
   -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: sum_of_signals.wav]
   -h, --help                     Print help
 "#,
@@ -121,6 +168,8 @@ Usage: minidsp sub [OPTIONS] --signal1 <SIGNAL1> --signal2 <SIGNAL2>
 Options:
   -1, --signal1 <SIGNAL1>        first signal
   -2, --signal2 <SIGNAL2>        second signal
+  -a, --align <ALIGN>            align second signal to first: none, integer, fractional [default: none]
+  -l, --length <LENGTH>          length of output: shortest, longest, first [default: shortest]
   -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: sub_of_signals.wav]
   -h, --help                     Print help
 "#,
@@ -141,6 +190,8 @@ Usage: minidsp mux [OPTIONS] --signal1 <SIGNAL1> --signal2 <SIGNAL2>
 Options:
   -1, --signal1 <SIGNAL1>        first signal
   -2, --signal2 <SIGNAL2>        second signal
+  -a, --align <ALIGN>            align second signal to first: none, integer, fractional [default: none]
+  -l, --length <LENGTH>          length of output: shortest, longest, first [default: shortest]
   -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: mux_of_signals.wav]
   -h, --help                     Print help
 "#,
@@ -190,317 +241,3506 @@ Options:
 }
 
 #[test]
-fn test_gen_sine() -> Result<(), Box<dyn std::error::Error>> {
+fn test_dyn_compress_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen").arg("sine");
-    cmd.assert().success().stdout("Generate sinus\n");
+    cmd.arg("dyn").arg("compress").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Feed-forward compressor
 
-    fs::remove_file("sine_50hz.wav").ok();
+Usage: minidsp dyn compress [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -c, --sidechain <SIDECHAIN>    sidechain signal for level detection [default: signal]
+  -t, --threshold <THRESHOLD>    threshold in dBFS [default: -20]
+  -r, --ratio <RATIO>            ratio [default: 4]
+  -k, --knee <KNEE>              width of soft knee in dB [default: 6]
+  -a, --attack <ATTACK>          attack time in ms [default: 10]
+      --release <RELEASE>        release time in ms [default: 100]
+  -m, --makeup <MAKEUP>          makeup gain in dB [default: 0]
+  -d, --detector <DETECTOR>      level detector: peak, rms [default: peak]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: dynamics_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
     Ok(())
 }
 
 #[test]
-fn test_gen_noise() -> Result<(), Box<dyn std::error::Error>> {
+fn test_dyn_limit_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen").arg("noise");
-    cmd.assert().success().stdout("Genearate noise!!\n");
+    cmd.arg("dyn").arg("limit").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Brickwall limiter with lookahead
 
-    fs::remove_file("noise_1mu_1std.wav").ok();
+Usage: minidsp dyn limit [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -c, --sidechain <SIDECHAIN>    sidechain signal for level detection [default: signal]
+  -t, --threshold <THRESHOLD>    ceiling in dBFS [default: -1]
+  -l, --lookahead <LOOKAHEAD>    lookahead in ms [default: 5]
+      --release <RELEASE>        release time in ms [default: 50]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: dynamics_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
     Ok(())
 }
 
 #[test]
-fn test_gen_linear_sweep() -> Result<(), Box<dyn std::error::Error>> {
+fn test_dyn_expand_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen").arg("sweep");
-    cmd.assert().success().stdout("Genearate sweep!!\n");
+    cmd.arg("dyn").arg("expand").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Downward expander
 
-    fs::remove_file("sweep_1_1_linear.wav").ok();
+Usage: minidsp dyn expand [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -c, --sidechain <SIDECHAIN>    sidechain signal for level detection [default: signal]
+  -t, --threshold <THRESHOLD>    threshold in dBFS [default: -40]
+  -r, --ratio <RATIO>            ratio [default: 2]
+  -k, --knee <KNEE>              width of soft knee in dB [default: 6]
+  -a, --attack <ATTACK>          attack time in ms [default: 1]
+      --release <RELEASE>        release time in ms [default: 100]
+      --range <RANGE>            maximum attenuation in dB [default: -80]
+  -d, --detector <DETECTOR>      level detector: peak, rms [default: peak]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: dynamics_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
     Ok(())
 }
 
 #[test]
-fn test_gen_quadratic_sweep() -> Result<(), Box<dyn std::error::Error>> {
+fn test_dyn_gate_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen").arg("sweep").arg("-m").arg("quadratic");
-    cmd.assert().success().stdout("Genearate sweep!!\n");
+    cmd.arg("dyn").arg("gate").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Noise gate
 
-    fs::remove_file("sweep_1_1_quadratic.wav").ok();
+Usage: minidsp dyn gate [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -c, --sidechain <SIDECHAIN>    sidechain signal for level detection [default: signal]
+  -t, --threshold <THRESHOLD>    threshold in dBFS [default: -50]
+  -a, --attack <ATTACK>          attack time in ms [default: 1]
+      --release <RELEASE>        release time in ms [default: 50]
+      --range <RANGE>            maximum attenuation in dB [default: -80]
+  -d, --detector <DETECTOR>      level detector: peak, rms [default: peak]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: dynamics_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
     Ok(())
 }
 
 #[test]
-fn test_gen_hyperbolic_sweep() -> Result<(), Box<dyn std::error::Error>> {
+fn test_dyn_agc_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen").arg("sweep").arg("-m").arg("hyperbolic");
-    cmd.assert().success().stdout("Genearate sweep!!\n");
+    cmd.arg("dyn").arg("agc").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Automatic gain control, the file is processed by blocks
 
-    fs::remove_file("sweep_1_1_hyperbolic.wav").ok();
+Usage: minidsp dyn agc [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -t, --target <TARGET>          target peak level in dBFS [default: -20]
+  -g, --max-gain <MAX_GAIN>      maximum gain in dB [default: 30]
+  -a, --attack <ATTACK>          attack time in ms [default: 10]
+      --decay <DECAY>            decay time in ms [default: 1000]
+      --hold <HOLD>              hold time after peak in ms [default: 200]
+  -b, --block-size <BLOCK_SIZE>  number of samples read at once [default: 65536]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: dynamics_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
     Ok(())
 }
 
 #[test]
-fn test_dsp_add() -> Result<(), Box<dyn std::error::Error>> {
-    let _guard = serial_test_guard();
+fn test_normalize_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen")
-        .arg("sine")
-        .arg("-f")
-        .arg("30")
-        .arg("-d")
-        .arg("3")
-        .arg("-o")
-        .arg("sine1.wav");
-
-    cmd.assert().success().stdout("Generate sinus\n");
-
-    let mut cmd2 = Command::cargo_bin("minidsp")?;
-    cmd2.arg("gen")
-        .arg("sine")
-        .arg("-f")
-        .arg("30")
-        .arg("-d")
-        .arg("2")
-        .arg("-p")
-        .arg("180")
-        .arg("-o")
-        .arg("sine2.wav");
+    cmd.arg("normalize").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Normalize signal to peak, true peak, RMS or integrated loudness
 
-    cmd2.assert().success().stdout("Generate sinus\n");
+Usage: minidsp normalize [OPTIONS] --signal <SIGNAL>
 
-    let mut cmd3 = Command::cargo_bin("minidsp")?;
-    cmd3.arg("add")
-        .arg("-1")
-        .arg("sine1.wav")
-        .arg("-2")
-        .arg("sine2.wav")
-        .arg("-o")
-        .arg("sine3.wav");
+Options:
+  -s, --signal <SIGNAL>          signal
+  -m, --mode <MODE>              level: peak, true-peak, rms, lufs [default: lufs]
+  -t, --target <TARGET>          target level in dBFS, dBTP or LUFS [default: -23]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: normalized_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
-    cmd3.assert().success();
+    Ok(())
+}
 
-    let mut reader = hound::WavReader::open("sine3.wav")?;
+#[test]
+fn test_loudness_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("loudness").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Loudness meter of EBU R128 and ITU-R BS.1770
 
-    for sample in reader.samples::<f32>().flatten() {
-        assert!(sample.abs() < 1e-6, "Summ not equal zero, it's {sample}");
-    }
+Usage: minidsp loudness [OPTIONS] --signal <SIGNAL>
 
-    fs::remove_file("sine1.wav").ok();
-    fs::remove_file("sine2.wav").ok();
-    fs::remove_file("sine3.wav").ok();
+Options:
+  -s, --signal <SIGNAL>                signal
+  -t, --target <TARGET>                check loudness against target in LUFS, e.g. -23, exit 1 on failure
+      --tolerance <TOLERANCE>          permitted deviation from target in LU [default: 0.5]
+      --max-true-peak <MAX_TRUE_PEAK>  maximum permitted true peak in dBTP [default: -1]
+  -o, --out-csv <OUT_CSV>              save momentary and short-term loudness to csv
+  -h, --help                           Print help
+"#,
+    );
 
     Ok(())
 }
 
 #[test]
-fn test_dsp_sub() -> Result<(), Box<dyn std::error::Error>> {
-    let _guard = serial_test_guard();
+fn test_smooth_median_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen")
-        .arg("sine")
-        .arg("-f")
-        .arg("30")
-        .arg("-d")
-        .arg("3")
-        .arg("-o")
-        .arg("sine1.wav");
+    cmd.arg("smooth").arg("median").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Running median, removes impulse noise
 
-    cmd.assert().success().stdout("Generate sinus\n");
+Usage: minidsp smooth median [OPTIONS] --signal <SIGNAL>
 
-    let mut cmd2 = Command::cargo_bin("minidsp")?;
-    cmd2.arg("gen")
-        .arg("sine")
-        .arg("-f")
-        .arg("30")
-        .arg("-d")
-        .arg("2")
-        .arg("-o")
-        .arg("sine2.wav");
+Options:
+  -s, --signal <SIGNAL>          signal
+  -w, --window <WINDOW>          odd length of window [default: 5]
+  -e, --edge <EDGE>              edges: valid, reflect, constant, nearest [default: reflect]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: smoothed_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
-    cmd2.assert().success().stdout("Generate sinus\n");
+    Ok(())
+}
 
-    let mut cmd3 = Command::cargo_bin("minidsp")?;
-    cmd3.arg("sub")
-        .arg("-1")
-        .arg("sine1.wav")
-        .arg("-2")
-        .arg("sine2.wav")
-        .arg("-o")
-        .arg("sine3.wav");
+#[test]
+fn test_smooth_savgol_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("smooth").arg("savgol").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Savitzky-Golay smoothing or derivative
 
-    cmd3.assert().success();
+Usage: minidsp smooth savgol [OPTIONS] --signal <SIGNAL>
 
-    let mut reader = hound::WavReader::open("sine3.wav")?;
+Options:
+  -s, --signal <SIGNAL>          signal
+  -w, --window <WINDOW>          odd length of window [default: 11]
+  -p, --polyorder <POLYORDER>    order of polynomial [default: 2]
+  -d, --deriv <DERIV>            order of derivative, per sample [default: 0]
+  -e, --edge <EDGE>              edges: valid, reflect, constant, nearest [default: reflect]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: smoothed_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
-    for sample in reader.samples::<f32>().flatten() {
-        assert!(sample.abs() < 1e-6, "Sub not equal zero, it's {sample}");
-    }
+    Ok(())
+}
 
-    fs::remove_file("sine1.wav").ok();
-    fs::remove_file("sine2.wav").ok();
-    fs::remove_file("sine3.wav").ok();
+#[test]
+fn test_smooth_ema_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("smooth").arg("ema").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Exponential moving average
+
+Usage: minidsp smooth ema [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -w, --span <SPAN>              span, alpha = 2 / (span + 1) [default: 10]
+  -e, --edge <EDGE>              edges: valid, reflect, constant, nearest [default: reflect]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: smoothed_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
     Ok(())
 }
 
 #[test]
-fn test_dsp_mux() -> Result<(), Box<dyn std::error::Error>> {
-    let _guard = serial_test_guard();
+fn test_smooth_gaussian_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen")
-        .arg("sine")
-        .arg("-f")
-        .arg("30")
-        .arg("-d")
-        .arg("3")
-        .arg("-o")
-        .arg("sine1.wav");
-
-    cmd.assert().success().stdout("Generate sinus\n");
+    cmd.arg("smooth").arg("gaussian").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Gaussian smoothing
 
-    let mut cmd2 = Command::cargo_bin("minidsp")?;
-    cmd2.arg("gen")
-        .arg("sine")
-        .arg("-f")
-        .arg("30")
-        .arg("-d")
-        .arg("2")
-        .arg("-a")
-        .arg("10")
-        .arg("-o")
-        .arg("sine2.wav");
+Usage: minidsp smooth gaussian [OPTIONS] --signal <SIGNAL>
 
-    cmd2.assert().success().stdout("Generate sinus\n");
+Options:
+  -s, --signal <SIGNAL>          signal
+      --sigma <SIGMA>            standard deviation in samples, kernel is truncated at 4 sigma [default: 2]
+  -e, --edge <EDGE>              edges: valid, reflect, constant, nearest [default: reflect]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: smoothed_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
 
-    let mut cmd3 = Command::cargo_bin("minidsp")?;
-    cmd3.arg("mux")
-        .arg("-1")
-        .arg("sine1.wav")
-        .arg("-2")
-        .arg("sine2.wav")
-        .arg("-o")
-        .arg("sine3.wav");
+    Ok(())
+}
 
-    cmd3.assert().success();
+#[test]
+fn test_filter_fir_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("filter").arg("fir").arg("-h");
+    cmd.assert().success().stdout(
+        r#"FIR filter designed by windowed-sinc method
 
-    let mut reader = hound::WavReader::open("sine3.wav")?;
-    let mut max_val = 0.0;
-    for sample_real in reader.samples::<f32>().flatten() {
-        if sample_real > max_val {
-            max_val = sample_real;
-        }
-    }
+Usage: minidsp filter fir [OPTIONS] --signal <SIGNAL> --cutoff <CUTOFF>
 
-    assert!(
-        (max_val - 10.).abs() < 1e-6,
-        "Amplitede should be around 10, but it's {max_val}"
+Options:
+  -s, --signal <SIGNAL>            signal
+  -t, --band-type <BAND_TYPE>      type of filter: lowpass, highpass, bandpass, bandstop [default: lowpass]
+  -c, --cutoff <CUTOFF>            cutoff frequencies in Hz, two for bandpass and bandstop
+  -l, --length <LENGTH>            length of filter
+      --transition <TRANSITION>    transition width in Hz, length is selected for Kaiser window
+  -a, --attenuation <ATTENUATION>  stopband attenuation in dB for Kaiser window [default: 60]
+  -w, --window <WINDOW>            window: rectangular, hann, hamming, blackman, kaiser [default: hamming]
+      --taps-out <TAPS_OUT>        save taps of filter to file, format by extension: .csv, .json, .h
+  -o, --out-signal <OUT_SIGNAL>    fname of output signal [default: filtered_signal.wav]
+  -h, --help                       Print help
+"#,
     );
 
-    fs::remove_file("sine1.wav").ok();
-    fs::remove_file("sine2.wav").ok();
-    fs::remove_file("sine3.wav").ok();
-
     Ok(())
 }
 
 #[test]
-fn test_dsp_scale() -> Result<(), Box<dyn std::error::Error>> {
-    let _guard = serial_test_guard();
+fn test_filter_biquad_help() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("minidsp")?;
-    cmd.arg("gen")
-        .arg("sine")
-        .arg("-f")
-        .arg("30")
-        .arg("-d")
-        .arg("3")
-        .arg("-o")
-        .arg("sine1.wav");
+    cmd.arg("filter").arg("biquad").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Biquad filter from RBJ audio EQ cookbook
 
-    cmd.assert().success().stdout("Generate sinus\n");
+Usage: minidsp filter biquad [OPTIONS] --signal <SIGNAL>
 
-    let mut cmd2 = Command::cargo_bin("minidsp")?;
-    cmd2.arg("scale")
+Options:
+  -s, --signal <SIGNAL>          signal
+  -t, --type <KIND>              type of filter: lowpass, highpass, bandpass, notch, allpass, peaking, lowshelf, highshelf [default: lowpass]
+  -f, --freq <FREQ>              frequency in Hz [default: 1000]
+  -q, --q <Q>                    quality factor (slope for shelves) [default: 0.707]
+  -g, --gain <GAIN>              gain in dB for peaking and shelves [default: 0]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: filtered_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_iir_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("filter").arg("iir").arg("-h");
+    cmd.assert().success().stdout(
+        r#"IIR filter as a cascade of second-order sections
+
+Usage: minidsp filter iir [OPTIONS] --signal <SIGNAL> --cutoff <CUTOFF>
+
+Options:
+  -s, --signal <SIGNAL>            signal
+  -f, --family <FAMILY>            family of filter: butter, cheby1, cheby2, ellip, bessel [default: butter]
+  -t, --band-type <BAND_TYPE>      type of filter: lowpass, highpass, bandpass, bandstop [default: lowpass]
+  -c, --cutoff <CUTOFF>            cutoff frequencies in Hz, two for bandpass and bandstop
+  -n, --order <ORDER>              order of prototype, doubled for bandpass and bandstop [default: 4]
+  -r, --ripple <RIPPLE>            passband ripple in dB for cheby1 and ellip [default: 1]
+  -a, --attenuation <ATTENUATION>  stopband attenuation in dB for cheby2 and ellip [default: 60]
+      --sos-out <SOS_OUT>          save sections to csv, row per section: b0,b1,b2,a0,a1,a2
+  -o, --out-signal <OUT_SIGNAL>    fname of output signal [default: filtered_signal.wav]
+  -h, --help                       Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_filtfilt_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("filter").arg("filtfilt").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Zero-phase forward-backward filtering by coefficients from files
+
+Usage: minidsp filter filtfilt [OPTIONS] --signal <SIGNAL> <--taps <TAPS>|--numerator <NUMERATOR>|--sos <SOS>>
+
+Options:
+  -s, --signal <SIGNAL>            signal
+      --taps <TAPS>                file with FIR taps, csv or json
+  -b, --numerator <NUMERATOR>      file with numerator b of transfer function
+  -a, --denominator <DENOMINATOR>  file with denominator a of transfer function [default: 1]
+      --sos <SOS>                  file with second-order sections saved by filter iir
+  -o, --out-signal <OUT_SIGNAL>    fname of output signal [default: filtered_signal.wav]
+  -h, --help                       Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_convolve_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("convolve").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Convolution of signal with kernel, e.g. room impulse response
+
+Usage: minidsp convolve [OPTIONS] --signal <SIGNAL> --kernel <KERNEL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -k, --kernel <KERNEL>          kernel: .wav file or taps in .csv, .json
+  -m, --mode <MODE>              output mode: full, same, valid [default: full]
+      --method <METHOD>          method: auto, direct, overlap-add, overlap-save [default: auto]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: convolved_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_analyze_filter_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("analyze-filter").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Frequency response, delays, zeros and poles of filter
+
+Usage: minidsp analyze-filter [OPTIONS] <--taps <TAPS>|--numerator <NUMERATOR>|--sos <SOS>|--mov-average <MOV_AVERAGE>>
+
+Options:
+      --taps <TAPS>                file with FIR taps, csv or json
+  -b, --numerator <NUMERATOR>      file with numerator b of transfer function
+  -a, --denominator <DENOMINATOR>  file with denominator a of transfer function [default: 1]
+      --sos <SOS>                  file with second-order sections saved by filter iir
+  -m, --mov-average <MOV_AVERAGE>  length of window of moving average
+  -n, --points <POINTS>            number of frequencies from 0 to fs/2 [default: 512]
+      --fs <FS>                    sample rate in Hz [default: 10000]
+  -o, --out-csv <OUT_CSV>          fname of csv with frequency, magnitude, phase, group and phase delay [default: filter_response.csv]
+      --pz-out <PZ_OUT>            save zeros and poles to csv
+  -w, --width <WIDTH>              width of plots in characters [default: 80]
+      --height <HEIGHT>            height of plots in characters [default: 12]
+  -c, --charset <CHARSET>          characters for drawing: braille, block, ascii [default: braille]
+  -h, --help                       Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_resample_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("resample").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Sample rate conversion
+
+Usage: minidsp resample [OPTIONS] --signal <SIGNAL> --to <TO>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -t, --to <TO>                  new sample rate in Hz
+  -q, --quality <QUALITY>        quality of anti-aliasing filter: low, medium, high [default: medium]
+  -m, --method <METHOD>          method: auto, polyphase, arbitrary [default: auto]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: resampled_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_stretch_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("stretch").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Change duration keeping pitch by phase vocoder or WSOLA
+
+Usage: minidsp stretch [OPTIONS] --signal <SIGNAL> --factor <FACTOR>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -f, --factor <FACTOR>          ratio of output duration to input duration
+  -m, --method <METHOD>          method: vocoder, wsola [default: vocoder]
+  -n, --frame <FRAME>            frame length in samples [default: 1024]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: stretched_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pitch_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("pitch").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Shift pitch keeping duration by phase vocoder or WSOLA
+
+Usage: minidsp pitch [OPTIONS] --signal <SIGNAL> --semitones <SEMITONES>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -t, --semitones <SEMITONES>    pitch shift in semitones
+  -m, --method <METHOD>          method: vocoder, wsola [default: vocoder]
+  -n, --frame <FRAME>            frame length in samples [default: 1024]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: pitched_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_decimate_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("decimate").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Lower sample rate by integer factor
+
+Usage: minidsp decimate [OPTIONS] --signal <SIGNAL> --factor <FACTOR>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -f, --factor <FACTOR>          decimation factor
+      --filter <FILTER>          filter: fir, iir [default: fir]
+  -z, --zero-phase               compensate phase of filter
+  -q, --quality <QUALITY>        quality of fir filter: low, medium, high [default: medium]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: decimated_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_interpolate_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("interpolate").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Raise sample rate by integer factor
+
+Usage: minidsp interpolate [OPTIONS] --signal <SIGNAL> --factor <FACTOR>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -f, --factor <FACTOR>          interpolation factor
+      --filter <FILTER>          filter: fir, iir [default: fir]
+  -z, --zero-phase               compensate phase of filter
+  -q, --quality <QUALITY>        quality of fir filter: low, medium, high [default: medium]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: interpolated_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cic_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("cic").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Fixed-point CIC decimator or interpolator with droop compensation
+
+Usage: minidsp cic [OPTIONS] --signal <SIGNAL> --rate <RATE>
+
+Options:
+  -s, --signal <SIGNAL>              signal
+  -m, --mode <MODE>                  mode: decimate, interpolate [default: decimate]
+  -r, --rate <RATE>                  rate change factor R
+  -n, --stages <STAGES>              number of stages N [default: 4]
+  -d, --delay <DELAY>                differential delay M [default: 1]
+  -b, --bits <BITS>                  bits of input samples [default: 16]
+  -c, --compensation <COMPENSATION>  length of droop compensation FIR
+      --passband <PASSBAND>          compensated band as fraction of low-rate Nyquist [default: 0.5]
+      --taps-out <TAPS_OUT>          save compensation taps to .csv, .json or .h
+  -o, --out-signal <OUT_SIGNAL>      fname of output signal [default: cic_signal.wav]
+  -h, --help                         Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_envelope_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("envelope").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Envelope of signal by Hilbert transform
+
+Usage: minidsp envelope [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -m, --method <METHOD>          Hilbert transform: fft, fir [default: fft]
+  -l, --fir-length <FIR_LENGTH>  odd length of FIR Hilbert transformer [default: 101]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: envelope.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_inst_phase_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("inst-phase").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Unwrapped instantaneous phase in radians
+
+Usage: minidsp inst-phase [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -m, --method <METHOD>          Hilbert transform: fft, fir [default: fft]
+  -l, --fir-length <FIR_LENGTH>  odd length of FIR Hilbert transformer [default: 101]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: inst_phase.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_inst_freq_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("inst-freq").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Instantaneous frequency in Hz
+
+Usage: minidsp inst-freq [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -m, --method <METHOD>          Hilbert transform: fft, fir [default: fft]
+  -l, --fir-length <FIR_LENGTH>  odd length of FIR Hilbert transformer [default: 101]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: inst_freq.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_xcorr_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("xcorr").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Cross-correlation of two signals or autocorrelation of one
+
+Usage: minidsp xcorr [OPTIONS] --signal1 <SIGNAL1>
+
+Options:
+  -1, --signal1 <SIGNAL1>  first signal
+  -2, --signal2 <SIGNAL2>  second signal [default: autocorrelation of first]
+  -s, --scale <SCALE>      scale: none, biased, unbiased, normalized [default: none]
+  -l, --max-lag <MAX_LAG>  maximum lag in samples
+  -o, --out-csv <OUT_CSV>  fname of csv with lag and correlation [default: xcorr.csv]
+  -h, --help               Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_delay_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("delay").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Delay of second signal relative to first
+
+Usage: minidsp delay [OPTIONS] --signal1 <SIGNAL1> --signal2 <SIGNAL2>
+
+Options:
+  -1, --signal1 <SIGNAL1>      first signal
+  -2, --signal2 <SIGNAL2>      second signal
+  -w, --weighting <WEIGHTING>  weighting of cross spectrum: none, phat, scot [default: phat]
+  -l, --max-lag <MAX_LAG>      maximum lag in samples
+  -h, --help                   Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_shift_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("shift").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Shift signal by fractional delay with zero or circular fill
+
+Usage: minidsp shift [OPTIONS] --signal <SIGNAL> <--delay <DELAY>|--time <TIME>>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -d, --delay <DELAY>            delay in samples, negative advances
+  -t, --time <TIME>              delay in ms, negative advances
+  -m, --method <METHOD>          interpolation: sinc, lagrange, farrow [default: sinc]
+  -f, --fill <FILL>              fill: zero, circular [default: zero]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: shifted_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_effect_comb_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("effect").arg("comb").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Feedforward or feedback comb filter
+
+Usage: minidsp effect comb [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -d, --delay <DELAY>            delay in ms [default: 10]
+  -g, --gain <GAIN>              gain of delayed signal [default: 0.5]
+  -t, --comb-type <COMB_TYPE>    comb type: feedforward, feedback [default: feedback]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: effect_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_effect_echo_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("effect").arg("echo").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Echo with decaying repeats
+
+Usage: minidsp effect echo [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -d, --delay <DELAY>            delay in ms [default: 300]
+  -f, --feedback <FEEDBACK>      decay of each repeat [default: 0.4]
+  -m, --mix <MIX>                level of repeats [default: 0.5]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: effect_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_effect_chorus_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("effect").arg("chorus").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Chorus with several modulated voices
+
+Usage: minidsp effect chorus [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>                signal
+  -d, --delay <DELAY>                  base delay in ms [default: 20]
+      --depth <DEPTH>                  modulation depth in ms [default: 5]
+  -r, --rate <RATE>                    LFO rate in Hz [default: 1.5]
+  -v, --voices <VOICES>                number of voices [default: 3]
+  -m, --mix <MIX>                      share of wet signal [default: 0.5]
+  -i, --interpolation <INTERPOLATION>  fractional delay interpolation: linear, allpass, lagrange [default: linear]
+  -w, --waveform <WAVEFORM>            LFO waveform: sine, triangle [default: sine]
+  -o, --out-signal <OUT_SIGNAL>        fname of output signal [default: effect_signal.wav]
+  -h, --help                           Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_effect_flanger_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("effect").arg("flanger").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Flanger with swept short delay and feedback
+
+Usage: minidsp effect flanger [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>                signal
+  -d, --delay <DELAY>                  minimum delay in ms [default: 2]
+      --depth <DEPTH>                  modulation depth in ms [default: 2]
+  -r, --rate <RATE>                    LFO rate in Hz [default: 0.25]
+  -f, --feedback <FEEDBACK>            feedback gain [default: 0.5]
+  -m, --mix <MIX>                      share of wet signal [default: 0.5]
+  -i, --interpolation <INTERPOLATION>  fractional delay interpolation: linear, allpass, lagrange [default: linear]
+  -w, --waveform <WAVEFORM>            LFO waveform: sine, triangle [default: sine]
+  -o, --out-signal <OUT_SIGNAL>        fname of output signal [default: effect_signal.wav]
+  -h, --help                           Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_effect_phaser_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("effect").arg("phaser").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Phaser with swept allpass stages
+
+Usage: minidsp effect phaser [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -n, --stages <STAGES>          number of allpass stages [default: 4]
+      --min-freq <MIN_FREQ>      minimum break frequency in Hz [default: 300]
+      --max-freq <MAX_FREQ>      maximum break frequency in Hz [default: 1600]
+  -r, --rate <RATE>              LFO rate in Hz [default: 0.5]
+  -f, --feedback <FEEDBACK>      feedback gain [default: 0.5]
+  -m, --mix <MIX>                share of wet signal [default: 0.5]
+  -w, --waveform <WAVEFORM>      LFO waveform: sine, triangle [default: sine]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: effect_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_reverb_freeverb_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("reverb").arg("freeverb").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Freeverb: parallel lowpass-feedback combs and series allpasses
+
+Usage: minidsp reverb freeverb [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -t, --rt60 <RT60>              reverberation time RT60 in s [default: 1]
+  -d, --damping <DAMPING>        damping of high frequencies, from 0 to 1 [default: 0.2]
+  -p, --pre-delay <PRE_DELAY>    pre-delay in ms [default: 20]
+  -m, --mix <MIX>                share of wet signal [default: 0.3]
+      --tail <TAIL>              length of tail appended to signal in s [default: 0]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: reverb_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_reverb_fdn_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("reverb").arg("fdn").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Feedback delay network with Householder matrix
+
+Usage: minidsp reverb fdn [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -t, --rt60 <RT60>              reverberation time RT60 in s [default: 1]
+  -d, --damping <DAMPING>        damping of high frequencies, from 0 to 1 [default: 0.2]
+  -n, --lines <LINES>            number of delay lines, 1 to 8 [default: 8]
+  -p, --pre-delay <PRE_DELAY>    pre-delay in ms [default: 20]
+  -m, --mix <MIX>                share of wet signal [default: 0.3]
+      --tail <TAIL>              length of tail appended to signal in s [default: 0]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: reverb_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_reverb_ir_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("reverb").arg("ir").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Convolution with measured impulse response
+
+Usage: minidsp reverb ir [OPTIONS] --signal <SIGNAL> --ir <IR>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -i, --ir <IR>                  impulse response .wav with the same sample rate
+  -p, --pre-delay <PRE_DELAY>    pre-delay in ms [default: 20]
+  -m, --mix <MIX>                share of wet signal [default: 0.3]
+      --tail <TAIL>              length of tail appended to signal in s [default: 0]
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: reverb_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_eq_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("eq").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Parametric equalizer from TOML, JSON or REW/AutoEQ preset
+
+Usage: minidsp eq [OPTIONS] --signal <SIGNAL> --preset <PRESET>
+
+Options:
+  -s, --signal <SIGNAL>          signal
+  -p, --preset <PRESET>          preset: .toml, .json or REW/AutoEQ text with "Filter 1: ON PK Fc 100 Hz Gain -3 dB Q 1.41" lines
+  -o, --out-signal <OUT_SIGNAL>  fname of output signal [default: eq_signal.wav]
+  -h, --help                     Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_crossover_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("crossover").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Split signal into bands by Linkwitz-Riley or linear-phase FIR crossover
+
+Usage: minidsp crossover [OPTIONS] --signal <SIGNAL> --freqs <FREQS>
+
+Options:
+  -s, --signal <SIGNAL>                  signal
+  -f, --freqs <FREQS>                    increasing crossover frequencies in Hz
+  -t, --crossover-type <CROSSOVER_TYPE>  crossover type: lr, fir [default: lr]
+  -n, --order <ORDER>                    order of Linkwitz-Riley filters: 2, 4, 8 [default: 4]
+  -l, --length <LENGTH>                  odd length of FIR filters [default: 255]
+  -o, --out-prefix <OUT_PREFIX>          prefix of output files <prefix>_N.wav [default: band]
+  -h, --help                             Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_design_remez_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("design").arg("remez").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Equiripple FIR filter by Parks-McClellan (Remez) algorithm
+
+Usage: minidsp design remez [OPTIONS] --length <LENGTH> --bands <BANDS> --desired <DESIRED>
+
+Options:
+  -l, --length <LENGTH>            length of filter
+  -b, --bands <BANDS>              band edges in Hz, two for each band
+  -d, --desired <DESIRED>          desired gain for each band or each band edge
+  -w, --weight <WEIGHT>            weight for each band [default: 1 for each band]
+  -t, --filter-type <FILTER_TYPE>  type of filter: bandpass, differentiator, hilbert [default: bandpass]
+      --fs <FS>                    sample rate in Hz [default: 10000]
+  -o, --out-taps <OUT_TAPS>        fname of taps, format by extension: .csv, .json, .h [default: remez_taps.csv]
+  -h, --help                       Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_view_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("view").arg("-h");
+    cmd.assert().success().stdout(
+        r#"Draw waveform or spectrum in terminal
+
+Usage: minidsp view [OPTIONS] --signal <SIGNAL>
+
+Options:
+  -s, --signal <SIGNAL>    signal
+  -m, --mode <MODE>        what to draw: wave, spectrum [default: wave]
+      --start <START>      start of time range in seconds [default: 0]
+      --end <END>          end of time range in seconds [default: end of signal]
+  -w, --width <WIDTH>      width in characters [default: 80]
+      --height <HEIGHT>    height in characters [default: 20]
+  -c, --charset <CHARSET>  characters for drawing: braille, block, ascii [default: braille]
+  -h, --help               Print help
+"#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_gen_sine() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen").arg("sine");
+    cmd.assert().success().stdout("Generate sinus\n");
+
+    fs::remove_file("sine_50hz.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_gen_noise() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen").arg("noise");
+    cmd.assert().success().stdout("Genearate noise!!\n");
+
+    fs::remove_file("noise_1mu_1std.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_gen_linear_sweep() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen").arg("sweep");
+    cmd.assert().success().stdout("Genearate sweep!!\n");
+
+    fs::remove_file("sweep_1_1_linear.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_gen_quadratic_sweep() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen").arg("sweep").arg("-m").arg("quadratic");
+    cmd.assert().success().stdout("Genearate sweep!!\n");
+
+    fs::remove_file("sweep_1_1_quadratic.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_gen_hyperbolic_sweep() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen").arg("sweep").arg("-m").arg("hyperbolic");
+    cmd.assert().success().stdout("Genearate sweep!!\n");
+
+    fs::remove_file("sweep_1_1_hyperbolic.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dsp_add() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-d")
+        .arg("3")
+        .arg("-o")
+        .arg("sine1.wav");
+
+    cmd.assert().success().stdout("Generate sinus\n");
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-d")
+        .arg("2")
+        .arg("-p")
+        .arg("180")
+        .arg("-o")
+        .arg("sine2.wav");
+
+    cmd2.assert().success().stdout("Generate sinus\n");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("add")
+        .arg("-1")
+        .arg("sine1.wav")
+        .arg("-2")
+        .arg("sine2.wav")
+        .arg("-o")
+        .arg("sine3.wav");
+
+    cmd3.assert().success();
+
+    let mut reader = hound::WavReader::open("sine3.wav")?;
+
+    for sample in reader.samples::<f32>().flatten() {
+        assert!(sample.abs() < 1e-6, "Summ not equal zero, it's {sample}");
+    }
+
+    fs::remove_file("sine1.wav").ok();
+    fs::remove_file("sine2.wav").ok();
+    fs::remove_file("sine3.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dsp_sub() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-d")
+        .arg("3")
+        .arg("-o")
+        .arg("sine1.wav");
+
+    cmd.assert().success().stdout("Generate sinus\n");
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-d")
+        .arg("2")
+        .arg("-o")
+        .arg("sine2.wav");
+
+    cmd2.assert().success().stdout("Generate sinus\n");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("sub")
+        .arg("-1")
+        .arg("sine1.wav")
+        .arg("-2")
+        .arg("sine2.wav")
+        .arg("-o")
+        .arg("sine3.wav");
+
+    cmd3.assert().success();
+
+    let mut reader = hound::WavReader::open("sine3.wav")?;
+
+    for sample in reader.samples::<f32>().flatten() {
+        assert!(sample.abs() < 1e-6, "Sub not equal zero, it's {sample}");
+    }
+
+    fs::remove_file("sine1.wav").ok();
+    fs::remove_file("sine2.wav").ok();
+    fs::remove_file("sine3.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dsp_mux() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-d")
+        .arg("3")
+        .arg("-o")
+        .arg("sine1.wav");
+
+    cmd.assert().success().stdout("Generate sinus\n");
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-d")
+        .arg("2")
+        .arg("-a")
+        .arg("10")
+        .arg("-o")
+        .arg("sine2.wav");
+
+    cmd2.assert().success().stdout("Generate sinus\n");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("mux")
+        .arg("-1")
+        .arg("sine1.wav")
+        .arg("-2")
+        .arg("sine2.wav")
+        .arg("-o")
+        .arg("sine3.wav");
+
+    cmd3.assert().success();
+
+    let mut reader = hound::WavReader::open("sine3.wav")?;
+    let mut max_val = 0.0;
+    for sample_real in reader.samples::<f32>().flatten() {
+        if sample_real > max_val {
+            max_val = sample_real;
+        }
+    }
+
+    assert!(
+        (max_val - 10.).abs() < 1e-6,
+        "Amplitede should be around 10, but it's {max_val}"
+    );
+
+    fs::remove_file("sine1.wav").ok();
+    fs::remove_file("sine2.wav").ok();
+    fs::remove_file("sine3.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dsp_scale() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-d")
+        .arg("3")
+        .arg("-o")
+        .arg("sine1.wav");
+
+    cmd.assert().success().stdout("Generate sinus\n");
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("scale")
+        .arg("-s")
+        .arg("sine1.wav")
+        .arg("-a")
+        .arg("10")
+        .arg("-o")
+        .arg("sine2.wav");
+
+    cmd2.assert().success();
+
+    let mut reader = hound::WavReader::open("sine2.wav")?;
+    let mut max_val = 0.0;
+    for sample_real in reader.samples::<f32>().flatten() {
+        if sample_real > max_val {
+            max_val = sample_real;
+        }
+    }
+
+    assert!(
+        (max_val - 10.).abs() < 1e-6,
+        "Amplitede should be around 10, but it's {max_val}"
+    );
+
+    fs::remove_file("sine1.wav").ok();
+    fs::remove_file("sine2.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dsp_move_average() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("noise")
+        .arg("-d")
+        .arg("3")
+        .arg("-o")
+        .arg("noise.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("mov-average")
+        .arg("-s")
+        .arg("noise.wav")
+        .arg("-k")
+        .arg("13")
+        .arg("-o")
+        .arg("noise_meaned.wav");
+
+    cmd2.assert().success();
+
+    let mut reader = hound::WavReader::open("noise_meaned.wav")?;
+    let mut sig: Vec<f64> = Vec::new();
+
+    for sample_real in reader.samples::<f32>().flatten() {
+        if sample_real.is_nan() {
+            panic!("{sample_real}")
+        }
+        sig.push(sample_real as f64);
+    }
+
+    let n = sig.len() as f64;
+    let mean = sig.iter().sum::<f64>() / n;
+    let variance = sig.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    let std = variance.sqrt();
+
+    assert!(
+        (mean - 1.).abs() < 0.5,
+        "Mean should be around 1, but it's {mean}"
+    );
+
+    assert!(std < 0.5, "Std should be around 1, but it's {std}");
+
+    // fs::remove_file("noise.wav").ok();
+    // fs::remove_file("noise_meaned.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_view() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("30")
+        .arg("-o")
+        .arg("sine_view.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("view")
+        .arg("-s")
+        .arg("sine_view.wav")
+        .arg("--start")
+        .arg("0.1")
+        .arg("--end")
+        .arg("0.2")
+        .arg("-w")
+        .arg("30")
+        .arg("--height")
+        .arg("5");
+
+    let output = cmd2.assert().success().get_output().stdout.clone();
+    let text = String::from_utf8(output)?;
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].starts_with("amplitude:"));
+    assert!(lines[1..].iter().all(|l| l.chars().count() == 30));
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("view")
+        .arg("-s")
+        .arg("sine_view.wav")
+        .arg("-m")
+        .arg("spectrum")
+        .arg("-c")
+        .arg("ascii");
+
+    cmd3.assert()
+        .success()
+        .stdout(predicates::str::starts_with("frequency: 0 .. 5000 Hz"));
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("view")
+        .arg("-s")
+        .arg("sine_view.wav")
+        .arg("--height")
+        .arg("0");
+
+    cmd4.assert()
+        .success()
+        .stdout("Width and height must be positive\n");
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("view")
+        .arg("-s")
+        .arg("sine_view.wav")
+        .arg("-m")
+        .arg("spectrum")
+        .arg("--start")
+        .arg("0.5")
+        .arg("--end")
+        .arg("0.2");
+
+    cmd5.assert().success().stdout("Empty time range\n");
+
+    fs::remove_file("sine_view.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_fir() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("3000")
+        .arg("-o")
+        .arg("sine_fir.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("filter")
+        .arg("fir")
+        .arg("-s")
+        .arg("sine_fir.wav")
+        .arg("-t")
+        .arg("highpass")
+        .arg("-c")
+        .arg("1000")
+        .arg("--transition")
+        .arg("100")
+        .arg("--taps-out")
+        .arg("fir_taps.csv")
+        .arg("-o")
+        .arg("sine_fir_out.wav");
+
+    cmd2.assert().success().stdout("Filter with 365 taps\n");
+
+    let taps = fs::read_to_string("fir_taps.csv")?;
+    assert_eq!(taps.lines().count(), 365);
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("filter")
+        .arg("fir")
+        .arg("-s")
+        .arg("sine_fir.wav")
+        .arg("-c")
+        .arg("1000")
+        .arg("-l")
+        .arg("101")
+        .arg("-o")
+        .arg("sine_fir_out.wav");
+
+    cmd3.assert().success().stdout("Filter with 101 taps\n");
+
+    // Тон 3 кГц должен быть подавлен ФНЧ 1 кГц
+    let mut reader = hound::WavReader::open("sine_fir_out.wav")?;
+    for sample in reader.samples::<f32>().flatten().skip(101) {
+        assert!(sample.abs() < 0.01, "Tone is not suppressed: {sample}");
+    }
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("filter")
+        .arg("fir")
+        .arg("-s")
+        .arg("sine_fir.wav")
+        .arg("-t")
+        .arg("highpass")
+        .arg("-c")
+        .arg("1000")
+        .arg("-l")
+        .arg("100");
+
+    cmd4.assert()
+        .success()
+        .stdout("Error in filter design: Highpass filter must have odd length, got 100\n");
+
+    fs::remove_file("sine_fir.wav").ok();
+    fs::remove_file("sine_fir_out.wav").ok();
+    fs::remove_file("fir_taps.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_keeps_rate() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    // Тон 8 кГц при 48 кГц, выше среза ФНЧ 1 кГц
+    write_tone("sine_48k.wav", 8000.0, 48000)?;
+
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("filter")
+        .arg("fir")
+        .arg("-s")
+        .arg("sine_48k.wav")
+        .arg("-c")
+        .arg("1000")
+        .arg("-l")
+        .arg("101")
+        .arg("-o")
+        .arg("sine_48k_out.wav");
+
+    cmd.assert().success().stdout("Filter with 101 taps\n");
+    assert_eq!(wave_rate("sine_48k_out.wav")?, 48000);
+    let mut reader = hound::WavReader::open("sine_48k_out.wav")?;
+    for sample in reader.samples::<f32>().flatten().skip(101) {
+        assert!(sample.abs() < 0.01, "Tone is not suppressed: {sample}");
+    }
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("filter")
+        .arg("biquad")
+        .arg("-s")
+        .arg("sine_48k.wav")
+        .arg("-t")
+        .arg("lowpass")
+        .arg("-f")
+        .arg("1000")
+        .arg("-o")
+        .arg("sine_48k_out.wav");
+
+    cmd2.assert().success();
+    assert_eq!(wave_rate("sine_48k_out.wav")?, 48000);
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("filter")
+        .arg("iir")
+        .arg("-s")
+        .arg("sine_48k.wav")
+        .arg("-c")
+        .arg("1000")
+        .arg("-o")
+        .arg("sine_48k_out.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Filter with 2 second-order sections\n");
+    assert_eq!(wave_rate("sine_48k_out.wav")?, 48000);
+
+    fs::write("taps_48k.csv", "0.25\n0.5\n0.25\n")?;
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("filter")
+        .arg("filtfilt")
+        .arg("-s")
+        .arg("sine_48k.wav")
+        .arg("--taps")
+        .arg("taps_48k.csv")
+        .arg("-o")
+        .arg("sine_48k_out.wav");
+
+    cmd4.assert()
+        .success()
+        .stdout("Filter forward and backward\n");
+    assert_eq!(wave_rate("sine_48k_out.wav")?, 48000);
+
+    fs::remove_file("sine_48k.wav").ok();
+    fs::remove_file("sine_48k_out.wav").ok();
+    fs::remove_file("taps_48k.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_design_remez() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("design")
+        .arg("remez")
+        .arg("-l")
+        .arg("31")
+        .arg("-b")
+        .arg("0,1000,1500,5000")
+        .arg("-d")
+        .arg("1,0")
+        .arg("-o")
+        .arg("remez_lowpass.json");
+
+    cmd.assert()
+        .success()
+        .stdout("Design filter with 31 taps\n");
+
+    let text = fs::read_to_string("remez_lowpass.json")?;
+    assert!(text.starts_with("{\"taps\": ["));
+    assert_eq!(text.matches(',').count(), 30);
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("design")
+        .arg("remez")
+        .arg("-l")
+        .arg("31")
+        .arg("-b")
+        .arg("500,4500")
+        .arg("-d")
+        .arg("1")
+        .arg("-t")
+        .arg("hilbert")
+        .arg("-o")
+        .arg("remez_hilbert.h");
+
+    cmd2.assert()
+        .success()
+        .stdout("Design filter with 31 taps\n");
+
+    let text = fs::read_to_string("remez_hilbert.h")?;
+    assert!(text.contains("static const double remez_hilbert[REMEZ_HILBERT_LENGTH] = {"));
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("design")
+        .arg("remez")
+        .arg("-l")
+        .arg("31")
+        .arg("-b")
+        .arg("0,1000,1500")
+        .arg("-d")
+        .arg("1");
+
+    cmd3.assert()
+        .success()
+        .stdout("Error in filter design: Band edges must be given in pairs\n");
+
+    fs::remove_file("remez_lowpass.json").ok();
+    fs::remove_file("remez_hilbert.h").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_biquad() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("1000")
+        .arg("-o")
+        .arg("sine_biquad.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("filter")
+        .arg("biquad")
+        .arg("-s")
+        .arg("sine_biquad.wav")
+        .arg("--type")
+        .arg("peaking")
+        .arg("--freq")
+        .arg("1000")
+        .arg("--q")
+        .arg("2")
+        .arg("--gain")
+        .arg("6")
+        .arg("-o")
+        .arg("sine_biquad_out.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout(predicates::str::starts_with("Filter with peaking biquad"));
+
+    // После переходного процесса амплитуда тона вырастает на 6 дБ
+    let mut reader = hound::WavReader::open("sine_biquad_out.wav")?;
+    let tail: Vec<f32> = reader.samples::<f32>().flatten().skip(5000).collect();
+    let rms = (tail.iter().map(|x| x * x).sum::<f32>() / tail.len() as f32).sqrt();
+    let amplitude = rms * 2.0_f32.sqrt();
+    let expected = 10.0_f32.powf(6.0 / 20.0);
+
+    assert!(
+        (amplitude - expected).abs() < 1e-2,
+        "Amplitude should be around {expected}, but it's {amplitude}"
+    );
+
+    fs::remove_file("sine_biquad.wav").ok();
+    fs::remove_file("sine_biquad_out.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_iir() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("3000")
+        .arg("-o")
+        .arg("sine_iir.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("filter")
+        .arg("iir")
+        .arg("-s")
+        .arg("sine_iir.wav")
+        .arg("--family")
+        .arg("ellip")
+        .arg("-c")
+        .arg("1000")
+        .arg("--order")
+        .arg("6")
+        .arg("--sos-out")
+        .arg("iir_sos.csv")
+        .arg("-o")
+        .arg("sine_iir_out.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Filter with 3 second-order sections\n");
+
+    // Тон 3 кГц в полосе задерживания ослабляется минимум на 60 дБ
+    let mut reader = hound::WavReader::open("sine_iir_out.wav")?;
+    let tail: Vec<f32> = reader.samples::<f32>().flatten().skip(5000).collect();
+    let max = tail.iter().fold(0.0_f32, |m, x| m.max(x.abs()));
+    assert!(max < 1e-3, "Tone should be suppressed, but max is {max}");
+
+    let sos = fs::read_to_string("iir_sos.csv")?;
+    let rows: Vec<Vec<f64>> = sos
+        .lines()
+        .map(|l| l.split(',').map(|v| v.parse().unwrap()).collect())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows.iter().all(|r| r.len() == 6 && r[3] == 1.0));
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("filter")
+        .arg("iir")
+        .arg("-s")
+        .arg("sine_iir.wav")
+        .arg("-t")
+        .arg("bandpass")
+        .arg("-c")
+        .arg("1000");
+
+    cmd3.assert()
+        .success()
+        .stdout("Error in filter design: Bandpass filter needs 2 cutoff frequencies, got 1\n");
+
+    fs::remove_file("sine_iir.wav").ok();
+    fs::remove_file("sine_iir_out.wav").ok();
+    fs::remove_file("iir_sos.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_filtfilt() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("100")
+        .arg("-o")
+        .arg("sine_filtfilt.wav");
+
+    cmd.assert().success();
+
+    // Коэффициенты берём из файла, сохранённого filter fir
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("filter")
+        .arg("fir")
+        .arg("-s")
+        .arg("sine_filtfilt.wav")
+        .arg("-c")
+        .arg("1000")
+        .arg("-l")
+        .arg("51")
+        .arg("--taps-out")
+        .arg("filtfilt_taps.json")
+        .arg("-o")
+        .arg("sine_filtfilt_fir.wav");
+
+    cmd2.assert().success();
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("filter")
+        .arg("filtfilt")
+        .arg("-s")
+        .arg("sine_filtfilt.wav")
+        .arg("--taps")
+        .arg("filtfilt_taps.json")
+        .arg("-o")
+        .arg("sine_filtfilt_out.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Filter forward and backward\n");
+
+    // Фаза не сдвигается: выход совпадает со входом и на краях
+    let mut reader = hound::WavReader::open("sine_filtfilt.wav")?;
+    let input: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    let mut reader = hound::WavReader::open("sine_filtfilt_out.wav")?;
+    let output: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    assert_eq!(input.len(), output.len());
+    let max_diff = input
+        .iter()
+        .zip(output.iter())
+        .fold(0.0_f32, |m, (x, y)| m.max((x - y).abs()));
+    assert!(
+        max_diff < 1e-2,
+        "Output should match input, diff is {max_diff}"
+    );
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("filter")
+        .arg("filtfilt")
+        .arg("-s")
+        .arg("sine_filtfilt.wav")
+        .arg("--sos")
+        .arg("missing_sos.csv");
+
+    cmd4.assert().success().stdout(predicates::str::starts_with(
+        "Error in filtering: Fail to read missing_sos.csv",
+    ));
+
+    fs::remove_file("sine_filtfilt.wav").ok();
+    fs::remove_file("sine_filtfilt_fir.wav").ok();
+    fs::remove_file("sine_filtfilt_out.wav").ok();
+    fs::remove_file("filtfilt_taps.json").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_analyze_filter() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("analyze-filter")
+        .arg("--mov-average")
+        .arg("4")
+        .arg("-n")
+        .arg("5")
+        .arg("-o")
+        .arg("ma_response.csv")
+        .arg("--pz-out")
+        .arg("ma_pz.csv");
+
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "Zeros: 3, poles: 3, max pole radius: 0.000000\nFilter is stable\nmagnitude, dB:",
+        ))
+        .stdout(predicates::str::contains("group delay, samples:"))
+        .stdout(predicates::str::ends_with(
+            "Save response to ma_response.csv\n",
+        ));
+
+    // Окно длины 4 обнуляет fs/4, задержка полторы выборки
+    let text = fs::read_to_string("ma_response.csv")?;
+    let rows: Vec<Vec<f64>> = text
+        .lines()
+        .skip(1)
+        .map(|l| l.split(',').map(|v| v.parse().unwrap()).collect())
+        .collect();
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[0][1], 0.0);
+    assert_eq!(rows[2][0], 2500.0);
+    assert!(rows[2][1] < -100.0);
+    assert!((rows[1][3] - 1.5).abs() < 1e-9);
+
+    let pz = fs::read_to_string("ma_pz.csv")?;
+    assert_eq!(pz.lines().filter(|l| l.starts_with("zero")).count(), 3);
+    assert_eq!(pz.lines().filter(|l| l.starts_with("pole")).count(), 3);
+
+    // Неустойчивый фильтр с полюсом вне единичной окружности
+    fs::write("unstable_b.csv", "1\n")?;
+    fs::write("unstable_a.csv", "1\n-1.5\n")?;
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("analyze-filter")
+        .arg("-b")
+        .arg("unstable_b.csv")
+        .arg("-a")
+        .arg("unstable_a.csv")
+        .arg("-o")
+        .arg("ma_response.csv");
+
+    cmd2.assert()
+        .success()
+        .stdout(predicates::str::contains("Filter is unstable"));
+
+    fs::remove_file("ma_response.csv").ok();
+    fs::remove_file("ma_pz.csv").ok();
+    fs::remove_file("unstable_b.csv").ok();
+    fs::remove_file("unstable_a.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_convolve() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("50")
+        .arg("-o")
+        .arg("sine_convolve.wav");
+
+    cmd.assert().success();
+
+    // Ядро из файла коэффициентов: задержка на два отсчёта
+    fs::write("delay_kernel.csv", "0\n0\n1\n")?;
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("convolve")
+        .arg("-s")
+        .arg("sine_convolve.wav")
+        .arg("-k")
+        .arg("delay_kernel.csv")
+        .arg("-o")
+        .arg("sine_convolve_out.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Convolve by Direct method, 10002 samples\n");
+
+    let mut reader = hound::WavReader::open("sine_convolve.wav")?;
+    let input: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    let mut reader = hound::WavReader::open("sine_convolve_out.wav")?;
+    let output: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    assert_eq!(&output[2..], &input[..]);
+
+    // Длинное ядро из wav считается через БПФ
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("convolve")
+        .arg("-s")
+        .arg("sine_convolve.wav")
+        .arg("-k")
+        .arg("sine_convolve.wav")
+        .arg("-m")
+        .arg("same")
+        .arg("-o")
+        .arg("sine_convolve_out.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Convolve by OverlapAdd method, 10000 samples\n");
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("convolve")
+        .arg("-s")
+        .arg("sine_convolve.wav")
+        .arg("-k")
+        .arg("delay_kernel.csv")
+        .arg("-m")
+        .arg("circular");
+
+    cmd4.assert()
+        .success()
+        .stdout("Unknown convolution mode circular, expected full, same or valid\n");
+
+    // Частота дискретизации входа переходит в результат
+    write_tone("sine_convolve_44k.wav", 1000.0, 44100)?;
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("convolve")
+        .arg("-s")
+        .arg("sine_convolve_44k.wav")
+        .arg("-k")
+        .arg("delay_kernel.csv")
+        .arg("-o")
+        .arg("sine_convolve_out.wav");
+
+    cmd5.assert().success();
+    assert_eq!(wave_rate("sine_convolve_out.wav")?, 44100);
+
+    fs::remove_file("sine_convolve.wav").ok();
+    fs::remove_file("sine_convolve_44k.wav").ok();
+    fs::remove_file("sine_convolve_out.wav").ok();
+    fs::remove_file("delay_kernel.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_xcorr_and_delay() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("noise")
+        .arg("-d")
+        .arg("0.5")
+        .arg("-o")
+        .arg("noise_delay.wav");
+
+    cmd.assert().success();
+
+    // Копия шума, задержанная на 12 отсчётов
+    fs::write("delay_12.csv", format!("{}1\n", "0\n".repeat(12)))?;
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("convolve")
+        .arg("-s")
+        .arg("noise_delay.wav")
+        .arg("-k")
+        .arg("delay_12.csv")
+        .arg("-o")
+        .arg("noise_delayed.wav");
+
+    cmd2.assert().success();
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("delay")
+        .arg("-1")
+        .arg("noise_delay.wav")
+        .arg("-2")
+        .arg("noise_delayed.wav");
+
+    cmd3.assert().success().stdout(
+        "Delay of noise_delayed.wav relative to noise_delay.wav: 12.000 samples, 1.200 ms\n",
+    );
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("xcorr")
+        .arg("-1")
+        .arg("noise_delayed.wav")
+        .arg("-2")
+        .arg("noise_delay.wav")
+        .arg("-s")
+        .arg("normalized")
+        .arg("-l")
+        .arg("20")
+        .arg("-o")
+        .arg("noise_xcorr.csv");
+
+    // Задержанная копия содержит весь шум, поэтому пик ровно 1
+    cmd4.assert()
+        .success()
+        .stdout("Peak at lag 12 samples: 1.000000\nSave correlation to noise_xcorr.csv\n");
+
+    let text = fs::read_to_string("noise_xcorr.csv")?;
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 42);
+    assert_eq!(lines[0], "lag,value");
+    assert!(lines[1].starts_with("-20,"));
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("delay")
+        .arg("-1")
+        .arg("noise_delay.wav")
+        .arg("-2")
+        .arg("noise_delayed.wav")
+        .arg("-w")
+        .arg("roth");
+
+    cmd5.assert()
+        .success()
+        .stdout("Unknown weighting roth, expected none, phat or scot\n");
+
+    fs::remove_file("noise_delay.wav").ok();
+    fs::remove_file("noise_delayed.wav").ok();
+    fs::remove_file("delay_12.csv").ok();
+    fs::remove_file("noise_xcorr.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dsp_sub_aligned() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("noise")
+        .arg("-d")
+        .arg("0.5")
+        .arg("-o")
+        .arg("noise_align.wav");
+
+    cmd.assert().success();
+
+    fs::write("delay_align.csv", format!("{}1\n", "0\n".repeat(30)))?;
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("convolve")
+        .arg("-s")
+        .arg("noise_align.wav")
+        .arg("-k")
+        .arg("delay_align.csv")
+        .arg("-o")
+        .arg("noise_late.wav");
+
+    cmd2.assert().success();
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("sub")
+        .arg("-1")
+        .arg("noise_align.wav")
+        .arg("-2")
+        .arg("noise_late.wav")
+        .arg("-a")
+        .arg("integer")
+        .arg("-l")
+        .arg("first")
+        .arg("-o")
+        .arg("noise_align_sub.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Align noise_late.wav by 30.000 samples\n");
+
+    let mut reader = hound::WavReader::open("noise_align_sub.wav")?;
+    let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    assert_eq!(samples.len(), 5000);
+    for sample in samples {
+        assert!(
+            sample.abs() < 1e-6,
+            "Difference not equal zero, it's {sample}"
+        );
+    }
+
+    // Без выравнивания длина по более длинному сигналу, хвост дополнен нулями
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("add")
+        .arg("-1")
+        .arg("noise_align.wav")
+        .arg("-2")
+        .arg("noise_late.wav")
+        .arg("-l")
+        .arg("longest")
+        .arg("-o")
+        .arg("noise_align_sub.wav");
+
+    cmd4.assert().success().stdout("");
+
+    let reader = hound::WavReader::open("noise_align_sub.wav")?;
+    assert_eq!(reader.len(), 5030);
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("mux")
+        .arg("-1")
+        .arg("noise_align.wav")
+        .arg("-2")
+        .arg("noise_late.wav")
+        .arg("-l")
+        .arg("second");
+
+    cmd5.assert()
+        .success()
+        .stdout("Unknown length policy second, expected shortest, longest or first\n");
+
+    fs::remove_file("noise_align.wav").ok();
+    fs::remove_file("noise_late.wav").ok();
+    fs::remove_file("delay_align.csv").ok();
+    fs::remove_file("noise_align_sub.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dsp_pair_rates() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    write_tone("sine_pair_1.wav", 1000.0, 48000)?;
+    write_tone("sine_pair_2.wav", 3000.0, 48000)?;
+    write_tone("sine_pair_44k.wav", 1000.0, 44100)?;
+
+    for command in ["add", "sub", "mux"] {
+        let mut cmd = Command::cargo_bin("minidsp")?;
+        cmd.arg(command)
+            .arg("-1")
+            .arg("sine_pair_1.wav")
+            .arg("-2")
+            .arg("sine_pair_2.wav")
+            .arg("-o")
+            .arg("sine_pair_out.wav");
+
+        cmd.assert().success();
+        assert_eq!(wave_rate("sine_pair_out.wav")?, 48000, "{command}");
+
+        let mut cmd2 = Command::cargo_bin("minidsp")?;
+        cmd2.arg(command)
+            .arg("-1")
+            .arg("sine_pair_1.wav")
+            .arg("-2")
+            .arg("sine_pair_44k.wav");
+
+        cmd2.assert().success().stdout(
+            "Sample rate of sine_pair_44k.wav 44100 Hz differs from sine_pair_1.wav 48000 Hz\n",
+        );
+    }
+
+    fs::remove_file("sine_pair_1.wav").ok();
+    fs::remove_file("sine_pair_2.wav").ok();
+    fs::remove_file("sine_pair_44k.wav").ok();
+    fs::remove_file("sine_pair_out.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_resample() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("440")
+        .arg("-o")
+        .arg("sine_resample.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("resample")
+        .arg("-s")
+        .arg("sine_resample.wav")
+        .arg("--to")
+        .arg("48000")
+        .arg("-o")
+        .arg("sine_48k.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Resample 10000 Hz to 48000 Hz by polyphase filter, up 24, down 5\n");
+
+    let reader = hound::WavReader::open("sine_48k.wav")?;
+    assert_eq!(reader.spec().sample_rate, 48000);
+    assert_eq!(reader.len(), 48000);
+
+    // Обратно к исходной частоте через произвольный коэффициент
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("resample")
+        .arg("-s")
+        .arg("sine_48k.wav")
+        .arg("--to")
+        .arg("10000")
+        .arg("-m")
+        .arg("arbitrary")
+        .arg("-q")
+        .arg("high")
+        .arg("-o")
+        .arg("sine_resample_back.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Resample 48000 Hz to 10000 Hz with arbitrary ratio 0.208333\n");
+
+    let mut reader = hound::WavReader::open("sine_resample.wav")?;
+    let input: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    let mut reader = hound::WavReader::open("sine_resample_back.wav")?;
+    assert_eq!(reader.spec().sample_rate, 10000);
+    let output: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    assert_eq!(output.len(), input.len());
+    for (o, i) in output[500..9500].iter().zip(input[500..9500].iter()) {
+        assert!((o - i).abs() < 1e-3, "{o} {i}");
+    }
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("resample")
+        .arg("-s")
+        .arg("sine_resample.wav")
+        .arg("--to")
+        .arg("48000")
+        .arg("-q")
+        .arg("best");
+
+    cmd4.assert()
+        .success()
+        .stdout("Unknown quality best, expected low, medium or high\n");
+
+    fs::remove_file("sine_resample.wav").ok();
+    fs::remove_file("sine_48k.wav").ok();
+    fs::remove_file("sine_resample_back.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_decimate_and_interpolate() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("100")
+        .arg("-o")
+        .arg("sine_decimate.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("decimate")
+        .arg("-s")
+        .arg("sine_decimate.wav")
+        .arg("-f")
+        .arg("20")
+        .arg("-z")
+        .arg("-o")
+        .arg("sine_500.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Decimate 10000 Hz to 500 Hz in stages 10, 2\n");
+
+    let reader = hound::WavReader::open("sine_500.wav")?;
+    assert_eq!(reader.spec().sample_rate, 500);
+    assert_eq!(reader.len(), 500);
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("interpolate")
+        .arg("-s")
+        .arg("sine_500.wav")
+        .arg("-f")
+        .arg("20")
+        .arg("--filter")
+        .arg("iir")
+        .arg("-z")
+        .arg("-o")
+        .arg("sine_decimate_back.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Interpolate 500 Hz to 10000 Hz in stages 2, 10\n");
+
+    let mut reader = hound::WavReader::open("sine_decimate.wav")?;
+    let input: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    let mut reader = hound::WavReader::open("sine_decimate_back.wav")?;
+    assert_eq!(reader.spec().sample_rate, 10000);
+    let output: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    assert_eq!(output.len(), input.len());
+    for (o, i) in output[1000..9000].iter().zip(input[1000..9000].iter()) {
+        assert!((o - i).abs() < 3e-2, "{o} {i}");
+    }
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("decimate")
+        .arg("-s")
+        .arg("sine_decimate.wav")
+        .arg("-f")
+        .arg("3");
+
+    cmd4.assert()
+        .success()
+        .stdout("Sample rate 10000 Hz is not divisible by 3\n");
+
+    for command in ["decimate", "interpolate"] {
+        let mut cmd5 = Command::cargo_bin("minidsp")?;
+        cmd5.arg(command)
+            .arg("-s")
+            .arg("sine_decimate.wav")
+            .arg("-f")
+            .arg("0");
+
+        cmd5.assert()
+            .success()
+            .stdout("Error in resampling: Factor must be positive\n");
+
+        // Множитель 1 копирует сигнал без изменений
+        let mut cmd6 = Command::cargo_bin("minidsp")?;
+        cmd6.arg(command)
+            .arg("-s")
+            .arg("sine_decimate.wav")
+            .arg("-f")
+            .arg("1")
+            .arg("-o")
+            .arg("sine_decimate_back.wav");
+
+        cmd6.assert()
+            .success()
+            .stdout("Factor 1 keeps 10000 Hz, copy signal to sine_decimate_back.wav\n");
+        let input: Vec<f32> = hound::WavReader::open("sine_decimate.wav")?
+            .samples::<f32>()
+            .flatten()
+            .collect();
+        let output: Vec<f32> = hound::WavReader::open("sine_decimate_back.wav")?
+            .samples::<f32>()
+            .flatten()
+            .collect();
+        assert_eq!(output, input);
+        assert_eq!(wave_rate("sine_decimate_back.wav")?, 10000);
+    }
+
+    fs::remove_file("sine_decimate.wav").ok();
+    fs::remove_file("sine_500.wav").ok();
+    fs::remove_file("sine_decimate_back.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_cic() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("100")
+        .arg("-o")
+        .arg("sine_cic.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("cic")
+        .arg("-s")
+        .arg("sine_cic.wav")
+        .arg("-r")
+        .arg("10")
+        .arg("-o")
+        .arg("sine_cic_out.wav");
+
+    cmd2.assert().success().stdout(
+        r#"CIC decimator: 4 stages, delay 1, rate 10, 10000 Hz to 1000 Hz
+Gain 10000, bit growth 14 bits, register width 30 bits for 16-bit input
+"#,
+    );
+
+    let peak = |fname: &str| -> Result<f32, Box<dyn std::error::Error>> {
+        let mut reader = hound::WavReader::open(fname)?;
+        assert_eq!(reader.spec().sample_rate, 1000);
+        let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+        assert_eq!(samples.len(), 1000);
+        Ok(samples[100..].iter().fold(0.0, |m, v| m.max(v.abs())))
+    };
+    // Спад АЧХ CIC на 100 Гц в 0.9367 раза, пик между отсчётами
+    let droop = peak("sine_cic_out.wav")?;
+    assert!(droop < 0.94, "{droop}");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("cic")
+        .arg("-s")
+        .arg("sine_cic.wav")
+        .arg("-r")
+        .arg("10")
+        .arg("-c")
+        .arg("21")
+        .arg("--taps-out")
+        .arg("cic_taps.csv")
+        .arg("-o")
+        .arg("sine_cic_out.wav");
+
+    cmd3.assert().success().stdout(
+        r#"CIC decimator: 4 stages, delay 1, rate 10, 10000 Hz to 1000 Hz
+Gain 10000, bit growth 14 bits, register width 30 bits for 16-bit input
+Droop compensation with 21 taps
+"#,
+    );
+
+    // Задержка корректора на целый период тона, отсчёты попадают в те же фазы
+    let compensated = peak("sine_cic_out.wav")?;
+    assert!(
+        (compensated / droop * 0.9367 - 1.0).abs() < 5e-3,
+        "{compensated}"
+    );
+    assert_eq!(fs::read_to_string("cic_taps.csv")?.lines().count(), 21);
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("cic")
+        .arg("-s")
+        .arg("sine_cic.wav")
+        .arg("-r")
+        .arg("1000")
+        .arg("-n")
+        .arg("6")
+        .arg("-b")
+        .arg("24");
+
+    cmd4.assert()
+        .success()
+        .stdout("Register width 84 bits exceeds 64 bits\n");
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("cic")
+        .arg("-s")
+        .arg("sine_cic.wav")
+        .arg("-m")
+        .arg("interpolate")
+        .arg("-r")
+        .arg("1000000")
+        .arg("-n")
+        .arg("1");
+
+    cmd5.assert()
+        .success()
+        .stdout("Sample rate 10000 Hz multiplied by 1000000 is too high\n");
+
+    fs::remove_file("sine_cic.wav").ok();
+    fs::remove_file("sine_cic_out.wav").ok();
+    fs::remove_file("cic_taps.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_hilbert() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("500")
+        .arg("-o")
+        .arg("sine_hilbert.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("envelope")
+        .arg("-s")
+        .arg("sine_hilbert.wav")
+        .arg("-o")
+        .arg("sine_envelope.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Envelope from 1.000000 to 1.000000\n");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("inst-phase")
+        .arg("-s")
+        .arg("sine_hilbert.wav")
+        .arg("-o")
+        .arg("sine_phase.wav");
+
+    // sin = cos(phi - pi/2), за секунду фаза растёт на 2 pi 500
+    cmd3.assert()
+        .success()
+        .stdout("Phase from -1.571 rad to 3139.708 rad\n");
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("inst-freq")
+        .arg("-s")
+        .arg("sine_hilbert.wav")
+        .arg("-o")
+        .arg("sine_freq.wav");
+
+    cmd4.assert()
+        .success()
+        .stdout("Instantaneous frequency from 500.0 Hz to 500.0 Hz\n");
+
+    let mut reader = hound::WavReader::open("sine_freq.wav")?;
+    assert_eq!(reader.spec().sample_rate, 10000);
+    let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    assert_eq!(samples.len(), 9999);
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("envelope")
+        .arg("-s")
+        .arg("sine_hilbert.wav")
+        .arg("-m")
+        .arg("dft");
+
+    cmd5.assert()
+        .success()
+        .stdout("Unknown Hilbert method dft, expected fft or fir\n");
+
+    let mut cmd6 = Command::cargo_bin("minidsp")?;
+    cmd6.arg("envelope")
+        .arg("-s")
+        .arg("sine_hilbert.wav")
+        .arg("-m")
+        .arg("fir")
+        .arg("-l")
+        .arg("100");
+
+    cmd6.assert()
+        .success()
+        .stdout("Error in filter design: Hilbert transformer must have odd length, got 100\n");
+
+    fs::remove_file("sine_hilbert.wav").ok();
+    fs::remove_file("sine_envelope.wav").ok();
+    fs::remove_file("sine_phase.wav").ok();
+    fs::remove_file("sine_freq.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_smooth() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("100")
+        .arg("-o")
+        .arg("sine_smooth.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("smooth")
+        .arg("median")
+        .arg("-s")
+        .arg("sine_smooth.wav")
+        .arg("-w")
+        .arg("101")
+        .arg("-o")
+        .arg("sine_median.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Save 10000 samples to sine_median.wav\n");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("smooth")
+        .arg("savgol")
+        .arg("-s")
+        .arg("sine_smooth.wav")
+        .arg("-e")
+        .arg("valid")
+        .arg("-o")
+        .arg("sine_savgol.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Save 9990 samples to sine_savgol.wav\n");
+
+    // Период тона 100 отсчётов, окно Савицкого-Голея 11 почти не искажает его
+    let mut reader = hound::WavReader::open("sine_savgol.wav")?;
+    assert_eq!(reader.spec().sample_rate, 10000);
+    let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    let peak = samples.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+    assert!((peak - 1.0).abs() < 1e-2, "{peak}");
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("smooth")
+        .arg("gaussian")
+        .arg("-s")
+        .arg("sine_smooth.wav")
+        .arg("--sigma")
+        .arg("3")
+        .arg("-e")
+        .arg("nearest")
+        .arg("-o")
+        .arg("sine_gaussian.wav");
+
+    cmd4.assert()
+        .success()
+        .stdout("Save 10000 samples to sine_gaussian.wav\n");
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("smooth")
+        .arg("ema")
+        .arg("-s")
+        .arg("sine_smooth.wav")
+        .arg("-e")
+        .arg("wrap");
+
+    cmd5.assert()
+        .success()
+        .stdout("Unknown edge mode wrap, expected valid, reflect, constant or nearest\n");
+
+    let mut cmd6 = Command::cargo_bin("minidsp")?;
+    cmd6.arg("smooth")
+        .arg("median")
+        .arg("-s")
+        .arg("sine_smooth.wav")
+        .arg("-w")
+        .arg("4");
+
+    cmd6.assert()
+        .success()
+        .stdout("Error in smoothing: Window length must be odd, got 4\n");
+
+    fs::remove_file("sine_smooth.wav").ok();
+    fs::remove_file("sine_median.wav").ok();
+    fs::remove_file("sine_savgol.wav").ok();
+    fs::remove_file("sine_gaussian.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_normalize() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("1000")
+        .arg("-a")
+        .arg("0.5")
+        .arg("-o")
+        .arg("sine_normalize.wav");
+
+    cmd.assert().success();
+
+    // Отсчёты тона 1 кГц при 10 кГц не попадают в пик, истинный пик -6.02 dB
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("normalize")
+        .arg("-s")
+        .arg("sine_normalize.wav")
+        .arg("-m")
+        .arg("true-peak")
+        .arg("-t")
+        .arg("-1")
+        .arg("-o")
+        .arg("sine_normalized.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Level -6.01 dBTP, gain +5.01 dB to -1.00 dBTP\n");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("normalize")
+        .arg("-s")
+        .arg("sine_normalize.wav")
+        .arg("-m")
+        .arg("rms")
+        .arg("-t")
+        .arg("-20")
+        .arg("-o")
+        .arg("sine_normalized.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Level -9.03 dBFS, gain -10.97 dB to -20.00 dBFS\n");
+
+    let mut reader = hound::WavReader::open("sine_normalized.wav")?;
+    assert_eq!(reader.spec().sample_rate, 10000);
+    let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    let rms = (samples.iter().map(|x| x * x).sum::<f32>() / samples.len() as f32).sqrt();
+    assert!((20.0 * rms.log10() + 20.0).abs() < 1e-3, "{rms}");
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("normalize")
+        .arg("-s")
+        .arg("sine_normalize.wav")
+        .arg("-o")
+        .arg("sine_normalized.wav");
+
+    cmd4.assert()
+        .success()
+        .stdout("Level -8.99 LUFS, gain -14.01 dB to -23.00 LUFS\n");
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("normalize")
+        .arg("-s")
+        .arg("sine_normalize.wav")
+        .arg("-m")
+        .arg("lkfs");
+
+    cmd5.assert()
+        .success()
+        .stdout("Unknown normalization lkfs, expected peak, true-peak, rms or lufs\n");
+
+    fs::remove_file("sine_normalize.wav").ok();
+    fs::remove_file("sine_normalized.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_loudness() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    // Тон 1 кГц -20 dBFS в одном канале соответствует стереотону -23 dBFS из EBU Tech 3341
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("1000")
+        .arg("-a")
+        .arg("0.1")
+        .arg("-d")
+        .arg("5")
+        .arg("-o")
+        .arg("sine_loudness.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("loudness")
+        .arg("-s")
+        .arg("sine_loudness.wav")
+        .arg("-t")
+        .arg("-23")
+        .arg("-o")
+        .arg("loudness.csv");
+
+    cmd2.assert().code(0).stdout(
+        r#"Integrated loudness: -23.0 LUFS
+Loudness range: 0.0 LU
+Max momentary loudness: -23.0 LUFS
+Max short-term loudness: -23.0 LUFS
+True peak: -20.0 dBTP
+Integrated loudness -23.0 LUFS meets target -23.0 ±0.5 LU
+True peak -20.0 dBTP is within -1.0 dBTP
+Save loudness series to loudness.csv
+"#,
+    );
+
+    // Ряды каждые 100 мс, кратковременная громкость появляется через 3 с
+    let csv = fs::read_to_string("loudness.csv")?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 48);
+    assert_eq!(lines[0], "time,momentary,short_term");
+    assert!(lines[26].starts_with("2.9,") && lines[26].ends_with(','));
+    assert!(lines[27].starts_with("3.0,") && !lines[27].ends_with(','));
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("1000")
+        .arg("-o")
+        .arg("sine_loudness.wav");
+
+    cmd3.assert().success();
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("loudness")
+        .arg("-s")
+        .arg("sine_loudness.wav")
+        .arg("-t")
+        .arg("-23")
+        .arg("--tolerance")
+        .arg("1");
+
+    cmd4.assert().code(1).stdout(predicates::str::ends_with(
+        r#"Integrated loudness -3.0 LUFS misses target -23.0 ±1.0 LU
+True peak 0.0 dBTP exceeds -1.0 dBTP
+"#,
+    ));
+
+    // Громкость в допуске, но истинный пик выше потолка
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("loudness")
+        .arg("-s")
+        .arg("sine_loudness.wav")
+        .arg("-t")
+        .arg("-3");
+
+    cmd5.assert().code(1).stdout(predicates::str::ends_with(
+        r#"Integrated loudness -3.0 LUFS meets target -3.0 ±0.5 LU
+True peak 0.0 dBTP exceeds -1.0 dBTP
+"#,
+    ));
+
+    // Без цели проверки нет, код возврата нулевой
+    let mut cmd6 = Command::cargo_bin("minidsp")?;
+    cmd6.arg("loudness").arg("-s").arg("sine_loudness.wav");
+
+    cmd6.assert()
+        .code(0)
+        .stdout(predicates::str::ends_with("True peak: 0.0 dBTP\n"));
+
+    fs::remove_file("sine_loudness.wav").ok();
+    fs::remove_file("loudness.csv").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dyn() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("440")
+        .arg("-d")
+        .arg("2")
+        .arg("-o")
+        .arg("sine_dyn.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("100")
+        .arg("-d")
+        .arg("2")
+        .arg("-a")
+        .arg("0.1")
+        .arg("-o")
+        .arg("quiet_dyn.wav");
+
+    cmd2.assert().success();
+
+    let peak = |fname: &str| -> Result<f32, Box<dyn std::error::Error>> {
+        let mut reader = hound::WavReader::open(fname)?;
+        let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+        Ok(samples[10000..].iter().fold(0.0, |m, v| m.max(v.abs())))
+    };
+
+    // Синус 0 dBFS с RMS-детектором: уровень -3 дБ, сжатие (1/4 - 1) * 17 дБ
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("dyn")
+        .arg("compress")
         .arg("-s")
-        .arg("sine1.wav")
+        .arg("sine_dyn.wav")
+        .arg("-d")
+        .arg("rms")
+        .arg("-k")
+        .arg("0")
+        .arg("-m")
+        .arg("6")
+        .arg("-o")
+        .arg("sine_dyn_out.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout(predicates::str::ends_with("to 6.0 dB\n"));
+    let level = 20.0 * peak("sine_dyn_out.wav")?.log10();
+    assert!((level + 0.75 * (20.0 - 3.01) - 6.0).abs() < 0.1, "{level}");
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("dyn")
+        .arg("limit")
+        .arg("-s")
+        .arg("sine_dyn.wav")
+        .arg("-t")
+        .arg("-6")
+        .arg("-o")
+        .arg("sine_dyn_out.wav");
+
+    cmd4.assert()
+        .success()
+        .stdout(predicates::str::starts_with("Gain from -6.0 dB"));
+    let level = 20.0 * peak("sine_dyn_out.wav")?.log10();
+    assert!(level <= -6.0 + 1e-5 && level > -6.1, "{level}");
+
+    // Тихий тон приглушается громким управляющим сигналом
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("dyn")
+        .arg("compress")
+        .arg("-s")
+        .arg("quiet_dyn.wav")
+        .arg("-c")
+        .arg("sine_dyn.wav")
+        .arg("-k")
+        .arg("0")
+        .arg("-o")
+        .arg("quiet_dyn_out.wav");
+
+    cmd5.assert().success();
+    assert!(peak("quiet_dyn_out.wav")? < 0.1 * 10f32.powf(-6.0 / 20.0));
+
+    let mut cmd6 = Command::cargo_bin("minidsp")?;
+    cmd6.arg("dyn")
+        .arg("gate")
+        .arg("-s")
+        .arg("quiet_dyn.wav")
+        .arg("-t")
+        .arg("-10")
+        .arg("-o")
+        .arg("quiet_dyn_out.wav");
+
+    cmd6.assert()
+        .success()
+        .stdout(predicates::str::starts_with("Gain from -80.0 dB"));
+    assert!(peak("quiet_dyn_out.wav")? < 1e-4);
+
+    let mut cmd7 = Command::cargo_bin("minidsp")?;
+    cmd7.arg("dyn")
+        .arg("expand")
+        .arg("-s")
+        .arg("quiet_dyn.wav")
+        .arg("-d")
+        .arg("lufs");
+
+    cmd7.assert()
+        .success()
+        .stdout("Unknown detector lufs, expected peak or rms\n");
+
+    fs::remove_file("sine_dyn.wav").ok();
+    fs::remove_file("quiet_dyn.wav").ok();
+    fs::remove_file("sine_dyn_out.wav").ok();
+    fs::remove_file("quiet_dyn_out.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_dyn_agc() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("200")
+        .arg("-d")
+        .arg("3")
         .arg("-a")
+        .arg("0.01")
+        .arg("-o")
+        .arg("quiet_agc.wav");
+
+    cmd.assert().success();
+
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("dyn")
+        .arg("agc")
+        .arg("-s")
+        .arg("quiet_agc.wav")
+        .arg("-b")
+        .arg("1000")
+        .arg("-o")
+        .arg("quiet_agc_out.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout(predicates::str::starts_with("Process 30000 samples"));
+
+    // Тон -40 dBFS поднимается к цели -20 dBFS
+    let mut reader = hound::WavReader::open("quiet_agc_out.wav")?;
+    let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+    assert_eq!(samples.len(), 30000);
+    let peak = samples[20000..].iter().fold(0.0f32, |m, v| m.max(v.abs()));
+    assert!((20.0 * peak.log10() + 20.0).abs() < 1.0, "{peak}");
+
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("dyn").arg("agc").arg("-s").arg("missing_agc.wav");
+
+    cmd3.assert()
+        .success()
+        .stdout("Fail to read missing_agc.wav: No such file or directory (os error 2)\n");
+
+    fs::remove_file("quiet_agc.wav").ok();
+    fs::remove_file("quiet_agc_out.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_effect() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("500")
+        .arg("-o")
+        .arg("sine_effect.wav");
+
+    cmd.assert().success();
+
+    let samples = |fname: &str| -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let mut reader = hound::WavReader::open(fname)?;
+        Ok(reader.samples::<f32>().flatten().collect())
+    };
+
+    // Прямая гребёнка с задержкой в полпериода 500 Гц гасит тон
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("effect")
+        .arg("comb")
+        .arg("-s")
+        .arg("sine_effect.wav")
+        .arg("-d")
+        .arg("1")
+        .arg("-g")
+        .arg("1")
+        .arg("-t")
+        .arg("feedforward")
+        .arg("-o")
+        .arg("sine_effect_out.wav");
+
+    cmd2.assert()
+        .success()
+        .stdout("Save 10000 samples to sine_effect_out.wav\n");
+    let result = samples("sine_effect_out.wav")?;
+    assert!(result[10..].iter().all(|v| v.abs() < 1e-5));
+
+    // Одинаковые параметры дают одинаковый результат
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("effect")
+        .arg("chorus")
+        .arg("-s")
+        .arg("sine_effect.wav")
+        .arg("-i")
+        .arg("lagrange")
+        .arg("-w")
+        .arg("triangle")
+        .arg("-o")
+        .arg("sine_effect_out.wav");
+
+    cmd3.assert().success();
+    let first = samples("sine_effect_out.wav")?;
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("effect")
+        .arg("chorus")
+        .arg("-s")
+        .arg("sine_effect.wav")
+        .arg("-i")
+        .arg("lagrange")
+        .arg("-w")
+        .arg("triangle");
+
+    cmd4.assert().success();
+    assert_eq!(first, samples("effect_signal.wav")?);
+
+    let mut cmd5 = Command::cargo_bin("minidsp")?;
+    cmd5.arg("effect")
+        .arg("flanger")
+        .arg("-s")
+        .arg("sine_effect.wav")
+        .arg("-f")
+        .arg("1");
+
+    cmd5.assert()
+        .success()
+        .stdout("Error in effect: Feedback must be in (-1, 1), got 1\n");
+
+    let mut cmd6 = Command::cargo_bin("minidsp")?;
+    cmd6.arg("effect")
+        .arg("phaser")
+        .arg("-s")
+        .arg("sine_effect.wav")
+        .arg("-w")
+        .arg("square");
+
+    cmd6.assert()
+        .success()
+        .stdout("Unknown waveform square, expected sine or triangle\n");
+
+    fs::remove_file("sine_effect.wav").ok();
+    fs::remove_file("sine_effect_out.wav").ok();
+    fs::remove_file("effect_signal.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_reverb() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("300")
+        .arg("-d")
+        .arg("0.5")
+        .arg("-o")
+        .arg("sine_reverb.wav");
+
+    cmd.assert().success();
+
+    let samples = |fname: &str| -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let mut reader = hound::WavReader::open(fname)?;
+        Ok(reader.samples::<f32>().flatten().collect())
+    };
+
+    // Хвост реверберации продолжается после конца сигнала
+    for method in ["freeverb", "fdn"] {
+        let mut cmd2 = Command::cargo_bin("minidsp")?;
+        cmd2.arg("reverb")
+            .arg(method)
+            .arg("-s")
+            .arg("sine_reverb.wav")
+            .arg("-t")
+            .arg("0.8")
+            .arg("--tail")
+            .arg("1")
+            .arg("-o")
+            .arg("sine_reverb_out.wav");
+
+        cmd2.assert()
+            .success()
+            .stdout("Save 15000 samples to sine_reverb_out.wav\n");
+        let result = samples("sine_reverb_out.wav")?;
+        let tail = result[5500..6500]
+            .iter()
+            .fold(0.0f32, |m, v| m.max(v.abs()));
+        assert!(tail > 1e-3, "{method} {tail}");
+    }
+
+    // Импульсная характеристика из одного отсчёта 0.5 с предзадержкой 10 мс
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 10000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create("ir_reverb.wav", spec)?;
+    writer.write_sample(0.5f32)?;
+    writer.finalize()?;
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("reverb")
+        .arg("ir")
+        .arg("-s")
+        .arg("sine_reverb.wav")
+        .arg("-i")
+        .arg("ir_reverb.wav")
+        .arg("-p")
         .arg("10")
+        .arg("-m")
+        .arg("1")
         .arg("-o")
-        .arg("sine2.wav");
+        .arg("sine_reverb_out.wav");
 
-    cmd2.assert().success();
+    cmd3.assert()
+        .success()
+        .stdout("Save 5000 samples to sine_reverb_out.wav\n");
+    let signal = samples("sine_reverb.wav")?;
+    let result = samples("sine_reverb_out.wav")?;
+    assert!(result[..100].iter().all(|v| v.abs() < 1e-6));
+    for (y, x) in result[100..].iter().zip(signal.iter()) {
+        assert!((y - 0.5 * x).abs() < 1e-5);
+    }
 
-    let mut reader = hound::WavReader::open("sine2.wav")?;
-    let mut max_val = 0.0;
-    for sample_real in reader.samples::<f32>().flatten() {
-        if sample_real > max_val {
-            max_val = sample_real;
-        }
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("reverb")
+        .arg("freeverb")
+        .arg("-s")
+        .arg("sine_reverb.wav")
+        .arg("-d")
+        .arg("1");
+
+    cmd4.assert()
+        .success()
+        .stdout("Error in reverb: Damping must be in [0, 1), got 1\n");
+
+    fs::remove_file("sine_reverb.wav").ok();
+    fs::remove_file("sine_reverb_out.wav").ok();
+    fs::remove_file("ir_reverb.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_eq() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("1000")
+        .arg("-a")
+        .arg("0.5")
+        .arg("-o")
+        .arg("sine_eq.wav");
+
+    cmd.assert().success();
+
+    let rms = |fname: &str| -> Result<f32, Box<dyn std::error::Error>> {
+        let mut reader = hound::WavReader::open(fname)?;
+        let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+        let power = samples[5000..].iter().map(|v| v * v).sum::<f32>() / 5000.0;
+        Ok(power.sqrt())
+    };
+
+    // Пик +6 дБ на частоте тона и предусилитель -6 дБ оставляют уровень прежним
+    fs::write(
+        "preset_eq.txt",
+        "Preamp: -6 dB\nFilter 1: ON PK Fc 1000 Hz Gain 6 dB Q 1.41\nFilter 2: OFF HP Fc 2000 Hz\n",
+    )?;
+    fs::write(
+        "preset_eq.toml",
+        "preamp = -6.0\n\n[[band]]\ntype = \"peaking\"\nfreq = 1000\ngain = 6\nq = 1.41\n",
+    )?;
+    for preset in ["preset_eq.txt", "preset_eq.toml"] {
+        let mut cmd2 = Command::cargo_bin("minidsp")?;
+        cmd2.arg("eq")
+            .arg("-s")
+            .arg("sine_eq.wav")
+            .arg("-p")
+            .arg(preset)
+            .arg("-o")
+            .arg("sine_eq_out.wav");
+
+        cmd2.assert()
+            .success()
+            .stdout("Apply 1 bands, preamp -6.0 dB\nSave 10000 samples to sine_eq_out.wav\n");
+        let ratio = rms("sine_eq_out.wav")? / rms("sine_eq.wav")?;
+        assert!((ratio - 1.0).abs() < 1e-3, "{preset} {ratio}");
     }
 
-    assert!(
-        (max_val - 10.).abs() < 1e-6,
-        "Amplitede should be around 10, but it's {max_val}"
+    fs::write(
+        "preset_eq.json",
+        r#"{"bands": [{"type": "bell", "freq": 100}]}"#,
+    )?;
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("eq")
+        .arg("-s")
+        .arg("sine_eq.wav")
+        .arg("-p")
+        .arg("preset_eq.json");
+
+    cmd3.assert().success().stdout(predicates::str::starts_with(
+        "Error in equalizer: Band 1: Unknown biquad type bell",
+    ));
+
+    fs::remove_file("sine_eq.wav").ok();
+    fs::remove_file("sine_eq_out.wav").ok();
+    fs::remove_file("preset_eq.txt").ok();
+    fs::remove_file("preset_eq.toml").ok();
+    fs::remove_file("preset_eq.json").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_crossover() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("1000")
+        .arg("-a")
+        .arg("0.5")
+        .arg("-o")
+        .arg("sine_crossover.wav");
+
+    cmd.assert().success();
+
+    let samples = |fname: &str| -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let mut reader = hound::WavReader::open(fname)?;
+        Ok(reader.samples::<f32>().flatten().collect())
+    };
+    let peak = |samples: &[f32]| {
+        samples[2000..8000]
+            .iter()
+            .fold(0.0f32, |m, v| m.max(v.abs()))
+    };
+
+    // Тон 1 кГц попадает в среднюю полосу, сумма полос восстанавливает сигнал
+    let mut cmd2 = Command::cargo_bin("minidsp")?;
+    cmd2.arg("crossover")
+        .arg("-s")
+        .arg("sine_crossover.wav")
+        .arg("-f")
+        .arg("200,3000")
+        .arg("-t")
+        .arg("fir")
+        .arg("-l")
+        .arg("401")
+        .arg("-o")
+        .arg("band_crossover");
+
+    cmd2.assert().success().stdout(
+        "Save band 0-200 Hz to band_crossover_1.wav
+Save band 200-3000 Hz to band_crossover_2.wav
+Save band 3000-5000 Hz to band_crossover_3.wav
+",
     );
+    let input = samples("sine_crossover.wav")?;
+    let bands = [
+        samples("band_crossover_1.wav")?,
+        samples("band_crossover_2.wav")?,
+        samples("band_crossover_3.wav")?,
+    ];
+    assert!(peak(&bands[0]) < 1e-3 && peak(&bands[2]) < 1e-3);
+    for (n, x) in input.iter().enumerate() {
+        let total: f32 = bands.iter().map(|band| band[n]).sum();
+        assert!((total - x).abs() < 1e-5);
+    }
 
-    fs::remove_file("sine1.wav").ok();
-    fs::remove_file("sine2.wav").ok();
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("crossover")
+        .arg("-s")
+        .arg("sine_crossover.wav")
+        .arg("-f")
+        .arg("1000")
+        .arg("-n")
+        .arg("8")
+        .arg("-o")
+        .arg("band_crossover");
+
+    cmd3.assert().success();
+    // На частоте раздела Линквица-Райли обе полосы на -6 дБ
+    for band in ["band_crossover_1.wav", "band_crossover_2.wav"] {
+        let level = 20.0 * (peak(&samples(band)?) / peak(&input)).log10();
+        assert!((level + 6.02).abs() < 0.1, "{band} {level}");
+    }
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("crossover")
+        .arg("-s")
+        .arg("sine_crossover.wav")
+        .arg("-f")
+        .arg("1000")
+        .arg("-t")
+        .arg("fir")
+        .arg("-l")
+        .arg("100");
+
+    cmd4.assert()
+        .success()
+        .stdout("Error in crossover: Length of FIR filters must be odd, got 100\n");
+
+    fs::remove_file("sine_crossover.wav").ok();
+    for i in 1..=3 {
+        fs::remove_file(format!("band_crossover_{i}.wav")).ok();
+    }
 
     Ok(())
 }
 
 #[test]
-fn test_dsp_move_average() -> Result<(), Box<dyn std::error::Error>> {
+fn test_stretch_and_pitch() -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = serial_test_guard();
+    let mut cmd = Command::cargo_bin("minidsp")?;
+    cmd.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("250")
+        .arg("-a")
+        .arg("0.5")
+        .arg("-o")
+        .arg("sine_stretch.wav");
+
+    cmd.assert().success();
+
+    // Частота по переходам через ноль в середине записи
+    let frequency = |fname: &str| -> Result<f64, Box<dyn std::error::Error>> {
+        let mut reader = hound::WavReader::open(fname)?;
+        let rate = reader.spec().sample_rate as f64;
+        let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+        let middle = &samples[1500..samples.len() - 1500];
+        let crossings: Vec<usize> = (1..middle.len())
+            .filter(|&n| middle[n - 1] < 0.0 && middle[n] >= 0.0)
+            .collect();
+        let span = (crossings[crossings.len() - 1] - crossings[0]) as f64;
+        Ok((crossings.len() - 1) as f64 * rate / span)
+    };
+
+    for method in ["vocoder", "wsola"] {
+        let mut cmd2 = Command::cargo_bin("minidsp")?;
+        cmd2.arg("stretch")
+            .arg("-s")
+            .arg("sine_stretch.wav")
+            .arg("-f")
+            .arg("1.5")
+            .arg("-m")
+            .arg(method)
+            .arg("-o")
+            .arg("sine_stretch_out.wav");
+
+        cmd2.assert()
+            .success()
+            .stdout("Save 15000 samples to sine_stretch_out.wav\n");
+        let freq = frequency("sine_stretch_out.wav")?;
+        assert!((freq - 250.0).abs() < 1.0, "{method} {freq}");
+
+        let mut cmd3 = Command::cargo_bin("minidsp")?;
+        cmd3.arg("pitch")
+            .arg("-s")
+            .arg("sine_stretch.wav")
+            .arg("-t")
+            .arg("-12")
+            .arg("-m")
+            .arg(method)
+            .arg("-o")
+            .arg("sine_stretch_out.wav");
+
+        cmd3.assert()
+            .success()
+            .stdout("Save 10000 samples to sine_stretch_out.wav\n");
+        let freq = frequency("sine_stretch_out.wav")?;
+        assert!((freq - 125.0).abs() < 1.0, "{method} {freq}");
+    }
+
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("stretch")
+        .arg("-s")
+        .arg("sine_stretch.wav")
+        .arg("-f")
+        .arg("2")
+        .arg("-n")
+        .arg("10");
+
+    cmd4.assert().success().stdout(
+        "Error in stretching: Frame length must be a multiple of 4 not less than 16, got 10\n",
+    );
+
+    fs::remove_file("sine_stretch.wav").ok();
+    fs::remove_file("sine_stretch_out.wav").ok();
+
+    Ok(())
+}
+
+#[test]
+fn test_shift() -> Result<(), Box<dyn std::error::Error>> {
     let _guard = serial_test_guard();
     let mut cmd = Command::cargo_bin("minidsp")?;
     cmd.arg("gen")
         .arg("noise")
         .arg("-d")
-        .arg("3")
+        .arg("0.5")
         .arg("-o")
-        .arg("noise.wav");
+        .arg("noise_shift.wav");
 
     cmd.assert().success();
 
     let mut cmd2 = Command::cargo_bin("minidsp")?;
-    cmd2.arg("mov-average")
+    cmd2.arg("shift")
         .arg("-s")
-        .arg("noise.wav")
-        .arg("-k")
-        .arg("13")
+        .arg("noise_shift.wav")
+        .arg("-t")
+        .arg("1.2")
         .arg("-o")
-        .arg("noise_meaned.wav");
-
-    cmd2.assert().success();
+        .arg("noise_shifted.wav");
 
-    let mut reader = hound::WavReader::open("noise_meaned.wav")?;
-    let mut sig: Vec<f64> = Vec::new();
+    cmd2.assert()
+        .success()
+        .stdout("Shift by 12.000 samples (1.200 ms)\nSave 5000 samples to noise_shifted.wav\n");
 
-    for sample_real in reader.samples::<f32>().flatten() {
-        if sample_real.is_nan() {
-            panic!("{sample_real}")
+    let mut cmd3 = Command::cargo_bin("minidsp")?;
+    cmd3.arg("delay")
+        .arg("-1")
+        .arg("noise_shift.wav")
+        .arg("-2")
+        .arg("noise_shifted.wav");
+
+    // Дробная оценка по случайному шуму колеблется в третьем знаке
+    let output = cmd3.assert().success().get_output().stdout.clone();
+    let text = String::from_utf8(output)?;
+    let estimate: f64 = text
+        .strip_prefix("Delay of noise_shifted.wav relative to noise_shift.wav: ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .ok_or("unexpected output")?
+        .parse()?;
+    assert!((estimate - 12.0).abs() < 0.01, "{text}");
+
+    // Целое число периодов синуса: циклическое опережение без краевых эффектов
+    let mut cmd4 = Command::cargo_bin("minidsp")?;
+    cmd4.arg("gen")
+        .arg("sine")
+        .arg("-f")
+        .arg("250")
+        .arg("-o")
+        .arg("sine_shift.wav");
+
+    cmd4.assert().success();
+
+    for method in ["sinc", "lagrange", "farrow"] {
+        let mut cmd5 = Command::cargo_bin("minidsp")?;
+        cmd5.arg("shift")
+            .arg("-s")
+            .arg("sine_shift.wav")
+            .arg("-d")
+            .arg("-2.5")
+            .arg("-m")
+            .arg(method)
+            .arg("-f")
+            .arg("circular")
+            .arg("-o")
+            .arg("sine_shifted.wav");
+
+        cmd5.assert().success().stdout(
+            "Shift by -2.500 samples (-0.250 ms)\nSave 10000 samples to sine_shifted.wav\n",
+        );
+
+        let mut reader = hound::WavReader::open("sine_shifted.wav")?;
+        let samples: Vec<f32> = reader.samples::<f32>().flatten().collect();
+        for (n, &y) in samples.iter().enumerate() {
+            let expected = (2.0 * std::f64::consts::PI * 250.0 * (n as f64 + 2.5) / 10000.0).sin();
+            assert!((y as f64 - expected).abs() < 1e-3, "{method} {n}");
         }
-        sig.push(sample_real as f64);
     }
 
-    let n = sig.len() as f64;
-    let mean = sig.iter().sum::<f64>() / n;
-    let variance = sig.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
-    let std = variance.sqrt();
-
-    assert!(
-        (mean - 1.).abs() < 0.5,
-        "Mean should be around 1, but it's {mean}"
-    );
+    let mut cmd6 = Command::cargo_bin("minidsp")?;
+    cmd6.arg("shift")
+        .arg("-s")
+        .arg("sine_shift.wav")
+        .arg("-d")
+        .arg("1")
+        .arg("-m")
+        .arg("cubic");
 
-    assert!(std < 0.5, "Std should be around 1, but it's {std}");
+    cmd6.assert()
+        .success()
+        .stdout("Unknown shift method cubic, expected sinc, lagrange or farrow\n");
 
-    // fs::remove_file("noise.wav").ok();
-    // fs::remove_file("noise_meaned.wav").ok();
+    fs::remove_file("noise_shift.wav").ok();
+    fs::remove_file("noise_shifted.wav").ok();
+    fs::remove_file("sine_shift.wav").ok();
+    fs::remove_file("sine_shifted.wav").ok();
 
     Ok(())
 }